@@ -1,9 +1,9 @@
 use alloc::boxed::Box;
-use elf_rs::{ElfFile, ProgramHeaderEntry, ProgramType};
 use multiboot2::{
     BootLoaderNameTag, CommandLineTag, MaybeDynSized, MemoryArea, MemoryAreaType, MemoryMapTag,
     ModuleTag, SmbiosTag,
 };
+use multiboot2_header::{Firmware, Header, LoadOp, LoadPlan};
 
 /// Loads the first module into memory. Assumes that the module is a ELF file.
 /// The handoff is performed according to the Multiboot2 spec.
@@ -16,24 +16,21 @@ pub fn load_module(mut modules: multiboot::information::ModuleIter) -> ! {
             (elf_mod.end - elf_mod.start) as usize,
         )
     };
-    let elf = elf_rs::Elf32::from_bytes(elf_bytes).expect("Should be valid ELF");
-
     // Check if a header is present.
-    {
-        let (hdr, _) = multiboot2_header::Header::find_header(elf_bytes)
-            .expect("Should have Multiboot2 header");
-        log::info!("Multiboot2 header:\n{hdr:#?}");
-    }
+    let (hdr, _) = Header::find_header(elf_bytes).expect("Should have Multiboot2 header");
+    log::info!("Multiboot2 header:\n{hdr:#?}");
+
+    // The loader assumes that the addresses do not clash with the loader (or
+    // anything else).
+    let memory = [MemoryArea::new(
+        0,
+        u32::MAX.into(),
+        MemoryAreaType::Available,
+    )];
+    let plan = LoadPlan::new(elf_bytes, &hdr, &memory, Firmware::Bios).expect("Should be loadable");
 
     // Map the load segments into memory (at their corresponding link).
-    {
-        let elf = elf_rs::Elf32::from_bytes(elf_bytes).expect("Should be valid ELF");
-        elf.program_header_iter()
-            .filter(|ph| ph.ph_type() == ProgramType::LOAD)
-            .for_each(|ph| {
-                map_memory(ph);
-            });
-    }
+    plan.ops().iter().for_each(|op| map_memory(elf_bytes, op));
 
     // Currently, the MBI is not enriched with "real" information as requested.
     // The goal is not to write a feature-complete bootloader but to test
@@ -72,22 +69,25 @@ pub fn load_module(mut modules: multiboot::information::ModuleIter) -> ! {
         "jmp *%ecx",
         in("eax") multiboot2::MAGIC,
         in("ebx") mbi.as_ptr(),
-        in("ecx") elf.entry_point() as u32,
+        in("ecx") plan.entry().addr() as u32,
         options(noreturn, att_syntax));
     }
 }
 
-/// Blindly copies the LOAD segment content at its desired address in physical
-/// address space. The loader assumes that the addresses to not clash with the
-/// loader (or anything else).
-fn map_memory(ph: ProgramHeaderEntry) {
-    log::debug!("Mapping LOAD segment {ph:#?}");
-    let dest_ptr = ph.vaddr() as *mut u8;
-    let content = ph.content().expect("Should have content");
-    unsafe { core::ptr::copy(content.as_ptr(), dest_ptr, content.len()) };
-    let dest_ptr = unsafe { dest_ptr.add(ph.filesz() as usize) };
-
-    // Zero .bss memory
-    assert!(ph.memsz() >= ph.filesz());
-    unsafe { core::ptr::write_bytes(dest_ptr, 0, (ph.memsz() - ph.filesz()) as usize) };
+/// Blindly executes the load operation at its desired address in physical
+/// address space.
+fn map_memory(elf_bytes: &[u8], op: &LoadOp) {
+    log::debug!("Executing {op:x?}");
+    match op {
+        LoadOp::Copy { src, dest } => {
+            let content = &elf_bytes[src.clone()];
+            let dest_ptr = *dest as usize as *mut u8;
+            unsafe { core::ptr::copy(content.as_ptr(), dest_ptr, content.len()) };
+        }
+        // Zero .bss memory
+        LoadOp::Zero { dest, len } => {
+            let dest_ptr = *dest as usize as *mut u8;
+            unsafe { core::ptr::write_bytes(dest_ptr, 0, *len as usize) };
+        }
+    }
 }
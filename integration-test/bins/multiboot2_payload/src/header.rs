@@ -0,0 +1,29 @@
+//! Multiboot2 header of the payload.
+//!
+//! There is no entry address tag, as the address of `start` is not known at
+//! compile time. The bootloader uses the entry point of the ELF instead.
+
+use multiboot2_header::{
+    ConstBuilder, HeaderTagFlag, HeaderTagISA, MbiTagTypeId, ModuleAlignHeaderTag,
+    multiboot2_header,
+};
+
+/// Requests all tags except tag 13, which GRUB doesn't support.
+const REQUESTS: [MbiTagTypeId; 20] = {
+    let mut requests = [MbiTagTypeId::new(0); 20];
+    let mut i = 0;
+    while i < requests.len() {
+        let typ = i as u32 + 1;
+        requests[i] = MbiTagTypeId::new(if typ < 13 { typ } else { typ + 1 });
+        i += 1;
+    }
+    requests
+};
+
+multiboot2_header! {
+    #[used]
+    #[unsafe(link_section = ".multiboot2_header")]
+    static MULTIBOOT2_HEADER = ConstBuilder::new(HeaderTagISA::I386)
+        .information_request_tag(HeaderTagFlag::Required, &REQUESTS)
+        .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required));
+}
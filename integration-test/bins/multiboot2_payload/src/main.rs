@@ -7,11 +7,11 @@ extern crate alloc;
 extern crate integration_test_util;
 
 core::arch::global_asm!(include_str!("start.S"), options(att_syntax));
-core::arch::global_asm!(include_str!("multiboot2_header.S"));
 
 use integration_test_util::{init_environment, qemu_exit_success};
 use multiboot2::BootInformation;
 
+mod header;
 mod verify;
 
 /// Entry into the Rust code from assembly.
@@ -1,10 +1,23 @@
-//! Module for [`new_boxed`].
+//! Module for [`new_boxed`] and [`try_new_boxed`].
 
-use crate::{ALIGNMENT, Header, MaybeDynSized, increase_to_alignment};
+use crate::{ALIGNMENT, Header, MaybeDynSized};
 use alloc::boxed::Box;
 use core::alloc::Layout;
 use core::ops::Deref;
 use core::ptr;
+use thiserror::Error;
+
+/// Errors that may occur when creating a structure on the heap.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+pub enum NewBoxedError {
+    /// The total size overflows `usize` or can't be represented by the
+    /// [`Header`].
+    #[error("the size of the structure can't be represented")]
+    SizeOverflow,
+    /// The allocation of the given amount of bytes failed.
+    #[error("failed to allocate 0x{0:x} bytes")]
+    AllocationFailed(usize),
+}
 
 /// Creates a new tag implementing [`MaybeDynSized`] on the heap.
 ///
@@ -16,6 +29,8 @@ use core::ptr;
 /// by this function using [`Header::set_size`]. However, it must contain all
 /// other relevant metadata or update it in the `set_size` callback.
 ///
+/// This is the panicking variant of [`try_new_boxed`].
+///
 /// # Requirements
 ///
 /// `T` must uphold the requirements of [`MaybeDynSized`], in particular a
@@ -27,26 +42,50 @@ use core::ptr;
 /// - `additional_bytes_slices`: Array of byte slices that should be included
 ///   without additional padding in-between. You don't need to add the bytes
 ///   for [`Header`], but only additional payload.
+///
+/// # Panics
+/// Panics if [`try_new_boxed`] fails.
 #[must_use]
 pub fn new_boxed<T: MaybeDynSized<Metadata = usize> + ?Sized>(
-    mut header: T::Header,
+    header: T::Header,
     additional_bytes_slices: &[&[u8]],
 ) -> Box<T> {
-    let additional_size = additional_bytes_slices
+    try_new_boxed(header, additional_bytes_slices).unwrap()
+}
+
+/// Fallible variant of [`new_boxed`].
+///
+/// Returns an error instead of panicking if the size of the structure can't be
+/// represented by the [`Header`] or if the allocation fails.
+pub fn try_new_boxed<T: MaybeDynSized<Metadata = usize> + ?Sized>(
+    mut header: T::Header,
+    additional_bytes_slices: &[&[u8]],
+) -> Result<Box<T>, NewBoxedError> {
+    let tag_size = additional_bytes_slices
         .iter()
-        .map(|b| b.len())
-        .sum::<usize>();
+        .try_fold(size_of::<T::Header>(), |acc, b| acc.checked_add(b.len()))
+        .ok_or(NewBoxedError::SizeOverflow)?;
 
-    let tag_size = size_of::<T::Header>() + additional_size;
     header.set_size(tag_size);
+    // The header may truncate the size, e.g., when it stores an `u32`.
+    if header.total_size() != tag_size {
+        return Err(NewBoxedError::SizeOverflow);
+    }
 
     // Allocation size is multiple of alignment.
     // See <https://doc.rust-lang.org/reference/type-layout.html>
-    let alloc_size = increase_to_alignment(tag_size);
-    let layout = Layout::from_size_align(alloc_size, ALIGNMENT).unwrap();
+    let alloc_size = tag_size
+        .checked_add(ALIGNMENT - 1)
+        .ok_or(NewBoxedError::SizeOverflow)?
+        & !(ALIGNMENT - 1);
+    let layout =
+        Layout::from_size_align(alloc_size, ALIGNMENT).map_err(|_| NewBoxedError::SizeOverflow)?;
+    // Zeroed, so that the terminating padding is initialized as well.
     // SAFETY: `layout` matches the requested allocation size and alignment.
-    let heap_ptr = unsafe { alloc::alloc::alloc(layout) };
-    assert!(!heap_ptr.is_null());
+    let heap_ptr = unsafe { alloc::alloc::alloc_zeroed(layout) };
+    if heap_ptr.is_null() {
+        return Err(NewBoxedError::AllocationFailed(alloc_size));
+    }
 
     // write header
     {
@@ -90,20 +129,25 @@ pub fn new_boxed<T: MaybeDynSized<Metadata = usize> + ?Sized>(
         "Allocation should match Rusts expectation"
     );
 
-    reference
+    Ok(reference)
 }
 
 /// Clones a [`MaybeDynSized`] by calling [`new_boxed`].
+///
+/// Only the bytes reported by the [`Header`] are copied. Terminating padding
+/// of the source is not included, so the clone reports the same size.
 #[must_use]
 pub fn clone_dyn<T: MaybeDynSized<Metadata = usize> + ?Sized>(tag: &T) -> Box<T> {
-    new_boxed(tag.header().clone(), &[tag.payload()])
+    let header = tag.header();
+    let payload = &tag.payload()[..header.payload_len()];
+    new_boxed(header.clone(), &[payload])
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Tag;
     use crate::test_utils::{DummyDstTag, DummyTestHeader};
+    use crate::{DynSizedStructure, Tag};
 
     #[test]
     fn test_new_boxed() {
@@ -119,6 +163,34 @@ mod tests {
         assert_eq!(tag.payload(), &[0, 1, 2, 3]);
     }
 
+    #[test]
+    fn test_try_new_boxed_rejects_unrepresentable_size() {
+        /// Header that can only represent sizes up to 255 bytes.
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        #[repr(C, align(8))]
+        struct SmallHeader {
+            typ: u32,
+            size: u8,
+        }
+
+        impl Header for SmallHeader {
+            fn total_size(&self) -> usize {
+                self.size as usize
+            }
+
+            fn set_size(&mut self, total_size: usize) {
+                self.size = total_size as u8;
+            }
+        }
+
+        let header = SmallHeader { typ: 0, size: 0 };
+        let tag = try_new_boxed::<DynSizedStructure<SmallHeader>>(header.clone(), &[&[0; 247]]);
+        assert_eq!(tag.unwrap().header().total_size(), 255);
+
+        let tag = try_new_boxed::<DynSizedStructure<SmallHeader>>(header, &[&[0; 248]]);
+        assert_eq!(tag, Err(NewBoxedError::SizeOverflow));
+    }
+
     #[test]
     fn test_clone_tag() {
         let header = DummyTestHeader::new(DummyDstTag::ID, 0);
@@ -128,4 +200,15 @@ mod tests {
 
         let _cloned = clone_dyn(tag.as_ref());
     }
+
+    #[test]
+    fn test_clone_tag_keeps_reported_size() {
+        let header = DummyTestHeader::new(DummyDstTag::ID, 0);
+        let tag = new_boxed::<DummyDstTag>(header, &[&[0, 1, 2]]);
+        assert_eq!(tag.header().size(), 11);
+
+        let cloned = clone_dyn(tag.as_ref());
+        assert_eq!(cloned.header().size(), 11);
+        assert_eq!(cloned.as_bytes().as_ref(), tag.as_bytes().as_ref());
+    }
 }
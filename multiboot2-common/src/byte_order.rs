@@ -0,0 +1,140 @@
+//! Module for [`ByteOrder`] and [`ByteSwapper`].
+
+/// Byte order of the integer fields of a Multiboot2 structure.
+///
+/// Multiboot2 structures use the byte order of the machine they were created
+/// on. Host tools can use this to parse dumps from a machine with a different
+/// byte order, e.g., little-endian dumps on a big-endian CI host.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ByteOrder {
+    /// Least significant byte first, as on x86.
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+impl ByteOrder {
+    /// The byte order of the target this code is compiled for.
+    pub const NATIVE: Self = if cfg!(target_endian = "big") {
+        Self::Big
+    } else {
+        Self::Little
+    };
+
+    /// Returns whether this is the byte order of the target.
+    #[must_use]
+    pub const fn is_native(self) -> bool {
+        matches!(
+            (self, Self::NATIVE),
+            (Self::Little, Self::Little) | (Self::Big, Self::Big)
+        )
+    }
+
+    /// Reads a `u16` in this byte order.
+    #[must_use]
+    pub const fn read_u16(self, bytes: [u8; 2]) -> u16 {
+        match self {
+            Self::Little => u16::from_le_bytes(bytes),
+            Self::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    /// Reads a `u32` in this byte order.
+    #[must_use]
+    pub const fn read_u32(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            Self::Little => u32::from_le_bytes(bytes),
+            Self::Big => u32::from_be_bytes(bytes),
+        }
+    }
+
+    /// Reads a `u64` in this byte order.
+    #[must_use]
+    pub const fn read_u64(self, bytes: [u8; 8]) -> u64 {
+        match self {
+            Self::Little => u64::from_le_bytes(bytes),
+            Self::Big => u64::from_be_bytes(bytes),
+        }
+    }
+}
+
+/// Cursor over a byte buffer that reverses the byte order of consecutive
+/// integer fields in place.
+///
+/// This converts structures between little and big endian, field by field.
+/// Fields that exceed the end of the buffer are left untouched, so that
+/// malformed structures are rejected by the regular validation afterwards.
+#[derive(Debug)]
+pub struct ByteSwapper<'a> {
+    bytes: &'a mut [u8],
+    offset: usize,
+}
+
+impl<'a> ByteSwapper<'a> {
+    /// Creates a new cursor at the beginning of `bytes`.
+    #[must_use]
+    pub const fn new(bytes: &'a mut [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    /// Reverses the bytes of the next field of `width` bytes.
+    pub fn swap(&mut self, width: usize) -> &mut Self {
+        if let Some(field) = self.bytes.get_mut(self.offset..self.offset + width) {
+            field.reverse();
+        }
+        self.offset += width;
+        self
+    }
+
+    /// Reverses the bytes of the next `count` fields of `width` bytes each.
+    pub fn swap_n(&mut self, width: usize, count: usize) -> &mut Self {
+        for _ in 0..count {
+            self.swap(width);
+        }
+        self
+    }
+
+    /// Skips `len` bytes that consist of single bytes, such as strings.
+    pub const fn skip(&mut self, len: usize) -> &mut Self {
+        self.offset += len;
+        self
+    }
+
+    /// Returns the number of bytes behind the cursor.
+    #[must_use]
+    pub const fn remaining(&self) -> usize {
+        self.bytes.len().saturating_sub(self.offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read() {
+        assert_eq!(ByteOrder::Little.read_u16([1, 2]), 0x0201);
+        assert_eq!(ByteOrder::Big.read_u32([1, 2, 3, 4]), 0x0102_0304);
+        assert_eq!(
+            ByteOrder::Big.read_u64(0x1122_u64.to_be_bytes()),
+            ByteOrder::Little.read_u64(0x1122_u64.to_le_bytes())
+        );
+        assert!(ByteOrder::NATIVE.is_native());
+        assert_eq!(
+            ByteOrder::NATIVE.read_u32(0x1234_5678_u32.to_ne_bytes()),
+            0x1234_5678
+        );
+    }
+
+    #[test]
+    fn swap() {
+        let mut bytes = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let mut swapper = ByteSwapper::new(&mut bytes);
+        swapper.swap(2).skip(1).swap_n(2, 2);
+        assert_eq!(swapper.remaining(), 2);
+        // Exceeds the end and is left untouched.
+        swapper.swap(4);
+        assert_eq!(swapper.remaining(), 0);
+        assert_eq!(bytes, [2, 1, 3, 5, 4, 7, 6, 8, 9]);
+    }
+}
@@ -253,14 +253,18 @@ pub mod test_utils;
 
 #[cfg(feature = "alloc")]
 mod boxed;
+mod byte_order;
 mod bytes_ref;
 mod iter;
+mod no_padding;
 mod tag;
 
 #[cfg(feature = "alloc")]
-pub use boxed::{clone_dyn, new_boxed};
+pub use boxed::{NewBoxedError, clone_dyn, new_boxed, try_new_boxed};
+pub use byte_order::{ByteOrder, ByteSwapper};
 pub use bytes_ref::BytesRef;
 pub use iter::TagIter;
+pub use no_padding::{NoPadding, bytes_of};
 pub use tag::{MaybeDynSized, Tag};
 
 use core::fmt::Debug;
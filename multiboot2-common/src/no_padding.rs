@@ -0,0 +1,65 @@
+//! Module for [`NoPadding`] and [`bytes_of`].
+
+use core::{ptr, slice};
+
+/// Marker trait for types whose values consist only of initialized bytes,
+/// i.e., types without any (implicit) padding.
+///
+/// This enables [`bytes_of`] to safely view a value as raw bytes, for example
+/// to pass fields of a tag to [`new_boxed`].
+///
+/// # Safety
+/// Implementors must guarantee that every byte of every value of the type is
+/// initialized. This is the case for integers and arrays or slices of such
+/// types. It is also the case for `#[repr(C)]` or `#[repr(transparent)]`
+/// structs without padding whose fields all implement this trait.
+///
+/// [`new_boxed`]: crate::new_boxed
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` may contain padding bytes",
+    note = "implement `NoPadding` for `{Self}` if it has no padding bytes or use `#[tag(no_constructor)]`"
+)]
+pub unsafe trait NoPadding {}
+
+macro_rules! impl_no_padding {
+    ($($ty:ty),+) => {
+        $(
+            // SAFETY: Primitive integers have no padding.
+            unsafe impl NoPadding for $ty {}
+        )+
+    };
+}
+
+impl_no_padding!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+// SAFETY: Arrays have no padding between their elements.
+unsafe impl<T: NoPadding, const N: usize> NoPadding for [T; N] {}
+
+// SAFETY: Slices have no padding between their elements.
+unsafe impl<T: NoPadding> NoPadding for [T] {}
+
+/// Returns the raw bytes of a value without padding, e.g., a sized field of a
+/// tag or its dynamically sized portion.
+#[must_use]
+pub const fn bytes_of<T: NoPadding + ?Sized>(value: &T) -> &[u8] {
+    let ptr = ptr::from_ref(value).cast::<u8>();
+    // SAFETY: `ptr` points to `size_of_val(value)` bytes that are all
+    // initialized, as guaranteed by `NoPadding`.
+    unsafe { slice::from_raw_parts(ptr, size_of_val(value)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_of() {
+        assert_eq!(bytes_of(&0x1234_u16), 0x1234_u16.to_ne_bytes());
+        assert_eq!(bytes_of(&[1_u8, 2, 3]), [1, 2, 3]);
+        let slice: &[u32] = &[1, 2];
+        assert_eq!(bytes_of(slice).len(), 8);
+        assert_eq!(bytes_of::<[u64]>(&[]), []);
+    }
+}
@@ -0,0 +1,632 @@
+//! Derive macro [`Multiboot2Tag`] for custom Multiboot2 boot information tags
+//! and header tags.
+//!
+//! Don't use this crate directly. Instead, enable the `derive` feature of the
+//! `multiboot2` or `multiboot2-header` crate, which re-export the macro.
+//!
+//! ## MSRV
+//!
+//! The MSRV is 1.85.1 stable.
+
+// --- BEGIN STYLE CHECKS ---
+#![deny(
+    clippy::all,
+    clippy::cargo,
+    clippy::nursery,
+    clippy::must_use_candidate,
+    // clippy::restriction,
+    // clippy::pedantic
+)]
+// now allow a few rules which are denied by the above statement
+// --> They are either ridiculous, not necessary, or we can't fix them.
+#![allow(clippy::multiple_crate_versions)]
+#![deny(missing_docs)]
+#![deny(missing_debug_implementations)]
+#![deny(rustdoc::all)]
+// --- END STYLE CHECKS ---
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    Data, DeriveInput, Error, Field, Fields, Ident, LitInt, LitStr, Path, Type, parse_macro_input,
+    parse_quote,
+};
+
+/// Implements `MaybeDynSized`, `Tag`, and, for dynamically sized tags,
+/// `Pointee` for a custom tag and generates a constructor.
+///
+/// The first field must be the `TagHeader` of a boot information tag or the
+/// `HeaderTagHeader` of a header tag. All further fields follow the header
+/// without any implicit padding, which is checked at compile time. The last
+/// field may be a slice (`[u8]` or `[T]`), making the tag a dynamically sized
+/// type (DST). The struct must be `#[repr(C)]`; the header already enforces
+/// the alignment of 8 bytes.
+///
+/// The base size of the tag is the sum of the sizes of all fields except the
+/// slice. The amount of elements in the slice is derived from the size in the
+/// header.
+///
+/// # Attributes
+///
+/// - `#[tag(id = 0x1337)]`: The type ID of the tag. It is required and must
+///   not be an ID specified by Multiboot2, i.e., it must be greater than 21
+///   for boot information tags and greater than 10 for header tags.
+/// - `#[tag(no_constructor)]`: Doesn't generate the constructor.
+/// - `#[tag(crate = "path")]`: Path to the `multiboot2` or
+///   `multiboot2-header` crate, if it isn't available under its usual name.
+///
+/// # Constructor
+///
+/// Unless `no_constructor` is given, a `new` function with the same
+/// visibility as the struct is generated. It takes all fields except the
+/// header in their order. Header tags take the `HeaderTagFlag` as first
+/// parameter. Sized tags are returned by value. Dynamically sized tags take
+/// the slice as reference and are returned as `Box`; this constructor is only
+/// available with the `builder` feature of the corresponding crate. To copy
+/// the fields into the tag, all field types must implement `NoPadding`.
+///
+/// # Example
+///
+/// ```rust
+/// use multiboot2::{MaybeDynSized, Multiboot2Tag, TagHeader};
+///
+/// #[derive(Multiboot2Tag)]
+/// #[tag(id = 0x1337)]
+/// #[repr(C, align(8))]
+/// struct CustomTag {
+///     header: TagHeader,
+///     version: u32,
+///     name: [u8],
+/// }
+///
+/// let tag = CustomTag::new(7, b"name\0");
+/// assert_eq!(CustomTag::BASE_SIZE, 12);
+/// assert_eq!(tag.header().size, 17);
+/// assert_eq!(tag.version, 7);
+/// assert_eq!(&tag.name, b"name\0");
+/// ```
+///
+/// Header tags work the same way:
+///
+/// ```rust
+/// use multiboot2_header::{HeaderTagFlag, HeaderTagHeader, MaybeDynSized, Multiboot2Tag};
+///
+/// #[derive(Multiboot2Tag)]
+/// #[tag(id = 0x1337)]
+/// #[repr(C, align(8))]
+/// struct VendorHeaderTag {
+///     header: HeaderTagHeader,
+///     value: u32,
+/// }
+///
+/// let tag = VendorHeaderTag::new(HeaderTagFlag::Optional, 42);
+/// assert_eq!(tag.header().size(), 12);
+/// assert!(tag.header().flags().is_optional());
+/// ```
+#[proc_macro_derive(Multiboot2Tag, attributes(tag))]
+pub fn derive_multiboot2_tag(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The kind of a tag, determined by the type of its header.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum TagKind {
+    /// Boot information tag with a `TagHeader`.
+    BootInformation,
+    /// Header tag with a `HeaderTagHeader`.
+    Header,
+}
+
+impl TagKind {
+    /// Determines the kind from the type of the header field.
+    fn from_header_type(ty: &Type) -> Option<Self> {
+        let Type::Path(path) = ty else {
+            return None;
+        };
+        if path.qself.is_some() {
+            return None;
+        }
+        match path.path.segments.last()?.ident.to_string().as_str() {
+            "TagHeader" => Some(Self::BootInformation),
+            "HeaderTagHeader" => Some(Self::Header),
+            _ => None,
+        }
+    }
+
+    /// Returns the path of the crate that defines the header.
+    fn default_crate(self) -> Path {
+        match self {
+            Self::BootInformation => parse_quote!(::multiboot2),
+            Self::Header => parse_quote!(::multiboot2_header),
+        }
+    }
+}
+
+/// The content of the `#[tag(...)]` attributes.
+struct TagAttributes {
+    id: LitInt,
+    krate: Option<Path>,
+    no_constructor: bool,
+}
+
+impl TagAttributes {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut id = None;
+        let mut krate = None;
+        let mut no_constructor = false;
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("tag"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("id") {
+                    id = Some(meta.value()?.parse::<LitInt>()?);
+                } else if meta.path.is_ident("crate") {
+                    krate = Some(meta.value()?.parse::<LitStr>()?.parse::<Path>()?);
+                } else if meta.path.is_ident("no_constructor") {
+                    no_constructor = true;
+                } else {
+                    return Err(meta.error(
+                        "unknown tag attribute, expected `id`, `crate`, or `no_constructor`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        let id = id.ok_or_else(|| {
+            Error::new_spanned(&input.ident, "missing `#[tag(id = ...)]` attribute")
+        })?;
+        Ok(Self {
+            id,
+            krate,
+            no_constructor,
+        })
+    }
+}
+
+/// Ensures that the struct is `#[repr(C)]` and not packed.
+fn check_repr(input: &DeriveInput) -> syn::Result<()> {
+    let mut repr_c = false;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                repr_c = true;
+            } else if meta.path.is_ident("packed") {
+                return Err(meta.error("packed tags are not supported"));
+            }
+            // Skip arguments, such as in `align(8)`.
+            if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                content.parse::<TokenStream>()?;
+            }
+            Ok(())
+        })?;
+    }
+    if repr_c {
+        Ok(())
+    } else {
+        Err(Error::new_spanned(
+            &input.ident,
+            "tags must be `#[repr(C, align(8))]`",
+        ))
+    }
+}
+
+/// Validates the tag ID and returns it as literal of the ID type.
+fn tag_id(kind: TagKind, id: &LitInt) -> syn::Result<TokenStream> {
+    match kind {
+        TagKind::BootInformation => {
+            let value = id.base10_parse::<u32>()?;
+            if value <= 21 {
+                return Err(Error::new_spanned(
+                    id,
+                    "custom boot information tags need an ID greater than 21",
+                ));
+            }
+            Ok(quote!(#value))
+        }
+        TagKind::Header => {
+            let value = id.base10_parse::<u16>()?;
+            if value <= 10 {
+                return Err(Error::new_spanned(
+                    id,
+                    "custom header tags need an ID greater than 10",
+                ));
+            }
+            Ok(quote!(#value))
+        }
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "generic tags are not supported",
+        ));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(name, "only structs can be tags"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            &data.fields,
+            "tags must have named fields",
+        ));
+    };
+    let attributes = TagAttributes::parse(input)?;
+    check_repr(input)?;
+
+    let fields = fields.named.iter().collect::<Vec<_>>();
+    let Some((header, fields)) = fields.split_first() else {
+        return Err(Error::new_spanned(name, "tags must start with a header"));
+    };
+    let kind = TagKind::from_header_type(&header.ty).ok_or_else(|| {
+        Error::new_spanned(
+            &header.ty,
+            "the first field must be a `TagHeader` or a `HeaderTagHeader`",
+        )
+    })?;
+    let (sized, dst) = match fields.split_last() {
+        Some((last, init)) => match &last.ty {
+            Type::Slice(slice) => (init, Some((*last, &*slice.elem))),
+            _ => (fields, None),
+        },
+        None => (fields, None),
+    };
+    let id = tag_id(kind, &attributes.id)?;
+    let krate = attributes.krate.unwrap_or_else(|| kind.default_crate());
+    let p = quote!(#krate::__private);
+
+    let header_ident = header.ident.as_ref().expect("named field");
+    let header_ty = match kind {
+        TagKind::BootInformation => quote!(#p::TagHeader),
+        TagKind::Header => quote!(#p::HeaderTagHeader),
+    };
+    let (id_ty, id_value) = match kind {
+        TagKind::BootInformation => (quote!(#p::TagType), quote!(#p::TagType::Custom(#id))),
+        TagKind::Header => (
+            quote!(#p::HeaderTagTypeId),
+            quote!(#p::HeaderTagTypeId::new(#id)),
+        ),
+    };
+
+    let layout_checks = layout_checks(name, header, sized, dst);
+    let sized_tys = sized.iter().map(|field| &field.ty);
+    let pointee = dst.map(|_| {
+        quote! {
+            unsafe impl #p::Pointee for #name {
+                type Metadata = usize;
+            }
+        }
+    });
+    let dst_len = dst.map(|(_, elem)| {
+        quote! {
+            fn dst_len(header: &#header_ty) -> usize {
+                let size = #p::Header::total_size(header);
+                ::core::assert!(
+                    size >= <Self as #p::MaybeDynSized>::BASE_SIZE,
+                    "tag is smaller than its base size"
+                );
+                (size - <Self as #p::MaybeDynSized>::BASE_SIZE) / ::core::mem::size_of::<#elem>()
+            }
+        }
+    });
+    let constructor = if attributes.no_constructor {
+        None
+    } else {
+        Some(constructor(&p, kind, input, header_ident, sized, dst)?)
+    };
+
+    Ok(quote! {
+        const _: () = {
+            // The first field must be the header of the tag.
+            const _: fn(&#name) -> &#header_ty = |tag| &tag.#header_ident;
+            #layout_checks
+
+            #pointee
+
+            impl #p::MaybeDynSized for #name {
+                type Header = #header_ty;
+
+                const BASE_SIZE: usize =
+                    ::core::mem::size_of::<#header_ty>() #(+ ::core::mem::size_of::<#sized_tys>())*;
+
+                #dst_len
+            }
+
+            impl #p::Tag for #name {
+                type IDType = #id_ty;
+
+                const ID: #id_ty = #id_value;
+            }
+        };
+
+        #constructor
+    })
+}
+
+/// Generates compile-time checks that no field is preceded by implicit
+/// padding. Otherwise, the base size would not match the layout.
+fn layout_checks(
+    name: &Ident,
+    header: &Field,
+    sized: &[&Field],
+    dst: Option<(&Field, &Type)>,
+) -> TokenStream {
+    let header_ty = &header.ty;
+    let mut preceding = Vec::new();
+    let mut checks = Vec::new();
+    let fields = sized.iter().map(|field| (*field, &field.ty)).chain(dst);
+    for (field, ty) in fields {
+        let message = format!(
+            "field `{}` of `{name}` is preceded by implicit padding",
+            field.ident.as_ref().expect("named field")
+        );
+        checks.push(quote! {
+            ::core::assert!(
+                (::core::mem::size_of::<#header_ty>() #(+ ::core::mem::size_of::<#preceding>())*)
+                    % ::core::mem::align_of::<#ty>()
+                    == 0,
+                #message
+            );
+        });
+        preceding.push(&field.ty);
+    }
+    if let Some((field, elem)) = dst {
+        let message = format!(
+            "the elements of field `{}` of `{name}` must not be zero-sized",
+            field.ident.as_ref().expect("named field")
+        );
+        checks.push(quote! {
+            ::core::assert!(::core::mem::size_of::<#elem>() > 0, #message);
+        });
+    }
+    quote! {
+        const _: () = {
+            #(#checks)*
+        };
+    }
+}
+
+/// Generates the `new` constructor.
+fn constructor(
+    p: &TokenStream,
+    kind: TagKind,
+    input: &DeriveInput,
+    header_ident: &Ident,
+    sized: &[&Field],
+    dst: Option<(&Field, &Type)>,
+) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let doc = format!("Creates a new [`{name}`].");
+    let idents = sized
+        .iter()
+        .map(|field| field.ident.as_ref().expect("named field"))
+        .collect::<Vec<_>>();
+    let tys = sized.iter().map(|field| &field.ty);
+
+    let flags = match kind {
+        TagKind::BootInformation => None,
+        TagKind::Header => {
+            if let Some(field) = sized
+                .iter()
+                .chain(dst.iter().map(|(field, _)| field))
+                .find(|field| field.ident.as_ref().is_some_and(|ident| ident == "flags"))
+            {
+                return Err(Error::new_spanned(
+                    field,
+                    "`flags` is the first parameter of the constructor of header tags; rename the field or use `#[tag(no_constructor)]`",
+                ));
+            }
+            Some(quote!(flags: #p::HeaderTagFlag,))
+        }
+    };
+    let header = |size: TokenStream| match kind {
+        TagKind::BootInformation => {
+            quote!(#p::TagHeader::new(<Self as #p::Tag>::ID, #size))
+        }
+        TagKind::Header => quote! {
+            #p::HeaderTagHeader::new_raw(
+                <Self as #p::Tag>::ID,
+                #p::HeaderTagFlagId::new(flags as u16),
+                #size,
+            )
+        },
+    };
+
+    let Some((dst, elem)) = dst else {
+        let header = header(quote!(<Self as #p::MaybeDynSized>::BASE_SIZE as u32));
+        return Ok(quote! {
+            impl #name {
+                #[doc = #doc]
+                #[must_use]
+                #vis fn new(#flags #(#idents: #tys),*) -> Self {
+                    Self {
+                        #header_ident: #header,
+                        #(#idents),*
+                    }
+                }
+            }
+        });
+    };
+
+    let dst_ident = dst.ident.as_ref().expect("named field");
+    let header = header(quote!(0));
+    let bytes = sized
+        .iter()
+        .map(|field| {
+            let ident = field.ident.as_ref().expect("named field");
+            quote_spanned!(field.ty.span()=> #p::bytes_of(&#ident))
+        })
+        .chain(core::iter::once(
+            quote_spanned!(elem.span()=> #p::bytes_of(#dst_ident)),
+        ));
+    Ok(quote! {
+        #p::builder_only! {
+            impl #name {
+                #[doc = #doc]
+                #[must_use]
+                #vis fn new(#flags #(#idents: #tys,)* #dst_ident: &[#elem]) -> #p::Box<Self> {
+                    #p::new_boxed(#header, &[#(#bytes),*])
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_err(input: DeriveInput) -> String {
+        expand(&input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn expands_sized_and_dst_tags() {
+        let sized = expand(&parse_quote! {
+            #[tag(id = 0x1337)]
+            #[repr(C, align(8))]
+            struct Tag {
+                header: TagHeader,
+                a: u32,
+                b: u32,
+            }
+        })
+        .unwrap()
+        .to_string();
+        assert!(sized.contains(":: multiboot2 :: __private :: TagType :: Custom (4919u32)"));
+        assert!(!sized.contains("Pointee"));
+        assert!(!sized.contains("builder_only"));
+
+        let dst = expand(&parse_quote! {
+            #[tag(id = 0x1337, crate = "crate")]
+            #[repr(C, align(8))]
+            struct Tag {
+                header: multiboot2_header::HeaderTagHeader,
+                a: u32,
+                name: [u8],
+            }
+        })
+        .unwrap()
+        .to_string();
+        assert!(dst.contains("crate :: __private :: HeaderTagTypeId :: new (4919u16)"));
+        assert!(dst.contains("Pointee"));
+        assert!(dst.contains("builder_only"));
+
+        let no_constructor = expand(&parse_quote! {
+            #[tag(id = 0x1337, no_constructor)]
+            #[repr(C)]
+            struct Tag {
+                header: TagHeader,
+                data: [u64],
+            }
+        })
+        .unwrap()
+        .to_string();
+        assert!(!no_constructor.contains("fn new"));
+    }
+
+    #[test]
+    fn rejects_invalid_tags() {
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[repr(C)]
+                struct Tag { header: TagHeader }
+            }),
+            "missing `#[tag(id = ...)]` attribute"
+        );
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[tag(id = 21)]
+                #[repr(C)]
+                struct Tag { header: TagHeader }
+            }),
+            "custom boot information tags need an ID greater than 21"
+        );
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[tag(id = 10)]
+                #[repr(C)]
+                struct Tag { header: HeaderTagHeader }
+            }),
+            "custom header tags need an ID greater than 10"
+        );
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[tag(id = 0x10000)]
+                #[repr(C)]
+                struct Tag { header: HeaderTagHeader }
+            }),
+            "number too large to fit in target type"
+        );
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[tag(id = 0x1337, foo)]
+                #[repr(C)]
+                struct Tag { header: TagHeader }
+            }),
+            "unknown tag attribute, expected `id`, `crate`, or `no_constructor`"
+        );
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[tag(id = 0x1337)]
+                struct Tag { header: TagHeader }
+            }),
+            "tags must be `#[repr(C, align(8))]`"
+        );
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[tag(id = 0x1337)]
+                #[repr(C, packed)]
+                struct Tag { header: TagHeader }
+            }),
+            "packed tags are not supported"
+        );
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[tag(id = 0x1337)]
+                #[repr(C)]
+                struct Tag { a: u32, header: TagHeader }
+            }),
+            "the first field must be a `TagHeader` or a `HeaderTagHeader`"
+        );
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[tag(id = 0x1337)]
+                #[repr(C)]
+                struct Tag(TagHeader);
+            }),
+            "tags must have named fields"
+        );
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[tag(id = 0x1337)]
+                #[repr(C)]
+                struct Tag<T> { header: TagHeader, a: T }
+            }),
+            "generic tags are not supported"
+        );
+        assert!(
+            expand_err(parse_quote! {
+                #[tag(id = 0x1337)]
+                #[repr(C)]
+                struct Tag { header: HeaderTagHeader, flags: u32 }
+            })
+            .starts_with("`flags` is the first parameter")
+        );
+    }
+}
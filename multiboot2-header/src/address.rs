@@ -1,4 +1,4 @@
-use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use crate::{HeaderTagFlag, HeaderTagFlagId, HeaderTagHeader, HeaderTagType, HeaderTagTypeId};
 use multiboot2_common::{MaybeDynSized, Tag};
 
 /// Binary address information for non-ELF images.
@@ -62,15 +62,15 @@ impl AddressHeaderTag {
         }
     }
 
-    /// Returns the [`HeaderTagType`].
+    /// Returns the [`HeaderTagTypeId`].
     #[must_use]
-    pub const fn typ(&self) -> HeaderTagType {
+    pub const fn typ(&self) -> HeaderTagTypeId {
         self.header.typ()
     }
 
-    /// Returns the [`HeaderTagFlag`]s.
+    /// Returns the [`HeaderTagFlagId`].
     #[must_use]
-    pub const fn flags(&self) -> HeaderTagFlag {
+    pub const fn flags(&self) -> HeaderTagFlagId {
         self.header.flags()
     }
 
@@ -116,6 +116,44 @@ impl Tag for AddressHeaderTag {
     const ID: HeaderTagType = HeaderTagType::Address;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for AddressHeaderTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("AddressHeaderTag", 5)?;
+        state.serialize_field("flags", &self.flags().get())?;
+        state.serialize_field("header_addr", &self.header_addr)?;
+        state.serialize_field("load_addr", &self.load_addr)?;
+        state.serialize_field("load_end_addr", &self.load_end_addr)?;
+        state.serialize_field("bss_end_addr", &self.bss_end_addr)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AddressHeaderTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "AddressHeaderTag")]
+        struct Fields {
+            flags: u16,
+            header_addr: u32,
+            load_addr: u32,
+            load_end_addr: u32,
+            bss_end_addr: u32,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(
+            crate::UnknownHeaderValue::try_convert(HeaderTagFlagId::new(fields.flags))?,
+            fields.header_addr,
+            fields.load_addr,
+            fields.load_end_addr,
+            fields.bss_end_addr,
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::AddressHeaderTag;
@@ -2,13 +2,28 @@
 
 use crate::{
     AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EndHeaderTag,
-    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag,
-    HeaderTagISA, InformationRequestHeaderTag, ModuleAlignHeaderTag, Multiboot2BasicHeader,
-    RelocatableHeaderTag,
+    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag, Header,
+    HeaderTagHeader, HeaderTagISA, InformationRequestHeaderTag, ModuleAlignHeaderTag,
+    Multiboot2BasicHeader, RelocatableHeaderTag, Violation,
 };
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use multiboot2_common::{DynSizedStructure, MaybeDynSized, new_boxed};
+use thiserror::Error;
+
+/// Error of [`Builder::try_build`] listing every [`Violation`] of the built
+/// header.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Error)]
+#[error("header has {} violation(s), the first is: {}", .0.len(), .0[0])]
+pub struct HeaderBuildError(Vec<Violation>);
+
+impl HeaderBuildError {
+    /// Returns all violations. The list is never empty.
+    #[must_use]
+    pub fn violations(&self) -> &[Violation] {
+        &self.0
+    }
+}
 
 /// Builder for a Multiboot2 header.
 #[derive(Debug)]
@@ -24,7 +39,7 @@ pub struct Builder {
     efi_32_tag: Option<EntryEfi32HeaderTag>,
     efi_64_tag: Option<EntryEfi64HeaderTag>,
     relocatable_tag: Option<RelocatableHeaderTag>,
-    // TODO add support for custom tags once someone requests it.
+    custom_tags: Vec<Box<DynSizedStructure<HeaderTagHeader>>>,
 }
 
 impl Builder {
@@ -43,6 +58,7 @@ impl Builder {
             efi_32_tag: None,
             efi_64_tag: None,
             relocatable_tag: None,
+            custom_tags: alloc::vec![],
         }
     }
 
@@ -119,11 +135,33 @@ impl Builder {
         self
     }
 
+    /// Adds a custom tag, e.g., a vendor-specific tag that only a particular
+    /// bootloader understands.
+    ///
+    /// # Panics
+    /// Panics if the tag has a type known to this crate, see
+    /// [`HeaderTagTypeId::is_known`].
+    ///
+    /// [`HeaderTagTypeId::is_known`]: crate::HeaderTagTypeId::is_known
+    #[must_use]
+    pub fn add_custom_tag(mut self, custom_tag: Box<DynSizedStructure<HeaderTagHeader>>) -> Self {
+        assert!(
+            !custom_tag.header().typ().is_known(),
+            "Only for custom types!"
+        );
+        self.custom_tags.push(custom_tag);
+        self
+    }
+
     /// Returns properly aligned bytes on the heap representing a valid
     /// Multiboot2 header structure.
+    ///
+    /// This doesn't check the semantic rules of the spec, e.g., whether an
+    /// [`AddressHeaderTag`] comes with an [`EntryAddressHeaderTag`]. Use
+    /// [`Self::try_build`] for that.
     #[must_use]
     pub fn build(self) -> Box<DynSizedStructure<Multiboot2BasicHeader>> {
-        let header = Multiboot2BasicHeader::new(self.arch, 0);
+        let header = Multiboot2BasicHeader::new(self.arch.into(), 0);
         let mut byte_refs = Vec::new();
         if let Some(tag) = self.information_request_tag.as_ref() {
             byte_refs.push(tag.as_bytes().as_ref());
@@ -155,11 +193,164 @@ impl Builder {
         if let Some(tag) = self.relocatable_tag.as_ref() {
             byte_refs.push(tag.as_bytes().as_ref());
         }
-        // TODO add support for custom tags once someone requests it.
+        for tag in &self.custom_tags {
+            byte_refs.push(tag.as_bytes().as_ref());
+        }
         let end_tag = EndHeaderTag::new();
         byte_refs.push(end_tag.as_bytes().as_ref());
         new_boxed(header, byte_refs.as_slice())
     }
+
+    /// Like [`Self::build`] but checks the semantic rules of the spec that
+    /// don't depend on the kernel image, such as:
+    /// - the `min_addr` of the [`RelocatableHeaderTag`] is not above its
+    ///   `max_addr` and its `align` is a power of two,
+    /// - an [`AddressHeaderTag`] comes with an [`EntryAddressHeaderTag`],
+    /// - EFI entry tags come with an [`EfiBootServiceHeaderTag`].
+    ///
+    /// Use [`Header::check`] for the rules that depend on the image.
+    pub fn try_build(
+        self,
+    ) -> Result<Box<DynSizedStructure<Multiboot2BasicHeader>>, HeaderBuildError> {
+        let bytes = self.build();
+        let violations = {
+            // SAFETY: The builder creates a valid header.
+            let header = unsafe { Header::load(bytes.as_bytes().as_ptr().cast()) }
+                .expect("should create a valid header");
+            header.tag_violations().collect::<Vec<_>>()
+        };
+        if violations.is_empty() {
+            Ok(bytes)
+        } else {
+            Err(HeaderBuildError(violations))
+        }
+    }
+}
+
+/// Owned counterpart of the serialized tags of a [`Header`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum DeserializedHeaderTag {
+    InformationRequest(Box<InformationRequestHeaderTag>),
+    Address(AddressHeaderTag),
+    EntryAddress(EntryAddressHeaderTag),
+    ConsoleFlags(ConsoleHeaderTag),
+    Framebuffer(FramebufferHeaderTag),
+    ModuleAlign(ModuleAlignHeaderTag),
+    EfiBs(EfiBootServiceHeaderTag),
+    EntryAddressEfi32(EntryEfi32HeaderTag),
+    EntryAddressEfi64(EntryEfi64HeaderTag),
+    Relocatable(RelocatableHeaderTag),
+    Custom {
+        id: u16,
+        flags: u16,
+        payload: Vec<u8>,
+    },
+}
+
+/// Deserializes a builder from the format of the [`serde::Serialize`]
+/// implementation of [`Header`]. `length` and `checksum` are computed when
+/// building.
+///
+/// Each known tag type must occur at most once. Custom tags must have a type
+/// that isn't known to this crate.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Builder {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use crate::{HeaderTagFlagId, HeaderTagISAId, HeaderTagType, HeaderTagTypeId};
+        use serde::Deserialize;
+        use serde::de::Error;
+
+        #[derive(Deserialize)]
+        #[serde(rename = "Header")]
+        struct Fields {
+            arch: u32,
+            tags: Vec<DeserializedHeaderTag>,
+        }
+
+        /// Sets the tag unless it is already present.
+        fn set<T, E: Error>(slot: &mut Option<T>, tag: T, typ: HeaderTagType) -> Result<(), E> {
+            match slot.replace(tag) {
+                Some(_) => Err(E::custom(format_args!("duplicate {typ:?} tag"))),
+                None => Ok(()),
+            }
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let mut builder = Self::new(crate::UnknownHeaderValue::try_convert(
+            HeaderTagISAId::new(fields.arch),
+        )?);
+        for tag in fields.tags {
+            match tag {
+                DeserializedHeaderTag::InformationRequest(tag) => set(
+                    &mut builder.information_request_tag,
+                    tag,
+                    HeaderTagType::InformationRequest,
+                )?,
+                DeserializedHeaderTag::Address(tag) => {
+                    set(&mut builder.address_tag, tag, HeaderTagType::Address)?;
+                }
+                DeserializedHeaderTag::EntryAddress(tag) => {
+                    set(&mut builder.entry_tag, tag, HeaderTagType::EntryAddress)?;
+                }
+                DeserializedHeaderTag::ConsoleFlags(tag) => {
+                    set(&mut builder.console_tag, tag, HeaderTagType::ConsoleFlags)?;
+                }
+                DeserializedHeaderTag::Framebuffer(tag) => {
+                    set(
+                        &mut builder.framebuffer_tag,
+                        tag,
+                        HeaderTagType::Framebuffer,
+                    )?;
+                }
+                DeserializedHeaderTag::ModuleAlign(tag) => {
+                    set(
+                        &mut builder.module_align_tag,
+                        tag,
+                        HeaderTagType::ModuleAlign,
+                    )?;
+                }
+                DeserializedHeaderTag::EfiBs(tag) => {
+                    set(&mut builder.efi_bs_tag, tag, HeaderTagType::EfiBS)?;
+                }
+                DeserializedHeaderTag::EntryAddressEfi32(tag) => {
+                    set(
+                        &mut builder.efi_32_tag,
+                        tag,
+                        HeaderTagType::EntryAddressEFI32,
+                    )?;
+                }
+                DeserializedHeaderTag::EntryAddressEfi64(tag) => {
+                    set(
+                        &mut builder.efi_64_tag,
+                        tag,
+                        HeaderTagType::EntryAddressEFI64,
+                    )?;
+                }
+                DeserializedHeaderTag::Relocatable(tag) => {
+                    set(
+                        &mut builder.relocatable_tag,
+                        tag,
+                        HeaderTagType::Relocatable,
+                    )?;
+                }
+                DeserializedHeaderTag::Custom { id, flags, payload } => {
+                    let typ = HeaderTagTypeId::new(id);
+                    if typ.is_known() {
+                        return Err(D::Error::custom(format_args!(
+                            "custom tag type {id} is a known tag type"
+                        )));
+                    }
+                    let header = HeaderTagHeader::new_raw(typ, HeaderTagFlagId::new(flags), 0);
+                    let tag = multiboot2_common::try_new_boxed(header, &[&payload])
+                        .map_err(D::Error::custom)?;
+                    builder.custom_tags.push(tag);
+                }
+            }
+        }
+        Ok(builder)
+    }
 }
 
 #[cfg(test)]
@@ -168,7 +359,8 @@ mod tests {
     use crate::ConsoleHeaderTagFlags::ConsoleRequired;
     use crate::HeaderTagFlag::{Optional, Required};
     use crate::RelocatableHeaderTagPreference::High;
-    use crate::{Header, MbiTagType};
+    use crate::{HeaderTagType, HeaderTagTypeId, MbiTagType};
+    use multiboot2_common::Tag;
 
     #[test]
     fn build_and_parse() {
@@ -245,4 +437,218 @@ mod tests {
         dbg!(header.entry_address_efi64_tag());
         dbg!(header.relocatable_tag());
     }
+
+    #[test]
+    fn build_and_parse_custom_tag() {
+        let custom_tag = new_boxed::<DynSizedStructure<HeaderTagHeader>>(
+            HeaderTagHeader::new_raw(0x1337.into(), Optional.into(), 0),
+            &[&[1, 2, 3, 4]],
+        );
+        let structure = Builder::new(HeaderTagISA::I386)
+            .module_align_tag(ModuleAlignHeaderTag::new(Required))
+            .add_custom_tag(custom_tag)
+            .build();
+        // SAFETY: The builder emits a fully formed, aligned header buffer
+        // with a valid end tag.
+        let header =
+            unsafe { Header::load(structure.as_bytes().as_ref().as_ptr().cast()) }.unwrap();
+
+        let tag = header.unknown_tags().next().unwrap();
+        assert_eq!(tag.header().typ(), HeaderTagTypeId::new(0x1337));
+        assert_eq!(tag.payload(), [1, 2, 3, 4]);
+
+        #[repr(C, align(8))]
+        struct VendorTag {
+            header: HeaderTagHeader,
+            value: u32,
+        }
+
+        impl MaybeDynSized for VendorTag {
+            type Header = HeaderTagHeader;
+            const BASE_SIZE: usize = size_of::<HeaderTagHeader>() + size_of::<u32>();
+        }
+
+        impl Tag for VendorTag {
+            type IDType = HeaderTagTypeId;
+            const ID: HeaderTagTypeId = HeaderTagTypeId::new(0x1337);
+        }
+
+        let tag = header.get_tag::<VendorTag>().unwrap();
+        assert_eq!(tag.header.size(), 12);
+        assert_eq!(tag.value, u32::from_ne_bytes([1, 2, 3, 4]));
+        assert!(header.get_tag::<ModuleAlignHeaderTag>().is_some());
+        assert!(format!("{header:?}").contains("HeaderTagTypeId(0x1337)"));
+    }
+
+    #[test]
+    #[should_panic = "Only for custom types!"]
+    fn add_custom_tag_rejects_known_type() {
+        let tag = new_boxed::<DynSizedStructure<HeaderTagHeader>>(
+            HeaderTagHeader::new(crate::HeaderTagType::ModuleAlign, Required, 0),
+            &[],
+        );
+        let _builder = Builder::new(HeaderTagISA::I386).add_custom_tag(tag);
+    }
+
+    #[test]
+    fn try_build_lists_all_violations() {
+        let err = Builder::new(HeaderTagISA::I386)
+            .address_tag(AddressHeaderTag::new(Required, 0x100000, 0x100000, 0, 0))
+            .efi_32_tag(EntryEfi32HeaderTag::new(Required, 0x100000))
+            .relocatable_tag(RelocatableHeaderTag::new(
+                Required, 0x20000, 0x10000, 0x1001, High,
+            ))
+            .try_build()
+            .unwrap_err();
+        assert_eq!(
+            err.violations(),
+            [
+                Violation::EfiEntryWithoutBootServices(HeaderTagType::EntryAddressEFI32),
+                Violation::AddressWithoutEntry,
+                Violation::RelocatableMinAboveMax {
+                    min_addr: 0x20000,
+                    max_addr: 0x10000
+                },
+                Violation::RelocatableAlignNotPowerOfTwo(0x1001),
+            ]
+        );
+
+        let bytes = Builder::new(HeaderTagISA::I386)
+            .address_tag(AddressHeaderTag::new(Required, 0x100000, 0x100000, 0, 0))
+            .entry_tag(EntryAddressHeaderTag::new(Required, 0x100000))
+            .efi_bs_tag(EfiBootServiceHeaderTag::new(Optional))
+            .efi_32_tag(EntryEfi32HeaderTag::new(Required, 0x100000))
+            .relocatable_tag(RelocatableHeaderTag::new(
+                Required, 0x10000, 0x20000, 0x1000, High,
+            ))
+            .try_build()
+            .unwrap();
+        // SAFETY: The builder creates a valid header.
+        let header = unsafe { Header::load(bytes.as_bytes().as_ptr().cast()) }.unwrap();
+        assert!(header.relocatable_tag().is_some());
+    }
+
+    /// Serializes the built header, deserializes it as builder, and checks
+    /// that the result serializes the same. Returns the JSON.
+    #[cfg(feature = "serde")]
+    fn serde_roundtrip(builder: Builder) -> alloc::string::String {
+        let structure = builder.build();
+        // SAFETY: The builder emits a fully formed, aligned header buffer
+        // with a valid end tag.
+        let header =
+            unsafe { Header::load(structure.as_bytes().as_ref().as_ptr().cast()) }.unwrap();
+        let json = serde_json::to_string(&header).unwrap();
+        let structure = serde_json::from_str::<Builder>(&json).unwrap().build();
+        // SAFETY: The builder emits a fully formed, aligned header buffer
+        // with a valid end tag.
+        let roundtrip =
+            unsafe { Header::load(structure.as_bytes().as_ref().as_ptr().cast()) }.unwrap();
+        assert_eq!(serde_json::to_string(&roundtrip).unwrap(), json);
+        json
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_roundtrip_all_tags() {
+        let builder = Builder::new(HeaderTagISA::MIPS32)
+            .information_request_tag(InformationRequestHeaderTag::new(
+                Optional,
+                &[
+                    MbiTagType::Cmdline.into(),
+                    MbiTagType::Custom(0x1337).into(),
+                ],
+            ))
+            .address_tag(AddressHeaderTag::new(
+                Required, 0x1000, 0x2000, 0x3000, 0x4000,
+            ))
+            .entry_tag(EntryAddressHeaderTag::new(Required, 0x5000))
+            .console_tag(ConsoleHeaderTag::new(Required, ConsoleRequired))
+            .framebuffer_tag(FramebufferHeaderTag::new(Optional, 720, 1024, 8))
+            .module_align_tag(ModuleAlignHeaderTag::new(Required))
+            .efi_bs_tag(EfiBootServiceHeaderTag::new(Optional))
+            .efi_32_tag(EntryEfi32HeaderTag::new(Required, 0x7000))
+            .efi_64_tag(EntryEfi64HeaderTag::new(Required, 0x8000))
+            .relocatable_tag(RelocatableHeaderTag::new(
+                Required, 0x9000, 0x10000, 4096, High,
+            ))
+            .add_custom_tag(new_boxed::<DynSizedStructure<HeaderTagHeader>>(
+                HeaderTagHeader::new_raw(0x1337.into(), Optional.into(), 0),
+                &[&[1, 2, 3, 4]],
+            ));
+
+        let json = serde_roundtrip(builder);
+        assert!(json.starts_with(r#"{"arch":4,"length":"#));
+        assert!(json.contains(r#"{"type":"information_request","flags":1,"requests":[1,4919]}"#));
+        assert!(json.contains(r#"{"type":"custom","id":4919,"flags":1,"payload":[1,2,3,4]}"#));
+        assert!(!json.contains(r#""type":"end""#));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_errors() {
+        let duplicate = r#"{"arch":0,"tags":[
+            {"type":"module_align","flags":0},
+            {"type":"module_align","flags":1}
+        ]}"#;
+        let error = serde_json::from_str::<Builder>(duplicate).unwrap_err();
+        assert!(format!("{error}").contains("duplicate ModuleAlign tag"));
+
+        let arch = r#"{"arch":1,"tags":[]}"#;
+        assert!(serde_json::from_str::<Builder>(arch).is_err());
+
+        let flags = r#"{"arch":0,"tags":[{"type":"module_align","flags":2}]}"#;
+        assert!(serde_json::from_str::<Builder>(flags).is_err());
+
+        let known_custom = r#"{"arch":0,"tags":[{"type":"custom","id":6,"flags":0,"payload":[]}]}"#;
+        assert!(serde_json::from_str::<Builder>(known_custom).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    fn derive_custom_tags() {
+        use crate::Multiboot2Tag;
+        use multiboot2_common::clone_dyn;
+
+        #[derive(Debug, PartialEq, Eq, Multiboot2Tag)]
+        #[tag(id = 0x1337, crate = "crate")]
+        #[repr(C, align(8))]
+        struct SizedTag {
+            header: HeaderTagHeader,
+            value: u32,
+        }
+
+        #[derive(Multiboot2Tag)]
+        #[tag(id = 0x1338, crate = "crate")]
+        #[repr(C, align(8))]
+        struct DstTag {
+            header: HeaderTagHeader,
+            name: [u8],
+        }
+
+        assert_eq!(SizedTag::BASE_SIZE, 12);
+        assert_eq!(DstTag::BASE_SIZE, 8);
+
+        let sized = SizedTag::new(Optional, 42);
+        assert_eq!(sized.header.typ(), HeaderTagTypeId::new(0x1337));
+        assert!(sized.header.flags().is_optional());
+        assert_eq!(sized.header.size(), 12);
+        let dst = DstTag::new(Required, b"vendor");
+        assert_eq!(dst.header.typ(), HeaderTagTypeId::new(0x1338));
+        assert!(!dst.header.flags().is_optional());
+        assert_eq!(dst.header.size(), 14);
+
+        let boxed = |bytes: &[u8]| {
+            clone_dyn(DynSizedStructure::<HeaderTagHeader>::ref_from_slice(bytes).unwrap())
+        };
+        let structure = Builder::new(HeaderTagISA::I386)
+            .add_custom_tag(boxed(&sized.as_bytes()))
+            .add_custom_tag(boxed(&dst.as_bytes()))
+            .build();
+        // SAFETY: The builder emits a fully formed, aligned header buffer
+        // with a valid end tag.
+        let header =
+            unsafe { Header::load(structure.as_bytes().as_ref().as_ptr().cast()) }.unwrap();
+        assert_eq!(header.get_tag::<SizedTag>(), Some(&sized));
+        assert_eq!(&header.get_tag::<DstTag>().unwrap().name, b"vendor");
+    }
 }
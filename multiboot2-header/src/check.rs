@@ -0,0 +1,504 @@
+//! Module for [`Report`].
+
+use crate::{
+    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EndHeaderTag,
+    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag,
+    HEADER_SEARCH_LIMIT, Header, HeaderTagType, HeaderTagTypeId, InformationRequestHeaderTag,
+    MaybeDynSized, MbiTagTypeId, ModuleAlignHeaderTag, RelocatableHeaderTag,
+};
+use elf::ElfBytes;
+use elf::abi::PT_LOAD;
+use elf::endian::AnyEndian;
+use thiserror::Error;
+
+/// A violation of the semantic rules of the spec found by [`Header::check`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+pub enum Violation {
+    /// The header is not fully within the image or the first
+    /// [`HEADER_SEARCH_LIMIT`] bytes of it.
+    #[error("header is not fully within the first 0x{HEADER_SEARCH_LIMIT:x} bytes of the image")]
+    OutsideSearchLimit,
+    /// The fields of the [`AddressHeaderTag`] are not ordered, i.e., not
+    /// `load_addr <= header_addr` and `load_addr <= load_end_addr <=
+    /// bss_end_addr`, or the loaded range exceeds the image.
+    ///
+    /// [`AddressHeaderTag`]: crate::AddressHeaderTag
+    #[error("fields of the address tag are not ordered")]
+    AddressTagUnordered,
+    /// The address of the [`EntryAddressHeaderTag`] is not within the range
+    /// loaded according to the [`AddressHeaderTag`] or, without an address
+    /// tag, not within the physical range of a `PT_LOAD` segment of the ELF
+    /// file.
+    ///
+    /// [`AddressHeaderTag`]: crate::AddressHeaderTag
+    /// [`EntryAddressHeaderTag`]: crate::EntryAddressHeaderTag
+    #[error("entry address 0x{0:x} is outside the loaded range")]
+    EntryOutsideLoadedRange(u32),
+    /// An EFI entry tag is present without an [`EfiBootServiceHeaderTag`].
+    ///
+    /// [`EfiBootServiceHeaderTag`]: crate::EfiBootServiceHeaderTag
+    #[error("EFI entry tag {0:?} without EFI boot services tag")]
+    EfiEntryWithoutBootServices(HeaderTagType),
+    /// An [`AddressHeaderTag`] is present without an
+    /// [`EntryAddressHeaderTag`].
+    ///
+    /// [`AddressHeaderTag`]: crate::AddressHeaderTag
+    /// [`EntryAddressHeaderTag`]: crate::EntryAddressHeaderTag
+    #[error("address tag without entry address tag")]
+    AddressWithoutEntry,
+    /// The `min_addr` of the [`RelocatableHeaderTag`] is above its
+    /// `max_addr`.
+    ///
+    /// [`RelocatableHeaderTag`]: crate::RelocatableHeaderTag
+    #[error("relocatable tag has min_addr 0x{min_addr:x} above max_addr 0x{max_addr:x}")]
+    RelocatableMinAboveMax {
+        /// The `min_addr` field.
+        min_addr: u32,
+        /// The `max_addr` field.
+        max_addr: u32,
+    },
+    /// The `align` of the [`RelocatableHeaderTag`] is not a power of two.
+    ///
+    /// [`RelocatableHeaderTag`]: crate::RelocatableHeaderTag
+    #[error("relocatable tag alignment 0x{0:x} is not a power of two")]
+    RelocatableAlignNotPowerOfTwo(u32),
+    /// A tag of the given type is present more than once.
+    #[error("duplicate tag {0:?}")]
+    DuplicateTag(HeaderTagTypeId),
+    /// A tag that is not optional is not supported by the bootloader, e.g.,
+    /// because it is unknown.
+    #[error("unsupported required tag {0:?}")]
+    UnsupportedRequiredTag(HeaderTagTypeId),
+    /// A tag of a type known to this crate has a size that doesn't match its
+    /// layout. The checks that depend on the fields of such a tag are
+    /// skipped.
+    #[error("tag {typ:?} has invalid size {size}")]
+    InvalidTagSize {
+        /// The type of the tag.
+        typ: HeaderTagType,
+        /// The `size` field of the tag.
+        size: u32,
+    },
+}
+
+/// Result of [`Header::check`].
+///
+/// The violations are computed lazily, so creating and evaluating a report
+/// needs no allocation.
+#[derive(Debug)]
+pub struct Report<'a> {
+    header: &'a Header<'a>,
+    header_offset: usize,
+    image: &'a [u8],
+    supported: &'a [HeaderTagTypeId],
+}
+
+impl<'a> Report<'a> {
+    pub(crate) const fn new(
+        header: &'a Header<'a>,
+        header_offset: usize,
+        image: &'a [u8],
+        supported: &'a [HeaderTagTypeId],
+    ) -> Self {
+        Self {
+            header,
+            header_offset,
+            image,
+            supported,
+        }
+    }
+
+    /// Returns whether the header has no violations, i.e., whether a
+    /// bootloader may boot the image.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.violations().next().is_none()
+    }
+
+    /// Returns all violations.
+    pub fn violations(&self) -> impl Iterator<Item = Violation> + '_ {
+        let image = [
+            self.check_search_limit(),
+            self.check_address_tag(),
+            self.check_entry(),
+        ];
+        let unsupported = self
+            .header
+            .iter()
+            .filter(|tag| !tag.header().flags().is_optional())
+            .map(|tag| tag.header().typ())
+            .filter(|typ| *typ != HeaderTagType::End && !self.supported.contains(typ))
+            .map(Violation::UnsupportedRequiredTag);
+        image
+            .into_iter()
+            .flatten()
+            .chain(self.header.tag_violations())
+            .chain(unsupported)
+    }
+
+    fn check_search_limit(&self) -> Option<Violation> {
+        let end = self.header_offset + self.header.length() as usize;
+        (end > self.image.len().min(HEADER_SEARCH_LIMIT)).then_some(Violation::OutsideSearchLimit)
+    }
+
+    /// Returns the physical range loaded according to the
+    /// [`AddressHeaderTag`], if it is valid.
+    ///
+    /// [`AddressHeaderTag`]: crate::AddressHeaderTag
+    fn loaded_range(&self) -> Option<(u64, u64)> {
+        let tag = self.header.address_tag()?;
+        let header_addr = u64::from(tag.header_addr());
+        let (load_addr, file_start) = match tag.load_addr() {
+            // Special value: load the image from its beginning.
+            u32::MAX => (header_addr.checked_sub(self.header_offset as u64)?, 0),
+            addr => {
+                let addr = u64::from(addr);
+                let delta = header_addr.checked_sub(addr)?;
+                (addr, (self.header_offset as u64).checked_sub(delta)?)
+            }
+        };
+        let file_end = match tag.load_end_addr() {
+            0 => self.image.len() as u64,
+            end => file_start + u64::from(end).checked_sub(load_addr)?,
+        };
+        (file_end <= self.image.len() as u64).then_some(())?;
+        let load_end = load_addr + (file_end - file_start);
+        let bss_end = match tag.bss_end_addr() {
+            0 => load_end,
+            end => u64::from(end),
+        };
+        (load_end <= bss_end).then_some((load_addr, bss_end))
+    }
+
+    fn check_address_tag(&self) -> Option<Violation> {
+        if !self.header.has_valid_tags(HeaderTagType::Address) {
+            return None;
+        }
+        self.header.address_tag()?;
+        self.loaded_range()
+            .is_none()
+            .then_some(Violation::AddressTagUnordered)
+    }
+
+    /// Returns whether `addr` is within the physical range of a `PT_LOAD`
+    /// segment of the image, or `None` if the image is no valid ELF file.
+    fn in_elf_segment(&self, addr: u64) -> Option<bool> {
+        let file = ElfBytes::<AnyEndian>::minimal_parse(self.image).ok()?;
+        let loaded = file.segments()?.iter().any(|phdr| {
+            phdr.p_type == PT_LOAD && phdr.p_paddr <= addr && addr - phdr.p_paddr < phdr.p_memsz
+        });
+        Some(loaded)
+    }
+
+    fn check_entry(&self) -> Option<Violation> {
+        if !self.header.has_valid_tags(HeaderTagType::Address)
+            || !self.header.has_valid_tags(HeaderTagType::EntryAddress)
+        {
+            return None;
+        }
+        let entry = self.header.entry_address_tag()?.entry_addr();
+        let loaded = if self.header.address_tag().is_some() {
+            let (start, end) = self.loaded_range()?;
+            (start..end).contains(&u64::from(entry))
+        } else {
+            // Without an address tag, the ELF file defines the loaded range.
+            self.in_elf_segment(u64::from(entry))?
+        };
+        (!loaded).then_some(Violation::EntryOutsideLoadedRange(entry))
+    }
+}
+
+/// Returns whether `size` matches the layout of the tags of type `typ`.
+const fn is_valid_size(typ: HeaderTagType, size: u32) -> bool {
+    let size = size as usize;
+    let sized = match typ {
+        HeaderTagType::InformationRequest => {
+            let base_size = InformationRequestHeaderTag::BASE_SIZE;
+            return size >= base_size && (size - base_size) % size_of::<MbiTagTypeId>() == 0;
+        }
+        HeaderTagType::End => EndHeaderTag::BASE_SIZE,
+        HeaderTagType::Address => AddressHeaderTag::BASE_SIZE,
+        HeaderTagType::EntryAddress => EntryAddressHeaderTag::BASE_SIZE,
+        HeaderTagType::ConsoleFlags => ConsoleHeaderTag::BASE_SIZE,
+        HeaderTagType::Framebuffer => FramebufferHeaderTag::BASE_SIZE,
+        HeaderTagType::ModuleAlign => ModuleAlignHeaderTag::BASE_SIZE,
+        HeaderTagType::EfiBS => EfiBootServiceHeaderTag::BASE_SIZE,
+        HeaderTagType::EntryAddressEFI32 => EntryEfi32HeaderTag::BASE_SIZE,
+        HeaderTagType::EntryAddressEFI64 => EntryEfi64HeaderTag::BASE_SIZE,
+        HeaderTagType::Relocatable => RelocatableHeaderTag::BASE_SIZE,
+    };
+    size == sized
+}
+
+impl Header<'_> {
+    /// Returns the tags of known types whose size doesn't match their layout.
+    /// Casting them to their typed representation would panic.
+    fn size_violations(&self) -> impl Iterator<Item = Violation> + '_ {
+        self.iter().filter_map(|tag| {
+            let typ = HeaderTagType::try_from(tag.header().typ()).ok()?;
+            let size = tag.header().size();
+            (!is_valid_size(typ, size)).then_some(Violation::InvalidTagSize { typ, size })
+        })
+    }
+
+    /// Returns whether all tags of type `typ` have a valid size, i.e., whether
+    /// the corresponding getter can be used.
+    fn has_valid_tags(&self, typ: HeaderTagType) -> bool {
+        self.iter()
+            .filter(|tag| tag.header().typ() == typ)
+            .all(|tag| is_valid_size(typ, tag.header().size()))
+    }
+
+    /// Returns the violations that only depend on the tags of the header but not
+    /// on the image or the bootloader.
+    pub(crate) fn tag_violations(&self) -> impl Iterator<Item = Violation> + '_ {
+        let has_tag = |typ: HeaderTagType| self.iter().any(|tag| tag.header().typ() == typ);
+        let efi_entry = |typ: HeaderTagType| {
+            (has_tag(typ) && !has_tag(HeaderTagType::EfiBS))
+                .then_some(Violation::EfiEntryWithoutBootServices(typ))
+        };
+        let address_without_entry = (has_tag(HeaderTagType::Address)
+            && !has_tag(HeaderTagType::EntryAddress))
+        .then_some(Violation::AddressWithoutEntry);
+        let relocatable = self
+            .has_valid_tags(HeaderTagType::Relocatable)
+            .then(|| self.relocatable_tag())
+            .flatten();
+        let min_above_max = relocatable
+            .filter(|tag| tag.min_addr() > tag.max_addr())
+            .map(|tag| Violation::RelocatableMinAboveMax {
+                min_addr: tag.min_addr(),
+                max_addr: tag.max_addr(),
+            });
+        let align = relocatable
+            .filter(|tag| !tag.align().is_power_of_two())
+            .map(|tag| Violation::RelocatableAlignNotPowerOfTwo(tag.align()));
+        let fixed = [
+            efi_entry(HeaderTagType::EntryAddressEFI32),
+            efi_entry(HeaderTagType::EntryAddressEFI64),
+            address_without_entry,
+            min_above_max,
+            align,
+        ];
+        // Report each duplicate type once, at its second occurrence.
+        let duplicates = self
+            .iter()
+            .enumerate()
+            .filter(|&(i, tag)| {
+                self.iter()
+                    .take(i)
+                    .filter(|prev| prev.header().typ() == tag.header().typ())
+                    .count()
+                    == 1
+            })
+            .map(|(_, tag)| Violation::DuplicateTag(tag.header().typ()));
+        self.size_violations()
+            .chain(fixed.into_iter().flatten())
+            .chain(duplicates)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "builder")]
+mod tests {
+    use super::*;
+    use crate::test_utils::write_elf;
+    use crate::{
+        AddressHeaderTag, Builder, EntryAddressHeaderTag, EntryEfi64HeaderTag, GenericHeaderTag,
+        HeaderTagFlag, HeaderTagHeader, HeaderTagISA, ModuleAlignHeaderTag,
+    };
+    use alloc::vec::Vec;
+    use multiboot2_common::test_utils::AlignedBytes;
+    use multiboot2_common::{MaybeDynSized, new_boxed};
+
+    const ALL: [HeaderTagTypeId; 4] = [
+        HeaderTagTypeId::new(HeaderTagType::Address as u16),
+        HeaderTagTypeId::new(HeaderTagType::EntryAddress as u16),
+        HeaderTagTypeId::new(HeaderTagType::EntryAddressEFI64 as u16),
+        HeaderTagTypeId::new(HeaderTagType::ModuleAlign as u16),
+    ];
+
+    #[test]
+    fn valid_header() {
+        let bytes = Builder::new(HeaderTagISA::I386)
+            .address_tag(AddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x100000,
+                0x100000,
+                0,
+                0x110000,
+            ))
+            .entry_tag(EntryAddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x100040,
+            ))
+            .build();
+        // SAFETY: The builder creates a valid header.
+        let header = unsafe { Header::load(bytes.as_bytes().as_ptr().cast()) }.unwrap();
+
+        let report = header.check(0, &[0; 0x1000], &ALL);
+        assert_eq!(report.violations().collect::<Vec<_>>(), []);
+        assert!(report.is_ok());
+    }
+
+    #[test]
+    fn invalid_header() {
+        let bytes = Builder::new(HeaderTagISA::I386)
+            .address_tag(AddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x100000,
+                0x100010,
+                0x100100,
+                0x100080,
+            ))
+            .entry_tag(EntryAddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x200000,
+            ))
+            .efi_64_tag(EntryEfi64HeaderTag::new(HeaderTagFlag::Optional, 0x100000))
+            .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required))
+            .add_custom_tag(new_boxed::<GenericHeaderTag>(
+                HeaderTagHeader::new_raw(42.into(), HeaderTagFlag::Required.into(), 0),
+                &[],
+            ))
+            .add_custom_tag(new_boxed::<GenericHeaderTag>(
+                HeaderTagHeader::new_raw(42.into(), HeaderTagFlag::Optional.into(), 0),
+                &[],
+            ))
+            .build();
+        // SAFETY: The builder creates a valid header.
+        let header = unsafe { Header::load(bytes.as_bytes().as_ptr().cast()) }.unwrap();
+
+        let image = [0; HEADER_SEARCH_LIMIT + 0x1000];
+        let report = header.check(HEADER_SEARCH_LIMIT, &image, &ALL);
+        assert_eq!(
+            report.violations().collect::<Vec<_>>(),
+            [
+                Violation::OutsideSearchLimit,
+                Violation::AddressTagUnordered,
+                Violation::EfiEntryWithoutBootServices(HeaderTagType::EntryAddressEFI64),
+                Violation::DuplicateTag(42.into()),
+                Violation::UnsupportedRequiredTag(42.into()),
+            ]
+        );
+        assert!(!report.is_ok());
+    }
+
+    #[test]
+    fn entry_outside_loaded_range() {
+        let bytes = Builder::new(HeaderTagISA::I386)
+            .address_tag(AddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x100000,
+                0x100000,
+                0x100800,
+                0,
+            ))
+            .entry_tag(EntryAddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x100800,
+            ))
+            .build();
+        // SAFETY: The builder creates a valid header.
+        let header = unsafe { Header::load(bytes.as_bytes().as_ptr().cast()) }.unwrap();
+
+        assert_eq!(
+            header
+                .check(0, &[0; 0x1000], &ALL)
+                .violations()
+                .collect::<Vec<_>>(),
+            [Violation::EntryOutsideLoadedRange(0x100800)]
+        );
+        // Not supported by the bootloader
+        assert_eq!(
+            header
+                .check(0, &[0; 0x1000], &[])
+                .violations()
+                .collect::<Vec<_>>(),
+            [
+                Violation::EntryOutsideLoadedRange(0x100800),
+                Violation::UnsupportedRequiredTag(HeaderTagType::Address.into()),
+                Violation::UnsupportedRequiredTag(HeaderTagType::EntryAddress.into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn supported_custom_tag() {
+        let bytes = Builder::new(HeaderTagISA::I386)
+            .add_custom_tag(new_boxed::<GenericHeaderTag>(
+                HeaderTagHeader::new_raw(42.into(), HeaderTagFlag::Required.into(), 0),
+                &[],
+            ))
+            .build();
+        // SAFETY: The builder creates a valid header.
+        let header = unsafe { Header::load(bytes.as_bytes().as_ptr().cast()) }.unwrap();
+
+        assert!(header.check(0, &[0; 0x1000], &[42.into()]).is_ok());
+        assert!(!header.check(0, &[0; 0x1000], &ALL).is_ok());
+    }
+
+    #[test]
+    fn invalid_tag_size() {
+        let built = Builder::new(HeaderTagISA::I386)
+            .entry_tag(EntryAddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x100000,
+            ))
+            .add_custom_tag(new_boxed::<GenericHeaderTag>(
+                HeaderTagHeader::new_raw(42.into(), HeaderTagFlag::Required.into(), 0),
+                &[&[0; 8]],
+            ))
+            .build();
+        let mut bytes = AlignedBytes::new([0; 56]);
+        bytes.0.copy_from_slice(built.as_bytes().as_ref());
+        // Turn the custom tag behind the entry tag into an address tag with
+        // only 16 instead of 24 bytes.
+        bytes.0[32..34].copy_from_slice(&(HeaderTagType::Address as u16).to_ne_bytes());
+        // SAFETY: The buffer is aligned and contains a valid header layout.
+        let header = unsafe { Header::load(bytes.as_ptr().cast()) }.unwrap();
+
+        assert_eq!(
+            header
+                .check(0, &[0; 0x1000], &ALL)
+                .violations()
+                .collect::<Vec<_>>(),
+            [Violation::InvalidTagSize {
+                typ: HeaderTagType::Address,
+                size: 16,
+            }]
+        );
+    }
+
+    #[test]
+    fn entry_outside_elf_segments() {
+        let header = |entry_addr| {
+            Builder::new(HeaderTagISA::I386)
+                .entry_tag(EntryAddressHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    entry_addr,
+                ))
+                .build()
+        };
+        let mut image = [0; 0x200];
+        write_elf(&mut image);
+
+        let bytes = header(0x2001ff);
+        // SAFETY: The builder creates a valid header.
+        let inside = unsafe { Header::load(bytes.as_bytes().as_ptr().cast()) }.unwrap();
+        assert!(inside.check(0x180, &image, &ALL).is_ok());
+
+        let bytes = header(0x200200);
+        // SAFETY: The builder creates a valid header.
+        let outside = unsafe { Header::load(bytes.as_bytes().as_ptr().cast()) }.unwrap();
+        assert_eq!(
+            outside
+                .check(0x180, &image, &ALL)
+                .violations()
+                .collect::<Vec<_>>(),
+            [Violation::EntryOutsideLoadedRange(0x200200)]
+        );
+        // Not an ELF file, so the loaded range is unknown.
+        assert!(outside.check(0x180, &[0; 0x200], &ALL).is_ok());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use crate::{
+    ConsoleHeaderTagFlagsId, HeaderTagFlag, HeaderTagFlagId, HeaderTagHeader, HeaderTagType,
+    HeaderTagTypeId,
+};
 use multiboot2_common::{MaybeDynSized, Tag};
 
 /// Possible flags for [`ConsoleHeaderTag`].
@@ -17,7 +20,7 @@ pub enum ConsoleHeaderTagFlags {
 #[repr(C, align(8))]
 pub struct ConsoleHeaderTag {
     header: HeaderTagHeader,
-    console_flags: ConsoleHeaderTagFlags,
+    console_flags: ConsoleHeaderTagFlagsId,
 }
 
 impl ConsoleHeaderTag {
@@ -28,19 +31,19 @@ impl ConsoleHeaderTag {
             HeaderTagHeader::new(HeaderTagType::ConsoleFlags, flags, Self::BASE_SIZE as u32);
         Self {
             header,
-            console_flags,
+            console_flags: ConsoleHeaderTagFlagsId::new(console_flags as u32),
         }
     }
 
-    /// Returns the [`HeaderTagType`].
+    /// Returns the [`HeaderTagTypeId`].
     #[must_use]
-    pub const fn typ(&self) -> HeaderTagType {
+    pub const fn typ(&self) -> HeaderTagTypeId {
         self.header.typ()
     }
 
-    /// Returns the [`HeaderTagFlag`]s.
+    /// Returns the [`HeaderTagFlagId`].
     #[must_use]
-    pub const fn flags(&self) -> HeaderTagFlag {
+    pub const fn flags(&self) -> HeaderTagFlagId {
         self.header.flags()
     }
 
@@ -50,9 +53,9 @@ impl ConsoleHeaderTag {
         self.header.size()
     }
 
-    /// Returns the [`ConsoleHeaderTagFlags`].
+    /// Returns the [`ConsoleHeaderTagFlagsId`].
     #[must_use]
-    pub const fn console_flags(&self) -> ConsoleHeaderTagFlags {
+    pub const fn console_flags(&self) -> ConsoleHeaderTagFlagsId {
         self.console_flags
     }
 }
@@ -67,3 +70,34 @@ impl Tag for ConsoleHeaderTag {
     type IDType = HeaderTagType;
     const ID: HeaderTagType = HeaderTagType::ConsoleFlags;
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ConsoleHeaderTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("ConsoleHeaderTag", 2)?;
+        state.serialize_field("flags", &self.flags().get())?;
+        state.serialize_field("console_flags", &self.console_flags.get())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ConsoleHeaderTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "ConsoleHeaderTag")]
+        struct Fields {
+            flags: u16,
+            console_flags: u32,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(
+            crate::UnknownHeaderValue::try_convert(HeaderTagFlagId::new(fields.flags))?,
+            crate::UnknownHeaderValue::try_convert(ConsoleHeaderTagFlagsId::new(
+                fields.console_flags,
+            ))?,
+        ))
+    }
+}
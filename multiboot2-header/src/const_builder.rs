@@ -0,0 +1,378 @@
+//! Module for [`ConstBuilder`] and [`ConstHeader`].
+
+use crate::{
+    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EndHeaderTag,
+    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag, Header,
+    HeaderTagFlag, HeaderTagHeader, HeaderTagISA, HeaderTagISAId, HeaderTagType, MbiTagTypeId,
+    ModuleAlignHeaderTag, Multiboot2BasicHeader, RelocatableHeaderTag,
+};
+use core::mem::size_of;
+use multiboot2_common::ALIGNMENT;
+
+/// Maximum size in bytes of a header built by [`ConstBuilder`].
+pub const CONST_BUILDER_CAPACITY: usize = 512;
+
+/// Builder for a Multiboot2 header that works in `const` contexts and without
+/// `alloc`, e.g., for a header in a `static` of a kernel.
+///
+/// Unlike [`Builder`], tags are emitted in the order in which they are added
+/// and there are no checks for duplicate tags. The header is finished with
+/// [`Self::build`], which needs the final size as const generic parameter.
+/// The [`multiboot2_header!`] macro takes care of that.
+///
+/// [`Builder`]: crate::Builder
+/// [`multiboot2_header!`]: crate::multiboot2_header
+#[derive(Clone, Debug)]
+pub struct ConstBuilder {
+    arch: HeaderTagISA,
+    bytes: [u8; CONST_BUILDER_CAPACITY],
+    /// Number of used bytes, including the space for the basic header, which
+    /// is written last.
+    len: usize,
+}
+
+impl ConstBuilder {
+    /// Creates a new builder for the specified architecture.
+    #[must_use]
+    pub const fn new(arch: HeaderTagISA) -> Self {
+        Self {
+            arch,
+            bytes: [0; CONST_BUILDER_CAPACITY],
+            len: size_of::<Multiboot2BasicHeader>(),
+        }
+    }
+
+    /// Returns the size in bytes of the final header, including the end tag.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.len + size_of::<EndHeaderTag>()
+    }
+
+    /// Copies `len` bytes from `ptr` to `offset`.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for reads of `len` initialized bytes.
+    const unsafe fn write_raw(&mut self, offset: usize, ptr: *const u8, len: usize) {
+        assert!(
+            offset + len <= CONST_BUILDER_CAPACITY,
+            "header exceeds CONST_BUILDER_CAPACITY"
+        );
+        let mut i = 0;
+        while i < len {
+            // SAFETY: Guaranteed by the caller.
+            self.bytes[offset + i] = unsafe { *ptr.add(i) };
+            i += 1;
+        }
+    }
+
+    /// Appends `len` bytes from `ptr`.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for reads of `len` initialized bytes.
+    const unsafe fn push_raw(mut self, ptr: *const u8, len: usize) -> Self {
+        // SAFETY: Guaranteed by the caller.
+        unsafe { self.write_raw(self.len, ptr, len) };
+        self.len += len;
+        self
+    }
+
+    /// Appends the first `size` bytes of a tag, i.e., without the trailing
+    /// padding of the Rust type, and pads it to the next [`ALIGNMENT`]
+    /// boundary with zeroes.
+    ///
+    /// # Safety
+    /// The first `size` bytes of the tag must not contain padding.
+    const unsafe fn push_tag<T>(self, tag: &T, size: u32) -> Self {
+        assert!(size as usize <= size_of::<T>());
+        // SAFETY: Guaranteed by the caller.
+        let this = unsafe { self.push_raw((&raw const *tag).cast(), size as usize) };
+        this.pad()
+    }
+
+    /// Pads the bytes to the next [`ALIGNMENT`] boundary with zeroes.
+    const fn pad(mut self) -> Self {
+        self.len = self.len.next_multiple_of(ALIGNMENT);
+        assert!(
+            self.len <= CONST_BUILDER_CAPACITY,
+            "header exceeds CONST_BUILDER_CAPACITY"
+        );
+        self
+    }
+
+    /// Adds an [`InformationRequestHeaderTag`] requesting the given tags.
+    ///
+    /// [`InformationRequestHeaderTag`]: crate::InformationRequestHeaderTag
+    #[must_use]
+    pub const fn information_request_tag(
+        self,
+        flags: HeaderTagFlag,
+        requests: &[MbiTagTypeId],
+    ) -> Self {
+        let size = size_of::<HeaderTagHeader>() + size_of_val(requests);
+        let header = HeaderTagHeader::new(HeaderTagType::InformationRequest, flags, size as u32);
+        // SAFETY: `HeaderTagHeader` has no padding and `MbiTagTypeId` is a
+        // transparent `u32`.
+        unsafe {
+            self.push_raw((&raw const header).cast(), size_of::<HeaderTagHeader>())
+                .push_raw(requests.as_ptr().cast(), size_of_val(requests))
+        }
+        .pad()
+    }
+
+    /// Adds an [`AddressHeaderTag`].
+    #[must_use]
+    pub const fn address_tag(self, tag: AddressHeaderTag) -> Self {
+        // SAFETY: The tag has only trailing padding.
+        unsafe { self.push_tag(&tag, tag.size()) }
+    }
+
+    /// Adds an [`EntryAddressHeaderTag`].
+    #[must_use]
+    pub const fn entry_tag(self, tag: EntryAddressHeaderTag) -> Self {
+        // SAFETY: The tag has only trailing padding.
+        unsafe { self.push_tag(&tag, tag.size()) }
+    }
+
+    /// Adds a [`ConsoleHeaderTag`].
+    #[must_use]
+    pub const fn console_tag(self, tag: ConsoleHeaderTag) -> Self {
+        // SAFETY: The tag has only trailing padding.
+        unsafe { self.push_tag(&tag, tag.size()) }
+    }
+
+    /// Adds a [`FramebufferHeaderTag`].
+    #[must_use]
+    pub const fn framebuffer_tag(self, tag: FramebufferHeaderTag) -> Self {
+        // SAFETY: The tag has only trailing padding.
+        unsafe { self.push_tag(&tag, tag.size()) }
+    }
+
+    /// Adds a [`ModuleAlignHeaderTag`].
+    #[must_use]
+    pub const fn module_align_tag(self, tag: ModuleAlignHeaderTag) -> Self {
+        // SAFETY: The tag has only trailing padding.
+        unsafe { self.push_tag(&tag, tag.size()) }
+    }
+
+    /// Adds an [`EfiBootServiceHeaderTag`].
+    #[must_use]
+    pub const fn efi_bs_tag(self, tag: EfiBootServiceHeaderTag) -> Self {
+        // SAFETY: The tag has only trailing padding.
+        unsafe { self.push_tag(&tag, tag.size()) }
+    }
+
+    /// Adds an [`EntryEfi32HeaderTag`].
+    #[must_use]
+    pub const fn efi_32_tag(self, tag: EntryEfi32HeaderTag) -> Self {
+        // SAFETY: The tag has only trailing padding.
+        unsafe { self.push_tag(&tag, tag.size()) }
+    }
+
+    /// Adds an [`EntryEfi64HeaderTag`].
+    #[must_use]
+    pub const fn efi_64_tag(self, tag: EntryEfi64HeaderTag) -> Self {
+        // SAFETY: The tag has only trailing padding.
+        unsafe { self.push_tag(&tag, tag.size()) }
+    }
+
+    /// Adds a [`RelocatableHeaderTag`].
+    #[must_use]
+    pub const fn relocatable_tag(self, tag: RelocatableHeaderTag) -> Self {
+        // SAFETY: The tag has only trailing padding.
+        unsafe { self.push_tag(&tag, tag.size()) }
+    }
+
+    /// Finishes the header by adding the [`EndHeaderTag`] and the basic header
+    /// with a correct checksum.
+    ///
+    /// # Panics
+    /// Panics if `N` is not [`Self::size`]. In `const` contexts, this is a
+    /// compile-time error.
+    #[must_use]
+    pub const fn build<const N: usize>(self) -> ConstHeader<N> {
+        let end_tag = EndHeaderTag::new();
+        // SAFETY: The tag has no padding.
+        let mut this = unsafe { self.push_tag(&end_tag, end_tag.size()) };
+        assert!(this.len == N, "N must be the size of the header");
+
+        let arch = HeaderTagISAId::new(this.arch as u32);
+        let basic_header = Multiboot2BasicHeader::new(arch, N as u32);
+        // SAFETY: `Multiboot2BasicHeader` has no padding.
+        unsafe {
+            this.write_raw(
+                0,
+                (&raw const basic_header).cast(),
+                size_of::<Multiboot2BasicHeader>(),
+            );
+        }
+
+        let mut bytes = [0; N];
+        let mut i = 0;
+        while i < N {
+            bytes[i] = this.bytes[i];
+            i += 1;
+        }
+        ConstHeader(bytes)
+    }
+}
+
+/// A complete Multiboot2 header built by [`ConstBuilder`].
+///
+/// It is 8-byte aligned and may directly be placed in a `static` in the
+/// section of the Multiboot2 header, see [`multiboot2_header!`].
+///
+/// [`multiboot2_header!`]: crate::multiboot2_header
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(C, align(8))]
+pub struct ConstHeader<const N: usize>([u8; N]);
+
+impl<const N: usize> ConstHeader<N> {
+    /// Returns the raw bytes of the header.
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+
+    /// Parses the header.
+    #[must_use]
+    pub fn header(&self) -> Header<'_> {
+        // SAFETY: The bytes are aligned and the `ConstBuilder` creates a valid
+        // header.
+        unsafe { Header::load(self.0.as_ptr().cast()) }.expect("should be a valid header")
+    }
+}
+
+/// Defines a `static` holding a Multiboot2 header built at compile time by a
+/// [`ConstBuilder`].
+///
+/// The type of the static is [`ConstHeader`] with the size of the header
+/// computed from the builder expression. Attributes, such as `#[used]` and
+/// `#[unsafe(link_section = "...")]`, are forwarded.
+///
+/// # Example
+/// ```rust
+/// use multiboot2_header::{
+///     ConstBuilder, FramebufferHeaderTag, HeaderTagFlag, HeaderTagISA, MbiTagType,
+///     MbiTagTypeId, multiboot2_header,
+/// };
+///
+/// multiboot2_header! {
+///     #[used]
+///     #[unsafe(link_section = ".multiboot2_header")]
+///     static MULTIBOOT2_HEADER = ConstBuilder::new(HeaderTagISA::I386)
+///         .information_request_tag(
+///             HeaderTagFlag::Required,
+///             &[MbiTagTypeId::new(6) /* memory map */],
+///         )
+///         .framebuffer_tag(FramebufferHeaderTag::new(HeaderTagFlag::Optional, 1024, 768, 32));
+/// }
+///
+/// let header = MULTIBOOT2_HEADER.header();
+/// assert_eq!(header.length() as usize, MULTIBOOT2_HEADER.as_bytes().len());
+/// assert_eq!(header.framebuffer_tag().unwrap().width(), 1024);
+/// ```
+#[macro_export]
+macro_rules! multiboot2_header {
+    ($(#[$attr:meta])* $vis:vis static $name:ident = $builder:expr;) => {
+        $(#[$attr])*
+        $vis static $name: $crate::ConstHeader<{ $builder.size() }> = $builder.build();
+    };
+}
+
+#[cfg(test)]
+#[cfg(feature = "builder")]
+mod tests {
+    use super::*;
+    use crate::{
+        Builder, ConsoleHeaderTagFlags, HeaderTagFlag, InformationRequestHeaderTag, MbiTagType,
+    };
+    use multiboot2_common::MaybeDynSized;
+
+    const REQUESTS: [MbiTagTypeId; 3] = [
+        MbiTagTypeId::new(1),
+        MbiTagTypeId::new(6),
+        MbiTagTypeId::new(8),
+    ];
+
+    multiboot2_header! {
+        static HEADER = ConstBuilder::new(HeaderTagISA::I386)
+            .information_request_tag(HeaderTagFlag::Required, &REQUESTS)
+            .address_tag(AddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x100000,
+                0x100000,
+                0,
+                0,
+            ))
+            .entry_tag(EntryAddressHeaderTag::new(HeaderTagFlag::Required, 0x100040))
+            .console_tag(ConsoleHeaderTag::new(
+                HeaderTagFlag::Optional,
+                ConsoleHeaderTagFlags::ConsoleRequired,
+            ))
+            .framebuffer_tag(FramebufferHeaderTag::new(HeaderTagFlag::Optional, 1024, 768, 32))
+            .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required));
+    }
+
+    #[test]
+    fn matches_builder() {
+        let expected = Builder::new(HeaderTagISA::I386)
+            .information_request_tag(InformationRequestHeaderTag::new(
+                HeaderTagFlag::Required,
+                &[
+                    MbiTagType::Cmdline.into(),
+                    MbiTagType::Mmap.into(),
+                    MbiTagType::Framebuffer.into(),
+                ],
+            ))
+            .address_tag(AddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x100000,
+                0x100000,
+                0,
+                0,
+            ))
+            .entry_tag(EntryAddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x100040,
+            ))
+            .console_tag(ConsoleHeaderTag::new(
+                HeaderTagFlag::Optional,
+                ConsoleHeaderTagFlags::ConsoleRequired,
+            ))
+            .framebuffer_tag(FramebufferHeaderTag::new(
+                HeaderTagFlag::Optional,
+                1024,
+                768,
+                32,
+            ))
+            .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required))
+            .build();
+        // SAFETY: The builder creates a valid header.
+        let expected = unsafe { Header::load(expected.as_bytes().as_ptr().cast()) }.unwrap();
+        assert_eq!((&raw const HEADER).align_offset(ALIGNMENT), 0);
+
+        let header = HEADER.header();
+        assert_eq!(header.length(), expected.length());
+        assert_eq!(header.checksum(), expected.checksum());
+        assert_eq!(header.iter().count(), expected.iter().count());
+        // The padding of the builder's tags is not necessarily zeroed.
+        assert!(
+            header
+                .iter()
+                .zip(expected.iter())
+                .all(|(a, b)| a.header() == b.header() && a.payload() == b.payload())
+        );
+        assert!(header.verify_checksum().is_ok());
+        assert_eq!(
+            header.information_request_tag().unwrap().requests(),
+            REQUESTS
+        );
+        assert_eq!(header.framebuffer_tag().unwrap().depth(), 32);
+    }
+
+    #[test]
+    #[should_panic(expected = "N must be the size of the header")]
+    fn build_wrong_size() {
+        let _header: ConstHeader<16> = ConstBuilder::new(HeaderTagISA::I386).build();
+    }
+}
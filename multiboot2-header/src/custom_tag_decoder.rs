@@ -0,0 +1,146 @@
+//! Module for [`CustomHeaderTagDecoder`].
+
+use crate::{GenericHeaderTag, HeaderTagHeader, HeaderTagTypeId};
+use core::fmt::{Debug, Formatter};
+use multiboot2_common::Tag;
+
+/// Function that formats a custom header tag, e.g., by casting it to its
+/// specific type. See [`CustomHeaderTagDecoder`].
+pub type CustomHeaderTagFormatter = fn(&GenericHeaderTag, &mut Formatter<'_>) -> core::fmt::Result;
+
+/// Decoder for custom header tags of a specific type, such as vendor tags.
+///
+/// By default, the [`Debug`] output of a [`Header`] only shows the types of
+/// unknown tags. Pass a set of decoders to [`Header::debug_with`] to fully
+/// show them instead. The first decoder registered for the type of an unknown
+/// tag is used.
+///
+/// # Example
+///
+/// ```no_run
+/// use multiboot2_header::{CustomHeaderTagDecoder, Header, HeaderTagTypeId};
+///
+/// let header = unsafe { Header::load(0xdeadbeef as *const _) }.unwrap();
+/// let decoders = [CustomHeaderTagDecoder::new(HeaderTagTypeId::new(0x1337), |tag, f| {
+///     write!(f, "VendorTag({:x?})", tag.payload())
+/// })];
+/// println!("{:#?}", header.debug_with(&decoders));
+/// ```
+///
+/// [`Header`]: crate::Header
+/// [`Header::debug_with`]: crate::Header::debug_with
+#[derive(Copy, Clone)]
+pub struct CustomHeaderTagDecoder {
+    typ: HeaderTagTypeId,
+    fmt: CustomHeaderTagFormatter,
+}
+
+impl CustomHeaderTagDecoder {
+    /// Creates a decoder that formats tags of type `typ` with `fmt`.
+    #[must_use]
+    pub const fn new(typ: HeaderTagTypeId, fmt: CustomHeaderTagFormatter) -> Self {
+        Self { typ, fmt }
+    }
+
+    /// Creates a decoder that formats tags of type `T::ID` with the [`Debug`]
+    /// implementation of `T`.
+    ///
+    /// # Panics
+    /// Formatting panics if a tag of that type can't be cast to `T`, just
+    /// like [`Header::get_tag`].
+    ///
+    /// [`Header::get_tag`]: crate::Header::get_tag
+    #[must_use]
+    pub fn for_tag<T>() -> Self
+    where
+        T: Tag<Header = HeaderTagHeader> + Debug + ?Sized,
+        T::IDType: Into<HeaderTagTypeId>,
+        T::Metadata: Default,
+    {
+        Self::new(T::ID.into(), |tag, f| Debug::fmt(tag.cast::<T>(), f))
+    }
+
+    /// Returns the type of the tags this decoder formats.
+    #[must_use]
+    pub const fn typ(&self) -> HeaderTagTypeId {
+        self.typ
+    }
+
+    /// Formats `tag` with this decoder.
+    pub fn format(&self, tag: &GenericHeaderTag, f: &mut Formatter<'_>) -> core::fmt::Result {
+        (self.fmt)(tag, f)
+    }
+}
+
+impl Debug for CustomHeaderTagDecoder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CustomHeaderTagDecoder")
+            .field("typ", &self.typ)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{Builder, Header, HeaderTagFlagId, HeaderTagISA};
+    use multiboot2_common::{MaybeDynSized, new_boxed};
+    use std::format;
+
+    #[derive(Debug)]
+    #[repr(C)]
+    struct VendorHeaderTag {
+        header: HeaderTagHeader,
+        version: u32,
+        flags: u32,
+    }
+
+    impl MaybeDynSized for VendorHeaderTag {
+        type Header = HeaderTagHeader;
+
+        const BASE_SIZE: usize = size_of::<Self>();
+    }
+
+    impl Tag for VendorHeaderTag {
+        type IDType = HeaderTagTypeId;
+
+        const ID: HeaderTagTypeId = HeaderTagTypeId::new(0x1337);
+    }
+
+    fn custom_tag(typ: u16, payload: &[u8]) -> alloc::boxed::Box<GenericHeaderTag> {
+        new_boxed::<GenericHeaderTag>(
+            HeaderTagHeader::new_raw(HeaderTagTypeId::new(typ), HeaderTagFlagId::new(1), 0),
+            &[payload],
+        )
+    }
+
+    #[test]
+    fn debug_with() {
+        let mut vendor_payload = [0; 8];
+        vendor_payload[..4].copy_from_slice(&7_u32.to_ne_bytes());
+        vendor_payload[4..].copy_from_slice(&3_u32.to_ne_bytes());
+        let structure = Builder::new(HeaderTagISA::I386)
+            .add_custom_tag(custom_tag(0x1337, &vendor_payload))
+            .add_custom_tag(custom_tag(0x1338, &[1, 2, 3]))
+            .add_custom_tag(custom_tag(0x1339, &[4]))
+            .build();
+        // SAFETY: The builder emits a fully formed, aligned header buffer
+        // with a valid end tag.
+        let header =
+            unsafe { Header::load(structure.as_bytes().as_ref().as_ptr().cast()) }.unwrap();
+
+        let decoders = [
+            CustomHeaderTagDecoder::for_tag::<VendorHeaderTag>(),
+            CustomHeaderTagDecoder::new(HeaderTagTypeId::new(0x1338), |tag, f| {
+                write!(f, "OtherTag({:?})", tag.payload())
+            }),
+        ];
+        let output = format!("{:?}", header.debug_with(&decoders));
+        assert!(output.contains("unknown_tags: [VendorHeaderTag { header: HeaderTagHeader { typ: HeaderTagTypeId(0x1337), flags: Optional, size: 16 }, version: 7, flags: 3 }, OtherTag([1, 2, 3]), HeaderTagTypeId(0x1339)]"), "{output}");
+        assert_eq!(
+            format!("{header:?}"),
+            format!("{:?}", header.debug_with(&[]))
+        );
+        assert!(format!("{header:?}").contains("unknown_tags: [HeaderTagTypeId(0x1337), HeaderTagTypeId(0x1338), HeaderTagTypeId(0x1339)]"));
+    }
+}
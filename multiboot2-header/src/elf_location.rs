@@ -0,0 +1,245 @@
+//! Module for [`ElfHeaderLocation`].
+
+#[cfg(feature = "alloc")]
+use crate::OwnedHeader;
+use crate::{Header, HeaderScratch, LoadError};
+use elf::ElfBytes;
+use elf::abi::PT_LOAD;
+use elf::endian::AnyEndian;
+use thiserror::Error;
+
+/// Errors that may occur in [`Header::find_in_elf`].
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ElfHeaderError {
+    /// The image is not a valid ELF file.
+    #[error("image is not a valid ELF file")]
+    InvalidElf,
+    /// No valid header was found, see [`Header::find_header_unaligned`].
+    #[error("no valid multiboot2 header found")]
+    Header(#[source] LoadError),
+}
+
+/// The `PT_LOAD` segment of an ELF file that contains the header.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ElfHeaderSegment {
+    /// Index of the segment in the program header table.
+    pub index: usize,
+    /// Offset of the segment in the file (`p_offset`).
+    pub offset: u64,
+    /// Virtual address of the segment (`p_vaddr`).
+    pub vaddr: u64,
+    /// Physical address of the segment (`p_paddr`).
+    pub paddr: u64,
+    /// Size of the segment in the file (`p_filesz`).
+    pub filesz: u64,
+    /// Size of the segment in memory (`p_memsz`).
+    pub memsz: u64,
+    offset_in_segment: u64,
+}
+
+impl ElfHeaderSegment {
+    /// Returns the physical address at which the header will be loaded.
+    #[must_use]
+    pub const fn header_paddr(&self) -> u64 {
+        self.paddr.wrapping_add(self.offset_in_segment)
+    }
+
+    /// Returns the virtual address at which the header will be loaded.
+    #[must_use]
+    pub const fn header_vaddr(&self) -> u64 {
+        self.vaddr.wrapping_add(self.offset_in_segment)
+    }
+}
+
+/// Location of a [`Header`] in an ELF file, see [`Header::find_in_elf`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ElfHeaderLocation {
+    file_offset: usize,
+    segment: Option<ElfHeaderSegment>,
+}
+
+impl ElfHeaderLocation {
+    /// Returns the offset of the header in the file.
+    #[must_use]
+    pub const fn file_offset(&self) -> usize {
+        self.file_offset
+    }
+
+    /// Returns the `PT_LOAD` segment that fully contains the header in its
+    /// file bytes, if any.
+    ///
+    /// Without such a segment, the header isn't loaded into memory, so the
+    /// `header_addr` of an [`AddressHeaderTag`] can't refer to it.
+    ///
+    /// [`AddressHeaderTag`]: crate::AddressHeaderTag
+    #[must_use]
+    pub const fn segment(&self) -> Option<&ElfHeaderSegment> {
+        self.segment.as_ref()
+    }
+
+    /// Returns the physical address at which the header will be loaded, if it
+    /// is within a segment.
+    #[must_use]
+    pub fn header_paddr(&self) -> Option<u64> {
+        self.segment.map(|segment| segment.header_paddr())
+    }
+}
+
+/// Returns the location of the header of `length` bytes at `file_offset` in
+/// the parsed ELF file.
+fn locate(file: &ElfBytes<AnyEndian>, file_offset: usize, length: u32) -> ElfHeaderLocation {
+    let start = file_offset as u64;
+    let end = start + u64::from(length);
+
+    let segment = file.segments().and_then(|phdrs| {
+        phdrs
+            .iter()
+            .enumerate()
+            .filter(|(_, phdr)| phdr.p_type == PT_LOAD)
+            .find(|(_, phdr)| {
+                phdr.p_offset <= start
+                    && phdr
+                        .p_offset
+                        .checked_add(phdr.p_filesz)
+                        .is_some_and(|segment_end| end <= segment_end)
+            })
+            .map(|(index, phdr)| ElfHeaderSegment {
+                index,
+                offset: phdr.p_offset,
+                vaddr: phdr.p_vaddr,
+                paddr: phdr.p_paddr,
+                filesz: phdr.p_filesz,
+                memsz: phdr.p_memsz,
+                offset_in_segment: start - phdr.p_offset,
+            })
+    });
+    ElfHeaderLocation {
+        file_offset,
+        segment,
+    }
+}
+
+impl Header<'_> {
+    /// Finds the header in an ELF file and determines the `PT_LOAD` segment
+    /// containing it and the address at which it will be loaded.
+    ///
+    /// The header is searched with [`Header::find_header_unaligned`], so
+    /// `elf` may be unaligned, e.g., a file read into a `Vec<u8>`. The header
+    /// is copied into the `scratch` buffer.
+    pub fn find_in_elf<'b>(
+        elf: &[u8],
+        scratch: &'b mut HeaderScratch,
+    ) -> Result<(Header<'b>, ElfHeaderLocation), ElfHeaderError> {
+        let file =
+            ElfBytes::<AnyEndian>::minimal_parse(elf).map_err(|_| ElfHeaderError::InvalidElf)?;
+        let (header, file_offset) =
+            Header::find_header_unaligned(elf, scratch).map_err(ElfHeaderError::Header)?;
+        let location = locate(&file, file_offset, header.length());
+        Ok((header, location))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl OwnedHeader {
+    /// Like [`Header::find_in_elf`] but copies the header into an owned
+    /// allocation.
+    pub fn find_in_elf(elf: &[u8]) -> Result<(Self, ElfHeaderLocation), ElfHeaderError> {
+        let file =
+            ElfBytes::<AnyEndian>::minimal_parse(elf).map_err(|_| ElfHeaderError::InvalidElf)?;
+        let (header, file_offset) = Self::find_header(elf).map_err(ElfHeaderError::Header)?;
+        let location = locate(&file, file_offset, header.header().length());
+        Ok((header, location))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::write_elf;
+    use crate::{ConstBuilder, HeaderTagISA, MAGIC};
+    use multiboot2_common::MemoryError;
+
+    const HEADER: [u8; 24] = *ConstBuilder::new(HeaderTagISA::I386)
+        .build::<24>()
+        .as_bytes();
+
+    /// Returns a buffer that starts at an odd address, so that the ELF file
+    /// in it is unaligned.
+    fn unaligned(buffer: &mut [u8; 0x201]) -> &mut [u8] {
+        let start = usize::from(buffer.as_ptr().align_offset(2) == 0);
+        &mut buffer[start..start + 0x200]
+    }
+
+    #[test]
+    fn header_in_segment() {
+        let mut buffer = [0; 0x201];
+        let image = unaligned(&mut buffer);
+        write_elf(image);
+        image[0x180..0x198].copy_from_slice(&HEADER);
+
+        let mut scratch = HeaderScratch::new();
+        let (header, location) = Header::find_in_elf(image, &mut scratch).unwrap();
+        assert_eq!(header.length(), 24);
+        assert_eq!(location.file_offset(), 0x180);
+        let segment = location.segment().unwrap();
+        assert_eq!(segment.index, 1);
+        assert_eq!(segment.offset, 0x100);
+        assert_eq!(segment.paddr, 0x200000);
+        assert_eq!(segment.vaddr, 0xc0200000);
+        assert_eq!(segment.filesz, 0x100);
+        assert_eq!(segment.memsz, 0x200);
+        assert_eq!(segment.header_paddr(), 0x200080);
+        assert_eq!(segment.header_vaddr(), 0xc0200080);
+        assert_eq!(location.header_paddr(), Some(0x200080));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn owned_header_in_segment() {
+        let mut buffer = [0; 0x201];
+        let image = unaligned(&mut buffer);
+        write_elf(image);
+        image[0x180..0x198].copy_from_slice(&HEADER);
+
+        let (header, location) = OwnedHeader::find_in_elf(image).unwrap();
+        assert_eq!(header.header().length(), 24);
+        assert_eq!(location.header_paddr(), Some(0x200080));
+    }
+
+    #[test]
+    fn header_outside_segment() {
+        let mut buffer = [0; 0x201];
+        let image = unaligned(&mut buffer);
+        write_elf(image);
+        // Only in the `PT_NOTE` segment.
+        image[0x80..0x98].copy_from_slice(&HEADER);
+
+        let mut scratch = HeaderScratch::new();
+        let (_, location) = Header::find_in_elf(image, &mut scratch).unwrap();
+        assert_eq!(location.file_offset(), 0x80);
+        assert_eq!(location.segment(), None);
+        assert_eq!(location.header_paddr(), None);
+    }
+
+    #[test]
+    fn errors() {
+        let mut buffer = [0; 0x201];
+        let image = unaligned(&mut buffer);
+        let mut scratch = HeaderScratch::new();
+        image[0x80..0x98].copy_from_slice(&HEADER);
+        assert_eq!(
+            Header::find_in_elf(image, &mut scratch),
+            Err(ElfHeaderError::InvalidElf)
+        );
+
+        write_elf(image);
+        image[0x80..0x84].copy_from_slice(&MAGIC.to_le_bytes());
+        image[0x88..0x8c].copy_from_slice(&0x400_u32.to_le_bytes());
+        assert_eq!(
+            Header::find_in_elf(image, &mut scratch),
+            Err(ElfHeaderError::Header(LoadError::Memory(
+                MemoryError::InvalidReportedTotalSize(0x400, 0x180)
+            )))
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use crate::{HeaderTagFlag, HeaderTagFlagId, HeaderTagHeader, HeaderTagType, HeaderTagTypeId};
 use multiboot2_common::{MaybeDynSized, Tag};
 
 /// Terminates a list of optional tags in a Multiboot2 header.
@@ -26,15 +26,15 @@ impl EndHeaderTag {
         Self { header }
     }
 
-    /// Returns the [`HeaderTagType`].
+    /// Returns the [`HeaderTagTypeId`].
     #[must_use]
-    pub const fn typ(&self) -> HeaderTagType {
+    pub const fn typ(&self) -> HeaderTagTypeId {
         self.header.typ()
     }
 
-    /// Returns the [`HeaderTagFlag`]s.
+    /// Returns the [`HeaderTagFlagId`].
     #[must_use]
-    pub const fn flags(&self) -> HeaderTagFlag {
+    pub const fn flags(&self) -> HeaderTagFlagId {
         self.header.flags()
     }
 
@@ -56,6 +56,16 @@ impl Tag for EndHeaderTag {
     const ID: HeaderTagType = HeaderTagType::End;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for EndHeaderTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("EndHeaderTag", 1)?;
+        state.serialize_field("flags", &self.flags().get())?;
+        state.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::EndHeaderTag;
@@ -1,4 +1,4 @@
-use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use crate::{HeaderTagFlag, HeaderTagFlagId, HeaderTagHeader, HeaderTagType, HeaderTagTypeId};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use multiboot2_common::{MaybeDynSized, Tag};
@@ -30,15 +30,15 @@ impl EntryEfi32HeaderTag {
         Self { header, entry_addr }
     }
 
-    /// Returns the [`HeaderTagType`].
+    /// Returns the [`HeaderTagTypeId`].
     #[must_use]
-    pub const fn typ(&self) -> HeaderTagType {
+    pub const fn typ(&self) -> HeaderTagTypeId {
         self.header.typ()
     }
 
-    /// Returns the [`HeaderTagFlag`]s.
+    /// Returns the [`HeaderTagFlagId`].
     #[must_use]
-    pub const fn flags(&self) -> HeaderTagFlag {
+    pub const fn flags(&self) -> HeaderTagFlagId {
         self.header.flags()
     }
 
@@ -76,3 +76,32 @@ impl Tag for EntryEfi32HeaderTag {
     type IDType = HeaderTagType;
     const ID: HeaderTagType = HeaderTagType::EntryAddressEFI32;
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for EntryEfi32HeaderTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("EntryEfi32HeaderTag", 2)?;
+        state.serialize_field("flags", &self.flags().get())?;
+        state.serialize_field("entry_addr", &self.entry_addr)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EntryEfi32HeaderTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "EntryEfi32HeaderTag")]
+        struct Fields {
+            flags: u16,
+            entry_addr: u32,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(
+            crate::UnknownHeaderValue::try_convert(HeaderTagFlagId::new(fields.flags))?,
+            fields.entry_addr,
+        ))
+    }
+}
@@ -1,8 +1,9 @@
 use crate::{
-    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EntryAddressHeaderTag,
-    EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag, HeaderTagHeader, HeaderTagISA,
-    HeaderTagType, InformationRequestHeaderTag, ModuleAlignHeaderTag, RelocatableHeaderTag,
-    TagIter,
+    AddressHeaderTag, ConsoleHeaderTag, CustomHeaderTagDecoder, EfiBootServiceHeaderTag,
+    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag,
+    GenericHeaderTag, HeaderTagFlagId, HeaderTagHeader, HeaderTagISAId, HeaderTagRef,
+    HeaderTagType, HeaderTagTypeId, InformationRequestDecision, InformationRequestHeaderTag,
+    MbiTagType, MbiTagTypeId, ModuleAlignHeaderTag, RelocatableHeaderTag, Report, TagIter,
 };
 use core::fmt::{Debug, Formatter};
 use core::ptr::NonNull;
@@ -73,9 +74,9 @@ impl<'a> Header<'a> {
     /// Checks whether the header has a valid, complete tag sequence.
     fn has_valid_tag_sequence(&self) -> Result<bool, MemoryError> {
         validate_tag_sequence(self.0.payload(), |tag| {
-            let typ = u16::from_le_bytes(tag[0..2].try_into().unwrap());
-            let flags = u16::from_le_bytes(tag[2..4].try_into().unwrap());
-            let size = u32::from_le_bytes(tag[4..8].try_into().unwrap()) as usize;
+            let typ = u16::from_ne_bytes(tag[0..2].try_into().unwrap());
+            let flags = u16::from_ne_bytes(tag[2..4].try_into().unwrap());
+            let size = u32::from_ne_bytes(tag[4..8].try_into().unwrap()) as usize;
 
             typ == HeaderTagType::End as u16
                 && flags == crate::HeaderTagFlag::Required as u16
@@ -97,6 +98,9 @@ impl<'a> Header<'a> {
     /// # Parameters
     /// - `buffer`: [64-bit aligned](ALIGNMENT) buffer describing the first
     ///   [`HEADER_SEARCH_LIMIT`] bytes of a potential Multiboot2 kernel image.
+    ///
+    /// For unaligned buffers, use [`Header::find_header_unaligned`] or
+    /// `OwnedHeader::find_header` (requires the `alloc` feature).
     pub fn find_header(buffer: &[u8]) -> Result<(Self, usize /* index in buffer */), LoadError> {
         if buffer.len() < size_of::<Multiboot2BasicHeader>() {
             return Err(LoadError::Memory(MemoryError::ShorterThanHeader));
@@ -173,6 +177,12 @@ impl<'a> Header<'a> {
         Ok((header, magic_begin_idx))
     }
 
+    /// Returns the address of the header in memory.
+    #[cfg(feature = "alloc")]
+    pub(crate) const fn as_ptr(&self) -> *const u8 {
+        (&raw const *self.0).cast()
+    }
+
     /// Returns a [`TagIter`].
     #[must_use]
     pub fn iter(&self) -> TagIter<'_> {
@@ -181,6 +191,27 @@ impl<'a> Header<'a> {
         unsafe { TagIter::new(self.0.payload()) }
     }
 
+    /// Returns an iterator over all tags as [`HeaderTagRef`], including the
+    /// end tag, in their original order.
+    ///
+    /// In contrast to [`Self::iter`], this enables exhaustive matching over
+    /// all header tag types without manual casts.
+    pub fn typed_tags(&self) -> impl Iterator<Item = HeaderTagRef<'_>> + Clone {
+        self.iter().map(HeaderTagRef::from)
+    }
+
+    /// Returns a [`Debug`] representation like the one of this type that
+    /// additionally shows all unknown tags for which a
+    /// [`CustomHeaderTagDecoder`] is given. Unknown tags without a decoder
+    /// are shown by their type.
+    #[must_use]
+    pub fn debug_with<'b>(&'b self, decoders: &'b [CustomHeaderTagDecoder]) -> impl Debug + 'b {
+        DebugHeader {
+            header: self,
+            decoders,
+        }
+    }
+
     /// Wrapper around [`Multiboot2BasicHeader::verify_checksum`].
     pub const fn verify_checksum(
         &self,
@@ -200,7 +231,7 @@ impl<'a> Header<'a> {
     }
     /// Wrapper around [`Multiboot2BasicHeader::arch`].
     #[must_use]
-    pub const fn arch(&self) -> HeaderTagISA {
+    pub const fn arch(&self) -> HeaderTagISAId {
         self.0.header().arch()
     }
     /// Wrapper around [`Multiboot2BasicHeader::length`].
@@ -215,7 +246,7 @@ impl<'a> Header<'a> {
     }
     /// Wrapper around [`Multiboot2BasicHeader::calc_checksum`].
     #[must_use]
-    pub const fn calc_checksum(magic: u32, arch: HeaderTagISA, length: u32) -> u32 {
+    pub const fn calc_checksum(magic: u32, arch: HeaderTagISAId, length: u32) -> u32 {
         Multiboot2BasicHeader::calc_checksum(magic, arch, length)
     }
 
@@ -279,39 +310,177 @@ impl<'a> Header<'a> {
         self.get_tag()
     }
 
-    /// Searches for the specified tag by iterating the structure and returns
-    /// the first occurrence, if present.
+    /// Returns all tags whose [`HeaderTagTypeId`] is unknown to this crate.
+    ///
+    /// The spec demands that bootloaders refuse to boot if they don't
+    /// understand a tag that is not optional (see
+    /// [`HeaderTagFlagId::is_optional`]).
+    pub fn unknown_tags(&self) -> impl Iterator<Item = &GenericHeaderTag> + Clone + '_ {
+        self.iter().filter(|tag| !tag.header().typ().is_known())
+    }
+
+    /// Returns all information requests of all [`InformationRequestHeaderTag`]s
+    /// together with the [`HeaderTagFlagId`] of the tag they belong to.
+    ///
+    /// All requests of a tag that is not optional (see
+    /// [`HeaderTagFlagId::is_optional`]) must be satisfied by the bootloader,
+    /// otherwise it must refuse to boot.
+    pub fn information_requests(
+        &self,
+    ) -> impl Iterator<Item = (MbiTagTypeId, HeaderTagFlagId)> + Clone + '_ {
+        self.iter()
+            .filter(|tag| tag.header().typ() == HeaderTagType::InformationRequest)
+            .map(|tag| tag.cast::<InformationRequestHeaderTag>())
+            .flat_map(|tag| tag.requests().iter().map(|&id| (id, tag.flags())))
+    }
+
+    /// Matches the information requests of this header against the MBI tag
+    /// types the bootloader is able to provide.
+    ///
+    /// The returned [`InformationRequestDecision`] tells which tags to emit,
+    /// which requests can't be satisfied, and whether the bootloader must
+    /// refuse to boot.
+    #[must_use]
+    pub const fn negotiate_information_requests<'b>(
+        &'b self,
+        supported: &'b [MbiTagType],
+    ) -> InformationRequestDecision<'b> {
+        InformationRequestDecision::new(self, supported)
+    }
+
+    /// Checks the header against the semantic rules of the spec, beyond what
+    /// [`Self::load`] verifies.
+    ///
+    /// `header_offset` is the offset of the header in `image`, as returned
+    /// by [`Self::find_header`]. It is needed because the header doesn't know
+    /// where it is located: The search limit applies to the offset, and the
+    /// `header_addr` of the [`AddressHeaderTag`] maps the offset to a load
+    /// address.
+    ///
+    /// `image` is the whole kernel image. Without an [`AddressHeaderTag`], it
+    /// must be an ELF file, and the entry address is checked against the
+    /// physical ranges of its `PT_LOAD` segments. If it is no valid ELF file,
+    /// the entry address is not checked.
+    ///
+    /// `supported` lists the tag types the bootloader understands, including
+    /// custom ones; every other tag must be optional. The end tag is always
+    /// supported.
+    ///
+    /// The header may come from an untrusted image: Tags of known types whose
+    /// size doesn't match their layout are reported as
+    /// [`Violation::InvalidTagSize`] and the checks that depend on their
+    /// fields are skipped.
+    ///
+    /// Bootloaders should refuse to boot if the returned [`Report`] contains
+    /// any [`Violation`].
+    ///
+    /// [`Violation`]: crate::Violation
+    /// [`Violation::InvalidTagSize`]: crate::Violation::InvalidTagSize
+    #[must_use]
+    pub const fn check<'b>(
+        &'b self,
+        header_offset: usize,
+        image: &'b [u8],
+        supported: &'b [HeaderTagTypeId],
+    ) -> Report<'b> {
+        Report::new(self, header_offset, image, supported)
+    }
+
+    /// Returns the first header tag of type `T`, if present.
+    ///
+    /// This works for the tags of this crate as well as for user-defined
+    /// custom tags, which are identified by their [`Tag::ID`]. Custom tags
+    /// typically use a [`HeaderTagTypeId`] as [`Tag::IDType`]. With the
+    /// `derive` feature, `#[derive(Multiboot2Tag)]` implements the traits of
+    /// the example below.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use multiboot2_header::{Header, HeaderTagHeader, HeaderTagTypeId, MaybeDynSized, Tag};
+    ///
+    /// #[repr(C, align(8))]
+    /// struct VendorTag {
+    ///     header: HeaderTagHeader,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl MaybeDynSized for VendorTag {
+    ///     type Header = HeaderTagHeader;
+    ///     const BASE_SIZE: usize = size_of::<Self>();
+    /// }
+    ///
+    /// impl Tag for VendorTag {
+    ///     type IDType = HeaderTagTypeId;
+    ///     const ID: HeaderTagTypeId = HeaderTagTypeId::new(0x1337);
+    /// }
+    ///
+    /// let ptr = 0x1337_0000 as *const u8 /* use real ptr here */;
+    /// let header = unsafe { Header::load(ptr.cast()) }.unwrap();
+    /// let value = header.get_tag::<VendorTag>().map(|tag| tag.value);
+    /// ```
     #[must_use]
-    fn get_tag<T: Tag<IDType = HeaderTagType, Header = HeaderTagHeader> + ?Sized + 'a>(
-        &'a self,
-    ) -> Option<&'a T>
+    pub fn get_tag<'b, T>(&'b self) -> Option<&'b T>
     where
+        T: Tag<Header = HeaderTagHeader> + ?Sized + 'b,
         T::Metadata: Default,
+        HeaderTagTypeId: PartialEq<T::IDType>,
+    {
+        self.get_tags::<T>().next()
+    }
+
+    /// Returns an iterator over all header tags of type `T`, in their
+    /// original order. See [`Self::get_tag`].
+    pub fn get_tags<'b, T>(&'b self) -> impl Iterator<Item = &'b T> + Clone
+    where
+        T: Tag<Header = HeaderTagHeader> + ?Sized + 'b,
+        T::Metadata: Default,
+        HeaderTagTypeId: PartialEq<T::IDType>,
     {
         self.iter()
-            .find(|tag| tag.header().typ() == T::ID)
+            .filter(|tag| tag.header().typ() == T::ID)
             .map(|tag| tag.cast::<T>())
     }
 }
 
 impl Debug for Header<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.debug_with(&[]), f)
+    }
+}
+
+/// Formats a [`Header`] with the given custom tag decoders.
+struct DebugHeader<'a, 'b> {
+    header: &'b Header<'a>,
+    decoders: &'b [CustomHeaderTagDecoder],
+}
+
+impl Debug for DebugHeader<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let header = self.header;
         f.debug_struct("Header")
-            .field("magic", &self.header_magic())
-            .field("arch", &self.arch())
-            .field("length", &self.length())
-            .field("checksum", &self.checksum())
-            .field("information_request", &self.information_request_tag())
-            .field("address", &self.address_tag())
-            .field("entry_address", &self.entry_address_tag())
-            .field("entry_address_efi32", &self.entry_address_efi32_tag())
-            .field("entry_address_efi64", &self.entry_address_efi64_tag())
-            .field("console_flags", &self.console_flags_tag())
-            .field("framebuffer", &self.framebuffer_tag())
-            .field("module_align", &self.module_align_tag())
-            .field("efi_boot_services", &self.efi_boot_services_tag())
-            .field("relocatable", &self.relocatable_tag())
-            .field("tag_headers", &DebugTagHeaders(self.iter()))
+            .field("magic", &header.header_magic())
+            .field("arch", &header.arch())
+            .field("length", &header.length())
+            .field("checksum", &header.checksum())
+            .field("information_request", &header.information_request_tag())
+            .field("address", &header.address_tag())
+            .field("entry_address", &header.entry_address_tag())
+            .field("entry_address_efi32", &header.entry_address_efi32_tag())
+            .field("entry_address_efi64", &header.entry_address_efi64_tag())
+            .field("console_flags", &header.console_flags_tag())
+            .field("framebuffer", &header.framebuffer_tag())
+            .field("module_align", &header.module_align_tag())
+            .field("efi_boot_services", &header.efi_boot_services_tag())
+            .field("relocatable", &header.relocatable_tag())
+            .field(
+                "unknown_tags",
+                &DebugUnknownTags {
+                    tags: header.unknown_tags(),
+                    decoders: self.decoders,
+                },
+            )
+            .field("tag_headers", &DebugTagHeaders(header.iter()))
             .finish()
     }
 }
@@ -327,6 +496,116 @@ impl Debug for DebugTagHeaders<'_> {
     }
 }
 
+/// Formats all unknown tags with the first matching decoder, or only their
+/// raw type if there is none.
+struct DebugUnknownTags<'b, I> {
+    tags: I,
+    decoders: &'b [CustomHeaderTagDecoder],
+}
+
+impl<'a, I: Iterator<Item = &'a GenericHeaderTag> + Clone> Debug for DebugUnknownTags<'_, I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list()
+            .entries(self.tags.clone().map(|tag| DebugUnknownTag {
+                tag,
+                decoders: self.decoders,
+            }))
+            .finish()
+    }
+}
+
+/// Formats an unknown tag. See [`DebugUnknownTags`].
+struct DebugUnknownTag<'a, 'b> {
+    tag: &'a GenericHeaderTag,
+    decoders: &'b [CustomHeaderTagDecoder],
+}
+
+impl Debug for DebugUnknownTag<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let typ = self.tag.header().typ();
+        match self.decoders.iter().find(|decoder| decoder.typ() == typ) {
+            Some(decoder) => decoder.format(self.tag, f),
+            None => Debug::fmt(&typ, f),
+        }
+    }
+}
+
+/// Serialized form of a header tag, tagged with its type.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum SerializedHeaderTag<'a> {
+    InformationRequest(&'a InformationRequestHeaderTag),
+    Address(&'a AddressHeaderTag),
+    EntryAddress(&'a EntryAddressHeaderTag),
+    ConsoleFlags(&'a ConsoleHeaderTag),
+    Framebuffer(&'a FramebufferHeaderTag),
+    ModuleAlign(&'a ModuleAlignHeaderTag),
+    EfiBs(&'a EfiBootServiceHeaderTag),
+    EntryAddressEfi32(&'a EntryEfi32HeaderTag),
+    EntryAddressEfi64(&'a EntryEfi64HeaderTag),
+    Relocatable(&'a RelocatableHeaderTag),
+    Custom {
+        id: u16,
+        flags: u16,
+        payload: &'a [u8],
+    },
+}
+
+#[cfg(feature = "serde")]
+impl<'a> SerializedHeaderTag<'a> {
+    /// Returns the typed tag. Returns `None` for the end tag.
+    fn new(tag: &'a GenericHeaderTag) -> Option<Self> {
+        let typ = tag.header().typ();
+        let tag = match HeaderTagType::try_from(typ) {
+            Ok(HeaderTagType::End) => return None,
+            Ok(HeaderTagType::InformationRequest) => Self::InformationRequest(tag.cast()),
+            Ok(HeaderTagType::Address) => Self::Address(tag.cast()),
+            Ok(HeaderTagType::EntryAddress) => Self::EntryAddress(tag.cast()),
+            Ok(HeaderTagType::ConsoleFlags) => Self::ConsoleFlags(tag.cast()),
+            Ok(HeaderTagType::Framebuffer) => Self::Framebuffer(tag.cast()),
+            Ok(HeaderTagType::ModuleAlign) => Self::ModuleAlign(tag.cast()),
+            Ok(HeaderTagType::EfiBS) => Self::EfiBs(tag.cast()),
+            Ok(HeaderTagType::EntryAddressEFI32) => Self::EntryAddressEfi32(tag.cast()),
+            Ok(HeaderTagType::EntryAddressEFI64) => Self::EntryAddressEfi64(tag.cast()),
+            Ok(HeaderTagType::Relocatable) => Self::Relocatable(tag.cast()),
+            Err(_) => Self::Custom {
+                id: typ.get(),
+                flags: tag.header().flags().get(),
+                payload: tag.payload(),
+            },
+        };
+        Some(tag)
+    }
+}
+
+/// Serializes the header with its raw `arch`, `length`, and `checksum` and
+/// its `tags`. Each tag is an object with a snake-case `type` and the fields
+/// of the tag. Unknown tags are serialized as `custom` with their `id`, raw
+/// `flags`, and raw `payload`. The end tag is omitted.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Header<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        /// Serializes the tags as a sequence.
+        struct Tags<'a>(TagIter<'a>);
+
+        impl serde::Serialize for Tags<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.clone().filter_map(SerializedHeaderTag::new))
+            }
+        }
+
+        let mut state = serializer.serialize_struct("Header", 4)?;
+        state.serialize_field("arch", &self.arch().get())?;
+        state.serialize_field("length", &self.length())?;
+        state.serialize_field("checksum", &self.checksum())?;
+        state.serialize_field("tags", &Tags(self.iter()))?;
+        state.end()
+    }
+}
+
 /// Errors that occur when a chunk of memory can't be parsed as
 /// [`Header`].
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
@@ -356,7 +635,7 @@ pub enum LoadError {
 pub struct Multiboot2BasicHeader {
     /// Must be the value of [`MAGIC`].
     header_magic: u32,
-    arch: HeaderTagISA,
+    arch: HeaderTagISAId,
     length: u32,
     checksum: u32,
     // Followed by dynamic amount of dynamically sized header tags.
@@ -364,9 +643,8 @@ pub struct Multiboot2BasicHeader {
 }
 
 impl Multiboot2BasicHeader {
-    #[cfg(feature = "builder")]
     /// Constructor for the basic header.
-    pub(crate) const fn new(arch: HeaderTagISA, length: u32) -> Self {
+    pub(crate) const fn new(arch: HeaderTagISAId, length: u32) -> Self {
         let magic = MAGIC;
         let checksum = Self::calc_checksum(magic, arch, length);
         Self {
@@ -397,8 +675,8 @@ impl Multiboot2BasicHeader {
 
     /// Calculates the checksum as described in the spec.
     #[must_use]
-    pub const fn calc_checksum(magic: u32, arch: HeaderTagISA, length: u32) -> u32 {
-        (0x100000000 - magic as u64 - arch as u64 - length as u64) as u32
+    pub const fn calc_checksum(magic: u32, arch: HeaderTagISAId, length: u32) -> u32 {
+        (0x100000000 - magic as u64 - arch.get() as u64 - length as u64) as u32
     }
 
     /// Returns the header magic.
@@ -407,9 +685,9 @@ impl Multiboot2BasicHeader {
         self.header_magic
     }
 
-    /// Returns the [`HeaderTagISA`].
+    /// Returns the [`HeaderTagISAId`].
     #[must_use]
-    pub const fn arch(&self) -> HeaderTagISA {
+    pub const fn arch(&self) -> HeaderTagISAId {
         self.arch
     }
 
@@ -451,7 +729,9 @@ impl Debug for Multiboot2BasicHeader {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Header, HeaderTagISA, HeaderTagType, LoadError, MAGIC, Multiboot2BasicHeader};
+    use crate::{
+        Header, HeaderTagFlag, HeaderTagISA, HeaderTagType, LoadError, MAGIC, Multiboot2BasicHeader,
+    };
     use core::borrow::Borrow;
     use multiboot2_common::MemoryError;
     use multiboot2_common::test_utils::AlignedBytes;
@@ -541,7 +821,7 @@ mod tests {
     #[test]
     fn load_rejects_missing_end_tag() {
         let mut bytes = AlignedBytes::new([0; 16]);
-        let checksum = Multiboot2BasicHeader::calc_checksum(MAGIC, HeaderTagISA::I386, 16);
+        let checksum = Multiboot2BasicHeader::calc_checksum(MAGIC, HeaderTagISA::I386.into(), 16);
         bytes.0[0..4].copy_from_slice(&MAGIC.to_le_bytes());
         bytes.0[8..12].copy_from_slice(&16_u32.to_le_bytes());
         bytes.0[12..16].copy_from_slice(&checksum.to_le_bytes());
@@ -556,7 +836,7 @@ mod tests {
     #[test]
     fn load_rejects_invalid_inner_tag_size() {
         let mut bytes = AlignedBytes::new([0; 32]);
-        let checksum = Multiboot2BasicHeader::calc_checksum(MAGIC, HeaderTagISA::I386, 32);
+        let checksum = Multiboot2BasicHeader::calc_checksum(MAGIC, HeaderTagISA::I386.into(), 32);
         bytes.0[0..4].copy_from_slice(&MAGIC.to_le_bytes());
         bytes.0[4..8].copy_from_slice(&(HeaderTagISA::I386 as u32).to_le_bytes());
         bytes.0[8..12].copy_from_slice(&32_u32.to_le_bytes());
@@ -575,4 +855,39 @@ mod tests {
             )))
         );
     }
+
+    #[test]
+    fn load_accepts_unknown_values() {
+        let mut bytes = AlignedBytes::new([0; 40]);
+        let checksum = Multiboot2BasicHeader::calc_checksum(MAGIC, 42.into(), 40);
+        bytes.0[0..4].copy_from_slice(&MAGIC.to_le_bytes());
+        bytes.0[4..8].copy_from_slice(&42_u32.to_le_bytes());
+        bytes.0[8..12].copy_from_slice(&40_u32.to_le_bytes());
+        bytes.0[12..16].copy_from_slice(&checksum.to_le_bytes());
+        // Unknown tag with unknown flags
+        bytes.0[16..18].copy_from_slice(&42_u16.to_le_bytes());
+        bytes.0[18..20].copy_from_slice(&6_u16.to_le_bytes());
+        bytes.0[20..24].copy_from_slice(&12_u32.to_le_bytes());
+        // End tag
+        bytes.0[32..34].copy_from_slice(&0_u16.to_le_bytes());
+        bytes.0[36..40].copy_from_slice(&8_u32.to_le_bytes());
+
+        // SAFETY: The test buffer is aligned and contains a valid
+        // header layout.
+        let header = unsafe { Header::load(bytes.as_ptr().cast()) }.unwrap();
+
+        assert_eq!(header.arch().get(), 42);
+        assert!(HeaderTagISA::try_from(header.arch()).is_err());
+        let unknown = header.unknown_tags().collect::<std::vec::Vec<_>>();
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].header().typ().get(), 42);
+        assert!(HeaderTagType::try_from(unknown[0].header().typ()).is_err());
+        assert!(!unknown[0].header().flags().is_optional());
+        assert!(HeaderTagFlag::try_from(unknown[0].header().flags()).is_err());
+        assert_eq!(unknown[0].payload(), [0; 4]);
+
+        let debug = format!("{header:?}");
+        assert!(debug.contains("HeaderTagTypeId(0x2a)"));
+        assert!(debug.contains("HeaderTagISAId(0x2a)"));
+    }
 }
@@ -1,10 +1,13 @@
-use crate::{HeaderTagFlag, HeaderTagHeader};
-use crate::{HeaderTagType, MbiTagTypeId};
+#[cfg(feature = "builder")]
+use crate::HeaderTagFlag;
+use crate::{Header, HeaderTagFlagId, HeaderTagHeader};
+use crate::{HeaderTagType, HeaderTagTypeId, MbiTagType, MbiTagTypeId};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 #[cfg(feature = "builder")]
 use multiboot2_common::new_boxed;
 use multiboot2_common::{MaybeDynSized, Tag};
+use thiserror::Error;
 #[cfg(feature = "builder")]
 use {alloc::boxed::Box, core::slice};
 
@@ -31,15 +34,15 @@ impl InformationRequestHeaderTag {
         new_boxed(header, &[requests])
     }
 
-    /// Returns the [`HeaderTagType`].
+    /// Returns the [`HeaderTagTypeId`].
     #[must_use]
-    pub const fn typ(&self) -> HeaderTagType {
+    pub const fn typ(&self) -> HeaderTagTypeId {
         self.header.typ()
     }
 
-    /// Returns the [`HeaderTagFlag`]s.
+    /// Returns the [`HeaderTagFlagId`].
     #[must_use]
-    pub const fn flags(&self) -> HeaderTagFlag {
+    pub const fn flags(&self) -> HeaderTagFlagId {
         self.header.flags()
     }
 
@@ -84,11 +87,144 @@ impl Tag for InformationRequestHeaderTag {
     const ID: HeaderTagType = HeaderTagType::InformationRequest;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for InformationRequestHeaderTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        /// Serializes the requests as a sequence of raw tag types.
+        struct Requests<'a>(&'a [MbiTagTypeId]);
+
+        impl serde::Serialize for Requests<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.iter().map(|&id| u32::from(id)))
+            }
+        }
+
+        let mut state = serializer.serialize_struct("InformationRequestHeaderTag", 2)?;
+        state.serialize_field("flags", &self.flags().get())?;
+        state.serialize_field("requests", &Requests(&self.requests))?;
+        state.end()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "builder"))]
+impl<'de> serde::Deserialize<'de> for Box<InformationRequestHeaderTag> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use alloc::vec::Vec;
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "InformationRequestHeaderTag")]
+        struct Fields {
+            flags: u16,
+            requests: Vec<u32>,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        let requests = fields
+            .requests
+            .into_iter()
+            .map(MbiTagTypeId::from)
+            .collect::<Vec<_>>();
+        Ok(InformationRequestHeaderTag::new(
+            crate::UnknownHeaderValue::try_convert(HeaderTagFlagId::new(fields.flags))?,
+            &requests,
+        ))
+    }
+}
+
+/// Outcome of matching the information requests of a [`Header`] against the
+/// MBI tag types a bootloader is able to provide.
+///
+/// Created by [`Header::negotiate_information_requests`]. A bootloader uses it
+/// to decide which tags to put into the boot information and whether it must
+/// refuse to boot the image.
+#[derive(Clone, Debug)]
+pub struct InformationRequestDecision<'a> {
+    header: &'a Header<'a>,
+    supported: &'a [MbiTagType],
+}
+
+impl<'a> InformationRequestDecision<'a> {
+    pub(crate) const fn new(header: &'a Header<'a>, supported: &'a [MbiTagType]) -> Self {
+        Self { header, supported }
+    }
+
+    fn is_supported(&self, id: MbiTagTypeId) -> bool {
+        self.supported.iter().any(|&typ| typ == id)
+    }
+
+    /// Returns the requested tag types that the bootloader can provide and
+    /// therefore should emit. A type requested multiple times is returned
+    /// multiple times.
+    pub fn emit(&self) -> impl Iterator<Item = MbiTagTypeId> + '_ {
+        self.header
+            .information_requests()
+            .map(|(id, _)| id)
+            .filter(|&id| self.is_supported(id))
+    }
+
+    /// Returns the requested tag types that the bootloader can't provide,
+    /// together with the [`HeaderTagFlagId`] of the request.
+    pub fn missing(&self) -> impl Iterator<Item = (MbiTagTypeId, HeaderTagFlagId)> + '_ {
+        self.header
+            .information_requests()
+            .filter(|&(id, _)| !self.is_supported(id))
+    }
+
+    /// Returns the required tag types that the bootloader can't provide.
+    pub fn missing_required(&self) -> impl Iterator<Item = MbiTagTypeId> + '_ {
+        self.missing()
+            .filter(|&(_, flags)| !flags.is_optional())
+            .map(|(id, _)| id)
+    }
+
+    /// Returns whether the bootloader must refuse to boot the image, as at
+    /// least one required information request can't be satisfied.
+    #[must_use]
+    pub fn must_refuse_boot(&self) -> bool {
+        self.missing_required().next().is_some()
+    }
+}
+
+/// A required information request of a [`Header`] isn't satisfied by a
+/// boot information.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Error)]
+#[error("required information request for MBI tag {0:?} is not satisfied")]
+pub struct UnsatisfiedRequestError(pub MbiTagTypeId);
+
+/// Extension for [`multiboot2::Builder`] to verify a boot information against
+/// the information requests of a [`Header`].
+///
+/// This lives here as `multiboot2` doesn't depend on this crate. It requires
+/// the `mbi-builder` feature.
+#[cfg(feature = "mbi-builder")]
+pub trait MbiBuilderExt {
+    /// Checks that the boot information built by this builder contains a tag
+    /// for every required information request of `header`.
+    ///
+    /// Returns the first unsatisfied request on failure.
+    fn check_against(&self, header: &Header) -> Result<(), UnsatisfiedRequestError>;
+}
+
+#[cfg(feature = "mbi-builder")]
+impl MbiBuilderExt for multiboot2::Builder {
+    fn check_against(&self, header: &Header) -> Result<(), UnsatisfiedRequestError> {
+        header
+            .information_requests()
+            .filter(|&(_, flags)| !flags.is_optional())
+            .find(|&(id, _)| !self.has_tag(id))
+            .map_or(Ok(()), |(id, _)| Err(UnsatisfiedRequestError(id)))
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "builder")]
 mod tests {
     use super::*;
-    use crate::MbiTagType;
+    use crate::{Builder, HeaderTagISA, Multiboot2BasicHeader};
+    use alloc::vec::Vec;
+    #[cfg(feature = "mbi-builder")]
+    use multiboot2::{BootLoaderNameTag, CommandLineTag};
 
     #[test]
     fn creation() {
@@ -121,4 +257,63 @@ mod tests {
             ],
         );
     }
+
+    fn build_header() -> Box<multiboot2_common::DynSizedStructure<Multiboot2BasicHeader>> {
+        Builder::new(HeaderTagISA::I386)
+            .information_request_tag(InformationRequestHeaderTag::new(
+                HeaderTagFlag::Required,
+                &[MbiTagType::Cmdline.into(), MbiTagType::Mmap.into()],
+            ))
+            .build()
+    }
+
+    #[test]
+    fn negotiation() {
+        let bytes = build_header();
+        // SAFETY: The builder creates a valid header.
+        let header = unsafe { Header::load(bytes.as_bytes().as_ptr().cast()) }.unwrap();
+        assert_eq!(
+            header.information_requests().collect::<Vec<_>>(),
+            [
+                (MbiTagType::Cmdline.into(), HeaderTagFlag::Required.into()),
+                (MbiTagType::Mmap.into(), HeaderTagFlag::Required.into())
+            ]
+        );
+
+        let supported = [MbiTagType::Cmdline, MbiTagType::BootLoaderName];
+        let decision = header.negotiate_information_requests(&supported);
+        assert_eq!(
+            decision.emit().collect::<Vec<_>>(),
+            [MbiTagTypeId::from(MbiTagType::Cmdline)]
+        );
+        assert_eq!(
+            decision.missing_required().collect::<Vec<_>>(),
+            [MbiTagTypeId::from(MbiTagType::Mmap)]
+        );
+        assert!(decision.must_refuse_boot());
+
+        let supported = [MbiTagType::Mmap, MbiTagType::Cmdline];
+        let decision = header.negotiate_information_requests(&supported);
+        assert_eq!(decision.emit().count(), 2);
+        assert_eq!(decision.missing().count(), 0);
+        assert!(!decision.must_refuse_boot());
+    }
+
+    #[test]
+    #[cfg(feature = "mbi-builder")]
+    fn check_against() {
+        let bytes = build_header();
+        // SAFETY: The builder creates a valid header.
+        let header = unsafe { Header::load(bytes.as_bytes().as_ptr().cast()) }.unwrap();
+
+        let builder = multiboot2::Builder::new()
+            .cmdline(CommandLineTag::new("foo"))
+            .bootloader(BootLoaderNameTag::new("bar"));
+        assert_eq!(
+            builder.check_against(&header),
+            Err(UnsatisfiedRequestError(MbiTagType::Mmap.into()))
+        );
+        let builder = builder.mmap(multiboot2::MemoryMapTag::new(&[]));
+        assert_eq!(builder.check_against(&header), Ok(()));
+    }
 }
@@ -13,7 +13,21 @@
 //!
 //! This library is always `no_std`. The default `builder` feature enables
 //! `alloc`; using it requires an `#[global_allocator]`. Remove that feature if
-//! you do not need to construct headers.
+//! you do not need to construct headers. Headers can also be built at compile
+//! time without `alloc` using [`ConstBuilder`] and [`multiboot2_header!`].
+//!
+//! The optional `serde` feature implements `serde::Serialize` for [`Header`]
+//! and all header tags. Together with `builder`, it also implements
+//! `serde::Deserialize` for [`Builder`], which builds the same header again.
+//!
+//! The optional `mbi-builder` feature enables the `builder` feature of
+//! `multiboot2` and provides `MbiBuilderExt` to check a
+//! `multiboot2::Builder` against the information requests of a header, and
+//! `ModulePlanner` to place boot modules.
+//!
+//! The optional `derive` feature provides the `Multiboot2Tag` derive macro,
+//! which implements the necessary traits for custom header tags. See
+//! [`Header::get_tag`].
 //!
 //! ## Example: Parsing a Header
 //!
@@ -50,7 +64,7 @@
 #![deny(rustdoc::all)]
 // --- END STYLE CHECKS ---
 
-#[cfg(feature = "builder")]
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
 #[cfg_attr(test, macro_use)]
@@ -64,11 +78,14 @@ pub type TagIter<'a> = multiboot2_common::TagIter<'a, HeaderTagHeader>;
 ///
 /// This represents an entire header tag, including its variable payload, not
 /// the fixed-size [`HeaderTagHeader`] prefix.
-#[cfg(test)]
 pub type GenericHeaderTag = multiboot2_common::DynSizedStructure<HeaderTagHeader>;
 
 mod address;
+mod check;
 mod console;
+mod const_builder;
+mod custom_tag_decoder;
+mod elf_location;
 mod end;
 mod entry_address;
 mod entry_efi_32;
@@ -76,18 +93,31 @@ mod entry_efi_64;
 mod framebuffer;
 mod header;
 mod information_request;
+#[cfg(feature = "alloc")]
+mod load_plan;
 mod module_align;
+#[cfg(feature = "mbi-builder")]
+mod module_placement;
+mod multiboot1;
 mod relocatable;
+mod tag_ref;
 mod tags;
+#[cfg(test)]
+mod test_utils;
 mod uefi_bs;
+mod unaligned;
 
 #[cfg(feature = "builder")]
 mod builder;
 
-pub use multiboot2_common::{DynSizedStructure, MaybeDynSized, Tag};
+pub use multiboot2_common::{ByteOrder, DynSizedStructure, MaybeDynSized, Tag};
 
 pub use self::address::*;
+pub use self::check::*;
 pub use self::console::*;
+pub use self::const_builder::*;
+pub use self::custom_tag_decoder::*;
+pub use self::elf_location::*;
 pub use self::end::*;
 pub use self::entry_address::*;
 pub use self::entry_efi_32::*;
@@ -95,12 +125,52 @@ pub use self::entry_efi_64::*;
 pub use self::framebuffer::*;
 pub use self::header::*;
 pub use self::information_request::*;
+#[cfg(feature = "alloc")]
+pub use self::load_plan::*;
 pub use self::module_align::*;
+#[cfg(feature = "mbi-builder")]
+pub use self::module_placement::*;
+pub use self::multiboot1::*;
 pub use self::relocatable::*;
+pub use self::tag_ref::*;
 pub use self::tags::*;
 pub use self::uefi_bs::*;
+pub use self::unaligned::*;
 #[cfg(feature = "builder")]
-pub use builder::Builder;
+pub use builder::{Builder, HeaderBuildError};
 
 /// Re-export of [`multiboot2::TagType`] from `multiboot2`-crate.
 pub use multiboot2::{TagType as MbiTagType, TagTypeId as MbiTagTypeId};
+
+#[cfg(feature = "derive")]
+pub use multiboot2_common::NoPadding;
+#[cfg(feature = "derive")]
+pub use multiboot2_derive::Multiboot2Tag;
+
+/// Items used by the code generated by [`Multiboot2Tag`]. Not public API.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::__multiboot2_header_builder_only as builder_only;
+    pub use crate::{HeaderTagFlag, HeaderTagFlagId, HeaderTagHeader, HeaderTagTypeId};
+    pub use multiboot2_common::{Header, MaybeDynSized, Tag, bytes_of};
+    pub use ptr_meta::Pointee;
+    #[cfg(feature = "builder")]
+    pub use {alloc::boxed::Box, multiboot2_common::new_boxed};
+}
+
+/// Expands the given items only with the `builder` feature.
+#[cfg(all(feature = "derive", feature = "builder"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __multiboot2_header_builder_only {
+    ($($item:item)*) => { $($item)* };
+}
+
+/// Expands the given items only with the `builder` feature.
+#[cfg(all(feature = "derive", not(feature = "builder")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __multiboot2_header_builder_only {
+    ($($item:item)*) => {};
+}
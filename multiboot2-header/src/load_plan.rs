@@ -0,0 +1,578 @@
+//! Module for [`LoadPlan`].
+
+use crate::{Header, RelocatableHeaderTag, RelocatableHeaderTagPreference};
+use alloc::vec::Vec;
+use core::ops::Range;
+use elf::ElfBytes;
+use elf::abi::PT_LOAD;
+use elf::endian::AnyEndian;
+use multiboot2::{MemoryArea, MemoryAreaType};
+use thiserror::Error;
+
+/// Firmware environment in which the bootloader hands off to the kernel.
+///
+/// It determines which entry point of the [`Header`] is used.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Firmware {
+    /// Legacy BIOS or any other non-EFI environment.
+    Bios,
+    /// 32-bit EFI.
+    Efi32,
+    /// 64-bit EFI.
+    Efi64,
+}
+
+/// A single operation to place the kernel image in physical memory.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LoadOp {
+    /// Copies the bytes `src` of the image to the physical address `dest`.
+    Copy {
+        /// Byte range in the image.
+        src: Range<usize>,
+        /// Physical destination address.
+        dest: u64,
+    },
+    /// Zeroes `len` bytes at the physical address `dest`, e.g., for `.bss`.
+    Zero {
+        /// Physical destination address.
+        dest: u64,
+        /// Number of bytes to zero.
+        len: u64,
+    },
+}
+
+/// The entry point the bootloader jumps to, with the corresponding machine
+/// state as defined by the spec.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EntryPoint {
+    /// Regular entry with the machine state of the [`HeaderTagISA`].
+    ///
+    /// [`HeaderTagISA`]: crate::HeaderTagISA
+    Standard(u64),
+    /// EFI i386 entry with active boot services.
+    Efi32(u64),
+    /// EFI amd64 entry with active boot services.
+    Efi64(u64),
+}
+
+impl EntryPoint {
+    /// Returns the physical address of the entry point.
+    #[must_use]
+    pub const fn addr(&self) -> u64 {
+        match self {
+            Self::Standard(addr) | Self::Efi32(addr) | Self::Efi64(addr) => *addr,
+        }
+    }
+}
+
+/// Errors that may occur when computing a [`LoadPlan`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+pub enum LoadPlanError {
+    /// The [`Header`] doesn't lie within the image.
+    #[error("the header is not located in the image")]
+    HeaderNotInImage,
+    /// The image is neither a valid ELF file nor has an [`AddressHeaderTag`].
+    ///
+    /// [`AddressHeaderTag`]: crate::AddressHeaderTag
+    #[error("the image is not a valid ELF file and has no address tag")]
+    InvalidElf,
+    /// The [`AddressHeaderTag`] describes an inconsistent layout.
+    ///
+    /// [`AddressHeaderTag`]: crate::AddressHeaderTag
+    #[error("the address tag describes an invalid layout")]
+    InvalidAddressTag,
+    /// A segment has more file bytes than memory bytes or exceeds the image.
+    #[error("a segment exceeds the image or its memory size")]
+    InvalidSegment,
+    /// The image has nothing to load.
+    #[error("the image has no loadable segments")]
+    NoLoadableSegments,
+    /// There is no entry point, e.g., an a.out kludge image without an
+    /// [`EntryAddressHeaderTag`].
+    ///
+    /// [`EntryAddressHeaderTag`]: crate::EntryAddressHeaderTag
+    #[error("the image has no entry point")]
+    NoEntryPoint,
+    /// No suitable address for a relocatable image was found.
+    #[error("no suitable memory for a relocatable image of 0x{0:x} bytes")]
+    NoSuitableMemory(u64),
+    /// The given physical memory range isn't fully available.
+    #[error("the range at 0x{addr:x} with 0x{len:x} bytes is not available memory")]
+    NotInAvailableMemory {
+        /// Physical start address.
+        addr: u64,
+        /// Length in bytes.
+        len: u64,
+    },
+}
+
+/// A segment of the image at its link address.
+#[derive(Clone, Debug)]
+struct Segment {
+    src: Range<usize>,
+    dest: u64,
+    mem_size: u64,
+}
+
+/// Plan for loading a kernel image according to the Multiboot2 spec.
+///
+/// It is computed on the host without touching memory: it lists the copy and
+/// zero operations, the entry point, and the load base to report in the
+/// [`ImageLoadPhysAddrTag`]. The bootloader executes the operations in order.
+///
+/// The rules of the spec are applied as follows:
+/// - If an [`AddressHeaderTag`] is present, the image is loaded according to
+///   it (a.out kludge). Otherwise, the `PT_LOAD` segments of the ELF file are
+///   loaded to their physical addresses.
+/// - If a [`RelocatableHeaderTag`] is present, the image is moved as a whole
+///   within `min_addr` and `max_addr`, honoring the alignment and the
+///   [`RelocatableHeaderTagPreference`]. Without a preference, the link
+///   address is kept if possible. The entry point moves along.
+/// - With EFI [`Firmware`], active boot services requested by an
+///   [`EfiBootServiceHeaderTag`], and a matching EFI entry tag, the EFI entry
+///   is used. Otherwise, the [`EntryAddressHeaderTag`] overrides the ELF entry.
+///
+/// Each destination range must lie within a single available area of the
+/// memory map.
+///
+/// [`ImageLoadPhysAddrTag`]: multiboot2::ImageLoadPhysAddrTag
+/// [`AddressHeaderTag`]: crate::AddressHeaderTag
+/// [`EfiBootServiceHeaderTag`]: crate::EfiBootServiceHeaderTag
+/// [`EntryAddressHeaderTag`]: crate::EntryAddressHeaderTag
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LoadPlan {
+    ops: Vec<LoadOp>,
+    entry: EntryPoint,
+    load_base: u64,
+    boot_services_active: bool,
+}
+
+impl LoadPlan {
+    /// Computes the plan for the given image.
+    ///
+    /// `header` must have been parsed from `image`, for example, with
+    /// [`Header::find_header`]. `memory_map` describes the physical memory of
+    /// the machine.
+    pub fn new(
+        image: &[u8],
+        header: &Header,
+        memory_map: &[MemoryArea],
+        firmware: Firmware,
+    ) -> Result<Self, LoadPlanError> {
+        let header_offset = (header.as_ptr() as usize)
+            .checked_sub(image.as_ptr() as usize)
+            .filter(|&offset| offset + header.length() as usize <= image.len())
+            .ok_or(LoadPlanError::HeaderNotInImage)?;
+
+        let (segments, elf_entry) = if header.address_tag().is_some() {
+            (Self::aout_segments(image, header, header_offset)?, None)
+        } else {
+            let (segments, entry) = Self::elf_segments(image)?;
+            (segments, Some(entry))
+        };
+
+        let link_base = segments
+            .iter()
+            .map(|seg| seg.dest)
+            .min()
+            .ok_or(LoadPlanError::NoLoadableSegments)?;
+        let link_end = segments
+            .iter()
+            .map(|seg| seg.dest + seg.mem_size)
+            .max()
+            .unwrap_or(link_base);
+
+        let load_base = match header.relocatable_tag() {
+            Some(tag) => Self::relocate(tag, link_base, link_end - link_base, memory_map)?,
+            None => link_base,
+        };
+        let shift = |addr: u64| addr.wrapping_add(load_base.wrapping_sub(link_base));
+
+        let mut ops = Vec::new();
+        for seg in segments {
+            let dest = shift(seg.dest);
+            Self::check_available(memory_map, dest, seg.mem_size)?;
+            let file_size = seg.src.len() as u64;
+            if file_size > 0 {
+                ops.push(LoadOp::Copy { src: seg.src, dest });
+            }
+            if seg.mem_size > file_size {
+                ops.push(LoadOp::Zero {
+                    dest: dest + file_size,
+                    len: seg.mem_size - file_size,
+                });
+            }
+        }
+
+        // Like GRUB, keep the boot services only if the kernel also has an
+        // EFI entry point for the firmware.
+        let keep_boot_services = header.efi_boot_services_tag().is_some();
+        let efi_entry = match firmware {
+            Firmware::Efi32 if keep_boot_services => header
+                .entry_address_efi32_tag()
+                .map(|tag| EntryPoint::Efi32(shift(tag.entry_addr().into()))),
+            Firmware::Efi64 if keep_boot_services => header
+                .entry_address_efi64_tag()
+                .map(|tag| EntryPoint::Efi64(shift(tag.entry_addr().into()))),
+            _ => None,
+        };
+        let boot_services_active = efi_entry.is_some();
+        let entry = efi_entry
+            .or_else(|| {
+                header
+                    .entry_address_tag()
+                    .map(|tag| u64::from(tag.entry_addr()))
+                    .or(elf_entry)
+                    .map(|addr| EntryPoint::Standard(shift(addr)))
+            })
+            .ok_or(LoadPlanError::NoEntryPoint)?;
+
+        Ok(Self {
+            ops,
+            entry,
+            load_base,
+            boot_services_active,
+        })
+    }
+
+    /// Returns the segments described by the [`AddressHeaderTag`].
+    ///
+    /// [`AddressHeaderTag`]: crate::AddressHeaderTag
+    fn aout_segments(
+        image: &[u8],
+        header: &Header,
+        header_offset: usize,
+    ) -> Result<Vec<Segment>, LoadPlanError> {
+        let tag = header
+            .address_tag()
+            .ok_or(LoadPlanError::InvalidAddressTag)?;
+        let header_addr = u64::from(tag.header_addr());
+        let (file_start, load_addr) = if tag.load_addr() == u32::MAX {
+            // Special value: load the image from its beginning.
+            let load_addr = header_addr
+                .checked_sub(header_offset as u64)
+                .ok_or(LoadPlanError::InvalidAddressTag)?;
+            (0, load_addr)
+        } else {
+            let load_addr = u64::from(tag.load_addr());
+            let file_start = header_addr
+                .checked_sub(load_addr)
+                .and_then(|delta| (header_offset as u64).checked_sub(delta))
+                .ok_or(LoadPlanError::InvalidAddressTag)?;
+            (file_start as usize, load_addr)
+        };
+        let load_len = match tag.load_end_addr() {
+            0 => (image.len() - file_start) as u64,
+            end => u64::from(end)
+                .checked_sub(load_addr)
+                .ok_or(LoadPlanError::InvalidAddressTag)?,
+        };
+        let file_end = file_start
+            .checked_add(load_len as usize)
+            .filter(|&end| end <= image.len())
+            .ok_or(LoadPlanError::InvalidSegment)?;
+        let mem_size = match tag.bss_end_addr() {
+            0 => load_len,
+            end => u64::from(end)
+                .checked_sub(load_addr)
+                .filter(|&size| size >= load_len)
+                .ok_or(LoadPlanError::InvalidAddressTag)?,
+        };
+        Ok(alloc::vec![Segment {
+            src: file_start..file_end,
+            dest: load_addr,
+            mem_size,
+        }])
+    }
+
+    /// Returns the `PT_LOAD` segments and the entry point of the ELF file.
+    fn elf_segments(image: &[u8]) -> Result<(Vec<Segment>, u64), LoadPlanError> {
+        let elf =
+            ElfBytes::<AnyEndian>::minimal_parse(image).map_err(|_| LoadPlanError::InvalidElf)?;
+        let phdrs = elf.segments().ok_or(LoadPlanError::NoLoadableSegments)?;
+        let segments = phdrs
+            .iter()
+            .filter(|ph| ph.p_type == PT_LOAD)
+            .map(|ph| {
+                let start = usize::try_from(ph.p_offset).ok()?;
+                let end = start.checked_add(usize::try_from(ph.p_filesz).ok()?)?;
+                ph.p_paddr.checked_add(ph.p_memsz)?;
+                (end <= image.len() && ph.p_memsz >= ph.p_filesz).then_some(Segment {
+                    src: start..end,
+                    dest: ph.p_paddr,
+                    mem_size: ph.p_memsz,
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(LoadPlanError::InvalidSegment)?;
+        Ok((segments, elf.ehdr.e_entry))
+    }
+
+    /// Selects the load base of a relocatable image of `size` bytes.
+    fn relocate(
+        tag: &RelocatableHeaderTag,
+        link_base: u64,
+        size: u64,
+        memory_map: &[MemoryArea],
+    ) -> Result<u64, LoadPlanError> {
+        let align = u64::from(tag.align()).max(1);
+        let min = u64::from(tag.min_addr());
+        let max_end = u64::from(tag.max_addr());
+        // Range of possible aligned load bases per available area.
+        let candidates = memory_map
+            .iter()
+            .filter(|area| area.typ() == MemoryAreaType::Available)
+            .filter_map(|area| {
+                let lowest = area
+                    .start_address()
+                    .max(min)
+                    .checked_next_multiple_of(align)?;
+                let end = area.end_address().min(max_end);
+                let highest = end.checked_sub(size)? / align * align;
+                (lowest <= highest).then_some((lowest, highest))
+            });
+
+        let fits = |base: u64| {
+            base % align == 0 && candidates.clone().any(|(lo, hi)| lo <= base && base <= hi)
+        };
+        // Unknown preferences are treated as no preference.
+        let preference = RelocatableHeaderTagPreference::try_from(tag.preference())
+            .unwrap_or(RelocatableHeaderTagPreference::None);
+        let base = match preference {
+            RelocatableHeaderTagPreference::None if fits(link_base) => Some(link_base),
+            RelocatableHeaderTagPreference::None | RelocatableHeaderTagPreference::Low => {
+                candidates.clone().map(|(lo, _)| lo).min()
+            }
+            RelocatableHeaderTagPreference::High => candidates.clone().map(|(_, hi)| hi).max(),
+        };
+        base.ok_or(LoadPlanError::NoSuitableMemory(size))
+    }
+
+    /// Checks that the range lies within a single available memory area.
+    fn check_available(
+        memory_map: &[MemoryArea],
+        addr: u64,
+        len: u64,
+    ) -> Result<(), LoadPlanError> {
+        let end = addr
+            .checked_add(len)
+            .ok_or(LoadPlanError::NotInAvailableMemory { addr, len })?;
+        memory_map
+            .iter()
+            .filter(|area| area.typ() == MemoryAreaType::Available)
+            .any(|area| area.start_address() <= addr && end <= area.end_address())
+            .then_some(())
+            .ok_or(LoadPlanError::NotInAvailableMemory { addr, len })
+    }
+
+    /// Returns the copy and zero operations in the order to execute them.
+    #[must_use]
+    pub fn ops(&self) -> &[LoadOp] {
+        &self.ops
+    }
+
+    /// Returns the entry point.
+    #[must_use]
+    pub const fn entry(&self) -> EntryPoint {
+        self.entry
+    }
+
+    /// Returns the physical address of the lowest loaded byte, as reported in
+    /// the [`ImageLoadPhysAddrTag`].
+    ///
+    /// [`ImageLoadPhysAddrTag`]: multiboot2::ImageLoadPhysAddrTag
+    #[must_use]
+    pub const fn load_base(&self) -> u64 {
+        self.load_base
+    }
+
+    /// Returns whether the bootloader must keep the EFI boot services active,
+    /// i.e., not call `ExitBootServices`.
+    ///
+    /// This requires an [`EfiBootServiceHeaderTag`] and an EFI entry point
+    /// matching the firmware, see [`EntryPoint::Efi32`] and
+    /// [`EntryPoint::Efi64`].
+    ///
+    /// [`EfiBootServiceHeaderTag`]: crate::EfiBootServiceHeaderTag
+    #[must_use]
+    pub const fn boot_services_active(&self) -> bool {
+        self.boot_services_active
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "builder")]
+mod tests {
+    use super::*;
+    use crate::{
+        AddressHeaderTag, Builder, EfiBootServiceHeaderTag, EntryAddressHeaderTag,
+        EntryEfi64HeaderTag, HeaderTagFlag, HeaderTagISA,
+    };
+    use multiboot2_common::MaybeDynSized;
+    use multiboot2_common::test_utils::AlignedBytes;
+
+    fn memory() -> [MemoryArea; 2] {
+        [
+            MemoryArea::new(0, 0x9f000, MemoryAreaType::Available),
+            MemoryArea::new(0x100000, 0x1000000, MemoryAreaType::Available),
+        ]
+    }
+
+    /// Writes a minimal ELF32 file with one `PT_LOAD` segment of 0x100 file
+    /// bytes and 0x200 memory bytes at `paddr`. The Multiboot2 header is
+    /// expected at offset 0x100.
+    fn write_elf(image: &mut [u8], paddr: u32, entry: u32) {
+        image[..4].copy_from_slice(b"\x7fELF");
+        image[4..7].copy_from_slice(&[1, 1, 1]);
+        let fields: [(usize, &[u8]); 12] = [
+            (16, &2_u16.to_le_bytes()),
+            (18, &3_u16.to_le_bytes()),
+            (20, &1_u32.to_le_bytes()),
+            (24, &entry.to_le_bytes()),
+            (28, &52_u32.to_le_bytes()),
+            (40, &52_u16.to_le_bytes()),
+            (42, &32_u16.to_le_bytes()),
+            (44, &1_u16.to_le_bytes()),
+            (46, &40_u16.to_le_bytes()),
+            // Program header
+            (52, &PT_LOAD.to_le_bytes()),
+            (52 + 12, &paddr.to_le_bytes()),
+            (52 + 16, &[0x00, 0x01, 0, 0, 0x00, 0x02, 0, 0]),
+        ];
+        for (offset, bytes) in fields {
+            image[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+    }
+
+    fn write_header(image: &mut [u8], builder: Builder) -> usize {
+        let header = builder.build();
+        let bytes = header.as_bytes();
+        image[0x100..0x100 + bytes.len()].copy_from_slice(&bytes);
+        0x100
+    }
+
+    #[test]
+    fn elf_plan() {
+        let mut image = AlignedBytes::new([0; 0x200]);
+        write_elf(&mut image.0, 0x200000, 0x200010);
+        write_header(
+            &mut image.0,
+            Builder::new(HeaderTagISA::I386)
+                .efi_bs_tag(EfiBootServiceHeaderTag::new(HeaderTagFlag::Optional))
+                .efi_64_tag(EntryEfi64HeaderTag::new(HeaderTagFlag::Optional, 0x200020)),
+        );
+        let (header, _) = Header::find_header(&image.0).unwrap();
+
+        let plan = LoadPlan::new(&image.0, &header, &memory(), Firmware::Bios).unwrap();
+        assert_eq!(
+            plan.ops(),
+            [
+                LoadOp::Copy {
+                    src: 0..0x100,
+                    dest: 0x200000
+                },
+                LoadOp::Zero {
+                    dest: 0x200100,
+                    len: 0x100
+                }
+            ]
+        );
+        assert_eq!(plan.entry(), EntryPoint::Standard(0x200010));
+        assert_eq!(plan.load_base(), 0x200000);
+        assert!(!plan.boot_services_active());
+
+        let plan = LoadPlan::new(&image.0, &header, &memory(), Firmware::Efi64).unwrap();
+        assert_eq!(plan.entry(), EntryPoint::Efi64(0x200020));
+        assert!(plan.boot_services_active());
+
+        // No EFI entry point for 32-bit firmware: The kernel is entered with
+        // the standard entry point after exiting the boot services.
+        let plan = LoadPlan::new(&image.0, &header, &memory(), Firmware::Efi32).unwrap();
+        assert_eq!(plan.entry(), EntryPoint::Standard(0x200010));
+        assert!(!plan.boot_services_active());
+
+        assert_eq!(
+            LoadPlan::new(&image.0, &header, &memory()[..1], Firmware::Bios),
+            Err(LoadPlanError::NotInAvailableMemory {
+                addr: 0x200000,
+                len: 0x200
+            })
+        );
+    }
+
+    #[test]
+    fn relocatable_plan() {
+        let mut image = AlignedBytes::new([0; 0x200]);
+        write_elf(&mut image.0, 0x200000, 0x200010);
+        let reloc = |preference| {
+            Builder::new(HeaderTagISA::I386).relocatable_tag(RelocatableHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x1000,
+                0x800000,
+                0x1000,
+                preference,
+            ))
+        };
+
+        write_header(&mut image.0, reloc(RelocatableHeaderTagPreference::None));
+        let (header, _) = Header::find_header(&image.0).unwrap();
+        let plan = LoadPlan::new(&image.0, &header, &memory(), Firmware::Bios).unwrap();
+        assert_eq!(plan.load_base(), 0x200000);
+
+        write_header(&mut image.0, reloc(RelocatableHeaderTagPreference::Low));
+        let (header, _) = Header::find_header(&image.0).unwrap();
+        let plan = LoadPlan::new(&image.0, &header, &memory(), Firmware::Bios).unwrap();
+        assert_eq!(plan.load_base(), 0x1000);
+        assert_eq!(plan.entry(), EntryPoint::Standard(0x1010));
+
+        write_header(&mut image.0, reloc(RelocatableHeaderTagPreference::High));
+        let (header, _) = Header::find_header(&image.0).unwrap();
+        let plan = LoadPlan::new(&image.0, &header, &memory(), Firmware::Bios).unwrap();
+        assert_eq!(plan.load_base(), 0x7ff000);
+        assert_eq!(
+            plan.ops()[0],
+            LoadOp::Copy {
+                src: 0..0x100,
+                dest: 0x7ff000
+            }
+        );
+    }
+
+    #[test]
+    fn aout_kludge_plan() {
+        let mut image = AlignedBytes::new([0; 0x200]);
+        let offset = write_header(
+            &mut image.0,
+            Builder::new(HeaderTagISA::I386)
+                .address_tag(AddressHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    0x100100,
+                    0x100000,
+                    0x100180,
+                    0x101000,
+                ))
+                .entry_tag(EntryAddressHeaderTag::new(
+                    HeaderTagFlag::Required,
+                    0x100000,
+                )),
+        );
+        assert_eq!(offset, 0x100);
+        let (header, _) = Header::find_header(&image.0).unwrap();
+
+        let plan = LoadPlan::new(&image.0, &header, &memory(), Firmware::Bios).unwrap();
+        assert_eq!(
+            plan.ops(),
+            [
+                LoadOp::Copy {
+                    src: 0..0x180,
+                    dest: 0x100000
+                },
+                LoadOp::Zero {
+                    dest: 0x100180,
+                    len: 0xe80
+                }
+            ]
+        );
+        assert_eq!(plan.entry(), EntryPoint::Standard(0x100000));
+    }
+}
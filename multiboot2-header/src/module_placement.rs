@@ -0,0 +1,264 @@
+//! Module for [`ModulePlanner`].
+
+use crate::Header;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::Range;
+use multiboot2::{
+    MemoryArea, MemoryAreaType, MemoryAreaTypeId, MemoryMapTag, ModuleTag, NewTagError,
+};
+use thiserror::Error;
+
+/// Alignment of modules if the [`Header`] contains a [`ModuleAlignHeaderTag`].
+///
+/// [`ModuleAlignHeaderTag`]: crate::ModuleAlignHeaderTag
+pub const MODULE_PAGE_ALIGNMENT: u64 = 0x1000;
+
+/// Errors that may occur when placing a module with [`ModulePlanner::place`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+pub enum ModulePlacementError {
+    /// There is no free memory below 4 GiB for a module of the given size.
+    #[error("no free memory for a module of 0x{0:x} bytes")]
+    NoSpace(u64),
+    /// The [`ModuleTag`] can't be created, e.g., because the module is empty.
+    #[error(transparent)]
+    Tag(#[from] NewTagError),
+}
+
+/// Allocator-style planner that places boot modules in free physical memory.
+///
+/// Modules are placed at the lowest free address of an available area of the
+/// memory map, without overlapping reserved ranges, such as the kernel or the
+/// boot information, and other modules. If the [`Header`] contains a
+/// [`ModuleAlignHeaderTag`], modules are page-aligned. As [`ModuleTag`]s only
+/// hold 32-bit addresses, all modules are placed below 4 GiB.
+///
+/// The planner only computes addresses; copying the modules is up to the
+/// bootloader.
+///
+/// [`ModuleAlignHeaderTag`]: crate::ModuleAlignHeaderTag
+#[derive(Debug)]
+pub struct ModulePlanner<'a> {
+    memory_map: &'a [MemoryArea],
+    align: u64,
+    occupied: Vec<Range<u64>>,
+    modules: Vec<Range<u64>>,
+    module_tags: Vec<Box<ModuleTag>>,
+}
+
+impl<'a> ModulePlanner<'a> {
+    /// Creates a new planner for the given memory map and kernel header.
+    #[must_use]
+    pub fn new(memory_map: &'a [MemoryArea], header: &Header) -> Self {
+        let align = if header.module_align_tag().is_some() {
+            MODULE_PAGE_ALIGNMENT
+        } else {
+            1
+        };
+        Self {
+            memory_map,
+            align,
+            occupied: Vec::new(),
+            modules: Vec::new(),
+            module_tags: Vec::new(),
+        }
+    }
+
+    /// Marks the physical range as occupied, e.g., by the kernel or the boot
+    /// information, so that no module is placed there.
+    pub fn reserve(&mut self, range: Range<u64>) {
+        self.occupied.push(range);
+    }
+
+    /// Places a module of `size` bytes and returns its physical range.
+    ///
+    /// A [`ModuleTag`] with the given `cmdline` is created for it. Modules
+    /// must not be empty. If an error is returned, the state of the planner
+    /// is unchanged.
+    pub fn place(&mut self, size: u64, cmdline: &str) -> Result<Range<u64>, ModulePlacementError> {
+        let range = self
+            .memory_map
+            .iter()
+            .filter(|area| area.typ() == MemoryAreaType::Available)
+            .find_map(|area| self.find_free(area, size))
+            .ok_or(ModulePlacementError::NoSpace(size))?;
+        // The range is below 4 GiB, as ensured by `find_free`.
+        let tag = ModuleTag::try_new(range.start as u32, range.end as u32, cmdline)?;
+        self.occupied.push(range.clone());
+        self.modules.push(range.clone());
+        self.module_tags.push(tag);
+        Ok(range)
+    }
+
+    /// Returns the lowest free and aligned range of `size` bytes in the area.
+    fn find_free(&self, area: &MemoryArea, size: u64) -> Option<Range<u64>> {
+        let area_end = area.end_address().min(u64::from(u32::MAX));
+        let mut start = area.start_address().checked_next_multiple_of(self.align)?;
+        loop {
+            let end = start.checked_add(size).filter(|&end| end <= area_end)?;
+            match self
+                .occupied
+                .iter()
+                .filter(|occ| occ.start < end && start < occ.end)
+                .map(|occ| occ.end)
+                .max()
+            {
+                Some(occ_end) => start = occ_end.checked_next_multiple_of(self.align)?,
+                None => return Some(start..end),
+            }
+        }
+    }
+
+    /// Returns the physical ranges of all placed modules.
+    #[must_use]
+    pub fn modules(&self) -> &[Range<u64>] {
+        &self.modules
+    }
+
+    /// Returns the [`ModuleTag`]s of all placed modules.
+    #[must_use]
+    pub fn module_tags(&self) -> &[Box<ModuleTag>] {
+        &self.module_tags
+    }
+
+    /// Consumes the planner and returns the [`ModuleTag`]s of all placed
+    /// modules, e.g., for [`multiboot2::Builder::add_module`].
+    #[must_use]
+    pub fn into_module_tags(self) -> Vec<Box<ModuleTag>> {
+        self.module_tags
+    }
+
+    /// Returns a [`MemoryMapTag`] for the memory map in which the ranges of
+    /// the placed modules are split off the available areas and marked with
+    /// `module_typ`.
+    ///
+    /// The spec allows modules to remain within available memory, so passing
+    /// [`MemoryAreaType::Available`] keeps the original memory map.
+    #[must_use]
+    pub fn memory_map_tag(&self, module_typ: impl Into<MemoryAreaTypeId>) -> Box<MemoryMapTag> {
+        let module_typ = module_typ.into();
+        let mut modules = self.modules.clone();
+        modules.sort_unstable_by_key(|range| range.start);
+
+        let mut areas = Vec::new();
+        for area in self.memory_map {
+            if area.typ() != MemoryAreaType::Available {
+                areas.push(*area);
+                continue;
+            }
+            let mut cursor = area.start_address();
+            for module in modules
+                .iter()
+                .filter(|module| area.start_address() <= module.start)
+                .filter(|module| module.end <= area.end_address() && !module.is_empty())
+            {
+                if cursor < module.start {
+                    areas.push(MemoryArea::new(cursor, module.start - cursor, area.typ()));
+                }
+                areas.push(MemoryArea::new(
+                    module.start,
+                    module.end - module.start,
+                    module_typ,
+                ));
+                cursor = module.end;
+            }
+            if cursor < area.end_address() {
+                areas.push(MemoryArea::new(
+                    cursor,
+                    area.end_address() - cursor,
+                    area.typ(),
+                ));
+            }
+        }
+        MemoryMapTag::new(&areas)
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{Builder, HeaderTagFlag, HeaderTagISA, ModuleAlignHeaderTag};
+    use multiboot2_common::MaybeDynSized;
+
+    fn memory() -> [MemoryArea; 3] {
+        [
+            MemoryArea::new(0x1000, 0x9e000, MemoryAreaType::Available),
+            MemoryArea::new(0x9f000, 0x61000, MemoryAreaType::Reserved),
+            MemoryArea::new(0x100000, 0x100000, MemoryAreaType::Available),
+        ]
+    }
+
+    #[test]
+    fn placement() {
+        let header = Builder::new(HeaderTagISA::I386)
+            .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required))
+            .build();
+        // SAFETY: The builder creates a valid header.
+        let header = unsafe { Header::load(header.as_bytes().as_ptr().cast()) }.unwrap();
+        let memory = memory();
+        let mut planner = ModulePlanner::new(&memory, &header);
+        planner.reserve(0x1000..0x2000);
+
+        assert_eq!(planner.place(0x10, "a"), Ok(0x2000..0x2010));
+        assert_eq!(planner.place(0x9d000, "b"), Ok(0x100000..0x19d000));
+        assert_eq!(planner.place(0x20, "c"), Ok(0x3000..0x3020));
+        assert_eq!(
+            planner.place(0x100000, "d"),
+            Err(ModulePlacementError::NoSpace(0x100000))
+        );
+
+        let tags = planner.module_tags();
+        assert_eq!(tags.len(), 3);
+        assert_eq!(tags[1].start_address(), 0x100000);
+        assert_eq!(tags[1].end_address(), 0x19d000);
+        assert_eq!(tags[1].cmdline(), Ok("b"));
+
+        let mmap = planner.memory_map_tag(MemoryAreaType::Reserved);
+        assert_eq!(
+            mmap.memory_areas(),
+            [
+                MemoryArea::new(0x1000, 0x1000, MemoryAreaType::Available),
+                MemoryArea::new(0x2000, 0x10, MemoryAreaType::Reserved),
+                MemoryArea::new(0x2010, 0xff0, MemoryAreaType::Available),
+                MemoryArea::new(0x3000, 0x20, MemoryAreaType::Reserved),
+                MemoryArea::new(0x3020, 0x9bfe0, MemoryAreaType::Available),
+                MemoryArea::new(0x9f000, 0x61000, MemoryAreaType::Reserved),
+                MemoryArea::new(0x100000, 0x9d000, MemoryAreaType::Reserved),
+                MemoryArea::new(0x19d000, 0x63000, MemoryAreaType::Available),
+            ]
+        );
+    }
+
+    #[test]
+    fn placement_unaligned() {
+        let header = Builder::new(HeaderTagISA::I386).build();
+        // SAFETY: The builder creates a valid header.
+        let header = unsafe { Header::load(header.as_bytes().as_ptr().cast()) }.unwrap();
+        let memory = memory();
+        let mut planner = ModulePlanner::new(&memory, &header);
+
+        assert_eq!(planner.place(0x10, "a"), Ok(0x1000..0x1010));
+        assert_eq!(planner.place(0x10, "b"), Ok(0x1010..0x1020));
+        assert_eq!(planner.modules(), [0x1000..0x1010, 0x1010..0x1020]);
+    }
+
+    #[test]
+    fn placement_empty() {
+        let header = Builder::new(HeaderTagISA::I386).build();
+        // SAFETY: The builder creates a valid header.
+        let header = unsafe { Header::load(header.as_bytes().as_ptr().cast()) }.unwrap();
+        let memory = memory();
+        let mut planner = ModulePlanner::new(&memory, &header);
+
+        assert_eq!(
+            planner.place(0, "a"),
+            Err(ModulePlacementError::Tag(NewTagError::EmptyModule {
+                start: 0x1000,
+                end: 0x1000
+            }))
+        );
+        assert_eq!(planner.modules(), []);
+        assert!(planner.module_tags().is_empty());
+        assert_eq!(planner.place(0x10, "b"), Ok(0x1000..0x1010));
+    }
+}
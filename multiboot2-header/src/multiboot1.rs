@@ -0,0 +1,438 @@
+//! Module for [`Multiboot1Header`] and [`find_any_header`].
+
+use crate::{Header, HeaderScratch, LoadError};
+use bitflags::bitflags;
+use thiserror::Error;
+
+/// Magic value for a [`Multiboot1Header`], as defined by the Multiboot1 spec.
+pub const MULTIBOOT1_MAGIC: u32 = 0x1badb002;
+/// Range from the beginning of an image in which bootloaders will search for a
+/// Multiboot1 header.
+pub const MULTIBOOT1_HEADER_SEARCH_LIMIT: usize = 8192;
+/// The alignment of a Multiboot1 header within the image.
+pub const MULTIBOOT1_ALIGNMENT: usize = 4;
+
+/// Size of the mandatory `magic`, `flags`, and `checksum` fields.
+const MANDATORY_SIZE: usize = 12;
+/// Offset of the video mode fields.
+const VIDEO_OFFSET: usize = 32;
+/// Size of the header including the video mode fields.
+const VIDEO_END: usize = 48;
+
+bitflags! {
+    /// Flags of a [`Multiboot1Header`].
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct Multiboot1HeaderFlags: u32 {
+        /// Boot modules must be page-aligned.
+        const PAGE_ALIGN = 1 << 0;
+        /// The boot information must contain memory information.
+        const MEMORY_INFO = 1 << 1;
+        /// The boot information must contain the video mode table and the
+        /// video mode fields of the header are valid.
+        const VIDEO_MODE = 1 << 2;
+        /// The address fields of the header are valid ("a.out kludge").
+        const ADDRESS = 1 << 16;
+    }
+}
+
+/// Errors that may occur when parsing a [`Multiboot1Header`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+pub enum Multiboot1LoadError {
+    /// No [`MULTIBOOT1_MAGIC`] with a valid checksum was found.
+    #[error("header does not contain expected magic value")]
+    MagicNotFound,
+    /// The provided checksum does not match the expected value.
+    #[error("checksum 0x{0:X} does not match expected value 0x{1:x}")]
+    ChecksumMismatch(u32 /* is */, u32 /* expected */),
+    /// The fields announced by the flags exceed the buffer or the search
+    /// limit.
+    #[error("header with 0x{0:x} bytes exceeds the available 0x{1:x} bytes")]
+    Truncated(usize /* required */, usize /* available */),
+}
+
+/// The address fields of a [`Multiboot1Header`] ("a.out kludge").
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Multiboot1AddressFields {
+    /// Physical address of the beginning of the header.
+    pub header_addr: u32,
+    /// Physical address of the beginning of the text segment.
+    pub load_addr: u32,
+    /// Physical address of the end of the data segment, or zero if the whole
+    /// image is loaded.
+    pub load_end_addr: u32,
+    /// Physical address of the end of the bss segment, or zero if there is
+    /// none.
+    pub bss_end_addr: u32,
+    /// Physical address to which the bootloader jumps.
+    pub entry_addr: u32,
+}
+
+/// The preferred video mode of a [`Multiboot1Header`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Multiboot1VideoMode {
+    /// `0` for a linear graphics mode, `1` for EGA text mode.
+    pub mode_type: u32,
+    /// Number of columns, or zero for no preference.
+    pub width: u32,
+    /// Number of lines, or zero for no preference.
+    pub height: u32,
+    /// Bits per pixel in graphics mode, or zero for no preference.
+    pub depth: u32,
+}
+
+/// A parsed Multiboot1 header.
+///
+/// Unlike [`Header`], the header is copied from the buffer, so the buffer
+/// doesn't need to be aligned.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Multiboot1Header {
+    flags: Multiboot1HeaderFlags,
+    checksum: u32,
+    address: Option<Multiboot1AddressFields>,
+    video_mode: Option<Multiboot1VideoMode>,
+}
+
+impl Multiboot1Header {
+    /// Parses a header at the beginning of `bytes`.
+    pub fn parse(bytes: &[u8]) -> Result<Self, Multiboot1LoadError> {
+        let read_u32 = |idx: usize| {
+            bytes
+                .get(idx..idx + 4)
+                .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+                .ok_or(Multiboot1LoadError::Truncated(idx + 4, bytes.len()))
+        };
+
+        if read_u32(0)? != MULTIBOOT1_MAGIC {
+            return Err(Multiboot1LoadError::MagicNotFound);
+        }
+        let flags = read_u32(4)?;
+        let checksum = read_u32(8)?;
+        let expected = Self::calc_checksum(flags);
+        if checksum != expected {
+            return Err(Multiboot1LoadError::ChecksumMismatch(checksum, expected));
+        }
+
+        let flags = Multiboot1HeaderFlags::from_bits_retain(flags);
+        let len = if flags.contains(Multiboot1HeaderFlags::VIDEO_MODE) {
+            VIDEO_END
+        } else if flags.contains(Multiboot1HeaderFlags::ADDRESS) {
+            VIDEO_OFFSET
+        } else {
+            MANDATORY_SIZE
+        };
+        if bytes.len() < len {
+            return Err(Multiboot1LoadError::Truncated(len, bytes.len()));
+        }
+
+        let address =
+            flags
+                .contains(Multiboot1HeaderFlags::ADDRESS)
+                .then(|| Multiboot1AddressFields {
+                    header_addr: read_u32(12).unwrap(),
+                    load_addr: read_u32(16).unwrap(),
+                    load_end_addr: read_u32(20).unwrap(),
+                    bss_end_addr: read_u32(24).unwrap(),
+                    entry_addr: read_u32(28).unwrap(),
+                });
+        let video_mode =
+            flags
+                .contains(Multiboot1HeaderFlags::VIDEO_MODE)
+                .then(|| Multiboot1VideoMode {
+                    mode_type: read_u32(VIDEO_OFFSET).unwrap(),
+                    width: read_u32(VIDEO_OFFSET + 4).unwrap(),
+                    height: read_u32(VIDEO_OFFSET + 8).unwrap(),
+                    depth: read_u32(VIDEO_OFFSET + 12).unwrap(),
+                });
+        Ok(Self {
+            flags,
+            checksum,
+            address,
+            video_mode,
+        })
+    }
+
+    /// Tries finding a Multiboot1 header in the first
+    /// [`MULTIBOOT1_HEADER_SEARCH_LIMIT`] bytes of an image.
+    ///
+    /// Like bootloaders, this searches for the first [`MULTIBOOT1_ALIGNMENT`]
+    /// aligned [`MULTIBOOT1_MAGIC`] followed by a valid checksum. The buffer
+    /// doesn't need to be aligned.
+    ///
+    /// On success, it returns the parsed header and an index into the original
+    /// buffer pointing to where the header starts.
+    pub fn find_header(buffer: &[u8]) -> Result<(Self, usize), Multiboot1LoadError> {
+        let search_len = buffer.len().min(MULTIBOOT1_HEADER_SEARCH_LIMIT);
+        let buffer = &buffer[..search_len];
+        let offset = (0..search_len.saturating_sub(MANDATORY_SIZE - 1))
+            .step_by(MULTIBOOT1_ALIGNMENT)
+            .find(|&idx| {
+                let read_u32 =
+                    |i: usize| u32::from_le_bytes(buffer[idx + i..idx + i + 4].try_into().unwrap());
+                read_u32(0) == MULTIBOOT1_MAGIC && read_u32(8) == Self::calc_checksum(read_u32(4))
+            })
+            .ok_or(Multiboot1LoadError::MagicNotFound)?;
+        let header = Self::parse(&buffer[offset..])?;
+        Ok((header, offset))
+    }
+
+    /// Calculates the checksum as described in the spec.
+    #[must_use]
+    pub const fn calc_checksum(flags: u32) -> u32 {
+        0_u32.wrapping_sub(MULTIBOOT1_MAGIC).wrapping_sub(flags)
+    }
+
+    /// Returns the flags.
+    #[must_use]
+    pub const fn flags(&self) -> Multiboot1HeaderFlags {
+        self.flags
+    }
+
+    /// Returns the checksum.
+    #[must_use]
+    pub const fn checksum(&self) -> u32 {
+        self.checksum
+    }
+
+    /// Returns the address fields, if [`Multiboot1HeaderFlags::ADDRESS`] is
+    /// set.
+    #[must_use]
+    pub const fn address(&self) -> Option<&Multiboot1AddressFields> {
+        self.address.as_ref()
+    }
+
+    /// Returns the preferred video mode, if
+    /// [`Multiboot1HeaderFlags::VIDEO_MODE`] is set.
+    #[must_use]
+    pub const fn video_mode(&self) -> Option<&Multiboot1VideoMode> {
+        self.video_mode.as_ref()
+    }
+}
+
+/// Headers found by [`find_any_header`], each with its offset in the image.
+///
+/// Which header to use is up to the bootloader; see
+/// [`AnyHeader::prefer_multiboot2`] for a common policy.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AnyHeader<'a> {
+    /// Only a valid Multiboot1 header.
+    Multiboot1(Multiboot1Header, usize),
+    /// Only a valid Multiboot2 header.
+    Multiboot2(Header<'a>, usize),
+    /// Both a valid Multiboot1 and Multiboot2 header.
+    Both {
+        /// The Multiboot1 header and its offset.
+        multiboot1: (Multiboot1Header, usize),
+        /// The Multiboot2 header and its offset.
+        multiboot2: (Header<'a>, usize),
+    },
+}
+
+impl<'a> AnyHeader<'a> {
+    /// Returns the Multiboot1 header and its offset, if present.
+    #[must_use]
+    pub const fn multiboot1(&self) -> Option<(&Multiboot1Header, usize)> {
+        match self {
+            Self::Multiboot1(header, offset)
+            | Self::Both {
+                multiboot1: (header, offset),
+                ..
+            } => Some((header, *offset)),
+            Self::Multiboot2(..) => None,
+        }
+    }
+
+    /// Returns the Multiboot2 header and its offset, if present.
+    #[must_use]
+    pub const fn multiboot2(&self) -> Option<(&Header<'a>, usize)> {
+        match self {
+            Self::Multiboot2(header, offset)
+            | Self::Both {
+                multiboot2: (header, offset),
+                ..
+            } => Some((header, *offset)),
+            Self::Multiboot1(..) => None,
+        }
+    }
+
+    /// Picks the Multiboot2 header if present, and the Multiboot1 header
+    /// otherwise. The result is never [`AnyHeader::Both`].
+    #[must_use]
+    pub const fn prefer_multiboot2(self) -> Self {
+        match self {
+            Self::Both {
+                multiboot2: (header, offset),
+                ..
+            } => Self::Multiboot2(header, offset),
+            other => other,
+        }
+    }
+}
+
+/// Error of [`find_any_header`] if neither a Multiboot1 nor a Multiboot2
+/// header was found.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("neither a multiboot1 header ({multiboot1}) nor a multiboot2 header ({multiboot2}) found")]
+pub struct NoHeaderFoundError {
+    /// Why no Multiboot1 header was found.
+    pub multiboot1: Multiboot1LoadError,
+    /// Why no Multiboot2 header was found.
+    pub multiboot2: LoadError,
+}
+
+/// Searches for a Multiboot1 and a Multiboot2 header within their respective
+/// search limits.
+///
+/// The Multiboot2 header is searched with [`Header::find_header_unaligned`],
+/// so the buffer doesn't need to be aligned. The Multiboot2 header is copied
+/// into the `scratch` buffer.
+pub fn find_any_header<'a>(
+    buffer: &[u8],
+    scratch: &'a mut HeaderScratch,
+) -> Result<AnyHeader<'a>, NoHeaderFoundError> {
+    match (
+        Multiboot1Header::find_header(buffer),
+        Header::find_header_unaligned(buffer, scratch),
+    ) {
+        (Ok(multiboot1), Ok(multiboot2)) => Ok(AnyHeader::Both {
+            multiboot1,
+            multiboot2,
+        }),
+        (Ok((header, offset)), Err(_)) => Ok(AnyHeader::Multiboot1(header, offset)),
+        (Err(_), Ok((header, offset))) => Ok(AnyHeader::Multiboot2(header, offset)),
+        (Err(multiboot1), Err(multiboot2)) => Err(NoHeaderFoundError {
+            multiboot1,
+            multiboot2,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstBuilder, HEADER_SEARCH_LIMIT, HeaderTagISA, MAGIC};
+    use multiboot2_common::test_utils::AlignedBytes;
+
+    const MULTIBOOT2_HEADER: [u8; 24] = *ConstBuilder::new(HeaderTagISA::I386)
+        .build::<24>()
+        .as_bytes();
+
+    /// Writes a Multiboot1 header with the given flags and consecutive values
+    /// for the optional fields.
+    fn write_multiboot1(buffer: &mut [u8], flags: u32) {
+        let checksum = Multiboot1Header::calc_checksum(flags);
+        for (i, value) in [MULTIBOOT1_MAGIC, flags, checksum]
+            .into_iter()
+            .chain(1..10)
+            .enumerate()
+        {
+            buffer[i * 4..i * 4 + 4].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let mut bytes = [0; 48];
+        write_multiboot1(&mut bytes, 0x10007);
+        let header = Multiboot1Header::parse(&bytes).unwrap();
+        assert_eq!(
+            header.flags(),
+            Multiboot1HeaderFlags::PAGE_ALIGN
+                | Multiboot1HeaderFlags::MEMORY_INFO
+                | Multiboot1HeaderFlags::VIDEO_MODE
+                | Multiboot1HeaderFlags::ADDRESS
+        );
+        assert_eq!(header.checksum(), 0xe4514ff7);
+        assert_eq!(
+            header.address(),
+            Some(&Multiboot1AddressFields {
+                header_addr: 1,
+                load_addr: 2,
+                load_end_addr: 3,
+                bss_end_addr: 4,
+                entry_addr: 5,
+            })
+        );
+        assert_eq!(
+            header.video_mode(),
+            Some(&Multiboot1VideoMode {
+                mode_type: 6,
+                width: 7,
+                height: 8,
+                depth: 9,
+            })
+        );
+        assert_eq!(
+            Multiboot1Header::parse(&bytes[..40]),
+            Err(Multiboot1LoadError::Truncated(48, 40))
+        );
+
+        write_multiboot1(&mut bytes, 0x3);
+        let header = Multiboot1Header::parse(&bytes[..12]).unwrap();
+        assert_eq!(header.address(), None);
+        assert_eq!(header.video_mode(), None);
+
+        bytes[8] ^= 1;
+        assert!(matches!(
+            Multiboot1Header::parse(&bytes),
+            Err(Multiboot1LoadError::ChecksumMismatch(..))
+        ));
+    }
+
+    #[test]
+    fn find_header() {
+        let mut bytes = [0; 0x100];
+        // Unaligned and without checksum
+        bytes[0x22..0x26].copy_from_slice(&MULTIBOOT1_MAGIC.to_le_bytes());
+        bytes[0x40..0x44].copy_from_slice(&MULTIBOOT1_MAGIC.to_le_bytes());
+        write_multiboot1(&mut bytes[0x84..], 0);
+
+        let (header, offset) = Multiboot1Header::find_header(&bytes).unwrap();
+        assert_eq!(offset, 0x84);
+        assert_eq!(header.flags(), Multiboot1HeaderFlags::empty());
+        assert_eq!(
+            Multiboot1Header::find_header(&bytes[..0x8f]),
+            Err(Multiboot1LoadError::MagicNotFound)
+        );
+    }
+
+    #[test]
+    fn find_any() {
+        let mut buffer = AlignedBytes::new([0; HEADER_SEARCH_LIMIT + 8]);
+        // Unaligned, as an image read from a file may be.
+        let image = &mut buffer.0[1..=HEADER_SEARCH_LIMIT];
+        let mut scratch = HeaderScratch::new();
+        assert_eq!(
+            find_any_header(image, &mut scratch),
+            Err(NoHeaderFoundError {
+                multiboot1: Multiboot1LoadError::MagicNotFound,
+                multiboot2: LoadError::MagicNotFound,
+            })
+        );
+
+        // Beyond the Multiboot1 search limit
+        image[0x2000..0x2018].copy_from_slice(&MULTIBOOT2_HEADER);
+        write_multiboot1(&mut image[0x3000..], 0);
+        let found = find_any_header(image, &mut scratch).unwrap();
+        assert!(matches!(found, AnyHeader::Multiboot2(_, 0x2000)));
+        assert_eq!(found.multiboot1(), None);
+
+        write_multiboot1(&mut image[0x1000..], 0);
+        let found = find_any_header(image, &mut scratch).unwrap();
+        assert!(matches!(found, AnyHeader::Both { .. }));
+        assert_eq!(found.multiboot1().unwrap().1, 0x1000);
+        assert_eq!(found.multiboot2().unwrap().1, 0x2000);
+        assert!(matches!(
+            found.prefer_multiboot2(),
+            AnyHeader::Multiboot2(_, 0x2000)
+        ));
+
+        image[0x2000..0x2004].copy_from_slice(&(!MAGIC).to_le_bytes());
+        let found = find_any_header(image, &mut scratch).unwrap();
+        assert!(matches!(found, AnyHeader::Multiboot1(_, 0x1000)));
+        assert!(matches!(
+            found.prefer_multiboot2(),
+            AnyHeader::Multiboot1(_, 0x1000)
+        ));
+    }
+}
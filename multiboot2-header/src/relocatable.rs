@@ -1,4 +1,7 @@
-use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use crate::{
+    HeaderTagFlag, HeaderTagFlagId, HeaderTagHeader, HeaderTagType, HeaderTagTypeId,
+    RelocatableHeaderTagPreferenceId,
+};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use multiboot2_common::{MaybeDynSized, Tag};
@@ -32,7 +35,7 @@ pub struct RelocatableHeaderTag {
     max_addr: u32,
     /// Image alignment in memory, e.g. 4096.
     align: u32,
-    preference: RelocatableHeaderTagPreference,
+    preference: RelocatableHeaderTagPreferenceId,
 }
 
 impl RelocatableHeaderTag {
@@ -52,19 +55,19 @@ impl RelocatableHeaderTag {
             min_addr,
             max_addr,
             align,
-            preference,
+            preference: RelocatableHeaderTagPreferenceId::new(preference as u32),
         }
     }
 
-    /// Returns the [`HeaderTagType`].
+    /// Returns the [`HeaderTagTypeId`].
     #[must_use]
-    pub const fn typ(&self) -> HeaderTagType {
+    pub const fn typ(&self) -> HeaderTagTypeId {
         self.header.typ()
     }
 
-    /// Returns the [`HeaderTagFlag`]s.
+    /// Returns the [`HeaderTagFlagId`].
     #[must_use]
-    pub const fn flags(&self) -> HeaderTagFlag {
+    pub const fn flags(&self) -> HeaderTagFlagId {
         self.header.flags()
     }
 
@@ -94,7 +97,7 @@ impl RelocatableHeaderTag {
 
     /// Return the preference.
     #[must_use]
-    pub const fn preference(&self) -> RelocatableHeaderTagPreference {
+    pub const fn preference(&self) -> RelocatableHeaderTagPreferenceId {
         self.preference
     }
 }
@@ -125,6 +128,46 @@ impl Tag for RelocatableHeaderTag {
     const ID: HeaderTagType = HeaderTagType::Relocatable;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for RelocatableHeaderTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("RelocatableHeaderTag", 5)?;
+        state.serialize_field("flags", &self.flags().get())?;
+        state.serialize_field("min_addr", &self.min_addr)?;
+        state.serialize_field("max_addr", &self.max_addr)?;
+        state.serialize_field("align", &self.align)?;
+        state.serialize_field("preference", &self.preference.get())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RelocatableHeaderTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "RelocatableHeaderTag")]
+        struct Fields {
+            flags: u16,
+            min_addr: u32,
+            max_addr: u32,
+            align: u32,
+            preference: u32,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(
+            crate::UnknownHeaderValue::try_convert(HeaderTagFlagId::new(fields.flags))?,
+            fields.min_addr,
+            fields.max_addr,
+            fields.align,
+            crate::UnknownHeaderValue::try_convert(RelocatableHeaderTagPreferenceId::new(
+                fields.preference,
+            ))?,
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::RelocatableHeaderTag;
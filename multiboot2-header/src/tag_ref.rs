@@ -0,0 +1,136 @@
+//! Module for [`HeaderTagRef`].
+
+use crate::{
+    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EndHeaderTag,
+    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag,
+    GenericHeaderTag, HeaderTagType, HeaderTagTypeId, InformationRequestHeaderTag,
+    ModuleAlignHeaderTag, RelocatableHeaderTag,
+};
+
+/// A reference to a header tag, typed by its [`HeaderTagType`].
+///
+/// There is one variant per header tag type specified by Multiboot2, named
+/// like the corresponding [`HeaderTagType`] variant. This enables exhaustive
+/// matching over all header tags, e.g., for logging or conversions. Use
+/// [`Header::typed_tags`] to iterate the tags of a header.
+///
+/// [`Header::typed_tags`]: crate::Header::typed_tags
+#[derive(Copy, Clone, Debug)]
+pub enum HeaderTagRef<'a> {
+    /// The [`EndHeaderTag`].
+    End(&'a EndHeaderTag),
+    /// The [`InformationRequestHeaderTag`].
+    InformationRequest(&'a InformationRequestHeaderTag),
+    /// The [`AddressHeaderTag`].
+    Address(&'a AddressHeaderTag),
+    /// The [`EntryAddressHeaderTag`].
+    EntryAddress(&'a EntryAddressHeaderTag),
+    /// The [`ConsoleHeaderTag`].
+    ConsoleFlags(&'a ConsoleHeaderTag),
+    /// The [`FramebufferHeaderTag`].
+    Framebuffer(&'a FramebufferHeaderTag),
+    /// The [`ModuleAlignHeaderTag`].
+    ModuleAlign(&'a ModuleAlignHeaderTag),
+    /// The [`EfiBootServiceHeaderTag`].
+    EfiBS(&'a EfiBootServiceHeaderTag),
+    /// The [`EntryEfi32HeaderTag`].
+    EntryAddressEFI32(&'a EntryEfi32HeaderTag),
+    /// The [`EntryEfi64HeaderTag`].
+    EntryAddressEFI64(&'a EntryEfi64HeaderTag),
+    /// The [`RelocatableHeaderTag`].
+    Relocatable(&'a RelocatableHeaderTag),
+    /// A tag whose type doesn't correspond to a [`HeaderTagType`], e.g., a
+    /// custom tag. Use [`GenericHeaderTag::cast`] to cast it to a custom tag
+    /// type.
+    Unknown(&'a GenericHeaderTag),
+}
+
+impl HeaderTagRef<'_> {
+    /// Returns the [`HeaderTagTypeId`] of the tag.
+    #[must_use]
+    pub fn typ(&self) -> HeaderTagTypeId {
+        let typ = match self {
+            Self::End(_) => HeaderTagType::End,
+            Self::InformationRequest(_) => HeaderTagType::InformationRequest,
+            Self::Address(_) => HeaderTagType::Address,
+            Self::EntryAddress(_) => HeaderTagType::EntryAddress,
+            Self::ConsoleFlags(_) => HeaderTagType::ConsoleFlags,
+            Self::Framebuffer(_) => HeaderTagType::Framebuffer,
+            Self::ModuleAlign(_) => HeaderTagType::ModuleAlign,
+            Self::EfiBS(_) => HeaderTagType::EfiBS,
+            Self::EntryAddressEFI32(_) => HeaderTagType::EntryAddressEFI32,
+            Self::EntryAddressEFI64(_) => HeaderTagType::EntryAddressEFI64,
+            Self::Relocatable(_) => HeaderTagType::Relocatable,
+            Self::Unknown(tag) => return tag.header().typ(),
+        };
+        typ.into()
+    }
+}
+
+impl<'a> From<&'a GenericHeaderTag> for HeaderTagRef<'a> {
+    fn from(tag: &'a GenericHeaderTag) -> Self {
+        match HeaderTagType::try_from(tag.header().typ()) {
+            Ok(HeaderTagType::End) => Self::End(tag.cast()),
+            Ok(HeaderTagType::InformationRequest) => Self::InformationRequest(tag.cast()),
+            Ok(HeaderTagType::Address) => Self::Address(tag.cast()),
+            Ok(HeaderTagType::EntryAddress) => Self::EntryAddress(tag.cast()),
+            Ok(HeaderTagType::ConsoleFlags) => Self::ConsoleFlags(tag.cast()),
+            Ok(HeaderTagType::Framebuffer) => Self::Framebuffer(tag.cast()),
+            Ok(HeaderTagType::ModuleAlign) => Self::ModuleAlign(tag.cast()),
+            Ok(HeaderTagType::EfiBS) => Self::EfiBS(tag.cast()),
+            Ok(HeaderTagType::EntryAddressEFI32) => Self::EntryAddressEFI32(tag.cast()),
+            Ok(HeaderTagType::EntryAddressEFI64) => Self::EntryAddressEFI64(tag.cast()),
+            Ok(HeaderTagType::Relocatable) => Self::Relocatable(tag.cast()),
+            Err(_) => Self::Unknown(tag),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::HeaderTagFlag::{Optional, Required};
+    use crate::{Builder, Header, HeaderTagFlagId, HeaderTagHeader, HeaderTagISA};
+    use alloc::vec::Vec;
+    use multiboot2_common::{MaybeDynSized, new_boxed};
+
+    #[test]
+    fn typed_tags() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .entry_tag(EntryAddressHeaderTag::new(Required, 0x1000))
+            .module_align_tag(ModuleAlignHeaderTag::new(Optional))
+            .add_custom_tag(new_boxed::<GenericHeaderTag>(
+                HeaderTagHeader::new_raw(HeaderTagTypeId::new(0x1337), HeaderTagFlagId::new(1), 0),
+                &[&[1, 2, 3, 4]],
+            ))
+            .build();
+        // SAFETY: The builder emits a fully formed, aligned header buffer
+        // with a valid end tag.
+        let header =
+            unsafe { Header::load(structure.as_bytes().as_ref().as_ptr().cast()) }.unwrap();
+
+        let tags = header.typed_tags().collect::<Vec<_>>();
+        assert_eq!(
+            tags.iter().map(HeaderTagRef::typ).collect::<Vec<_>>(),
+            header
+                .iter()
+                .map(|tag| tag.header().typ())
+                .collect::<Vec<_>>()
+        );
+        let mut unknown = 0;
+        for tag in &tags {
+            match tag {
+                HeaderTagRef::EntryAddress(tag) => assert_eq!(tag.entry_addr(), 0x1000),
+                HeaderTagRef::ModuleAlign(tag) => assert!(tag.header().flags().is_optional()),
+                HeaderTagRef::Unknown(tag) => {
+                    assert_eq!(tag.payload(), [1, 2, 3, 4]);
+                    unknown += 1;
+                }
+                HeaderTagRef::End(_) => {}
+                tag => panic!("unexpected tag {tag:?}"),
+            }
+        }
+        assert_eq!(unknown, 1);
+        assert!(matches!(tags.last(), Some(HeaderTagRef::End(_))));
+    }
+}
@@ -1,8 +1,123 @@
 //! Definition for all types of "Multiboot2 header tags". The values are taken from the example C
 //! code at the end of the official Multiboot2 spec. These tags follow in memory right after
 //! [`crate::Multiboot2BasicHeader`].
+//!
+//! As headers are parsed from untrusted images, all enum-like fields are
+//! stored as raw newtype IDs, such as [`HeaderTagTypeId`]. They can be
+//! converted to the corresponding Rust enums, such as [`HeaderTagType`], if
+//! the value is known.
 
+use crate::{ConsoleHeaderTagFlags, RelocatableHeaderTagPreference};
+use core::fmt::{Debug, Formatter};
 use multiboot2_common::Header;
+use thiserror::Error;
+
+/// Error when a raw value of a Multiboot2 header doesn't correspond to a
+/// known variant of the Rust enum.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Error)]
+#[error("unknown value {0}")]
+pub struct UnknownHeaderValue(u32);
+
+impl UnknownHeaderValue {
+    /// Returns the raw value.
+    #[must_use]
+    pub const fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl UnknownHeaderValue {
+    /// Converts a deserialized raw value to its enum. Unknown values are an
+    /// error.
+    pub(crate) fn try_convert<Id, T, E>(raw: Id) -> Result<T, E>
+    where
+        T: TryFrom<Id, Error = Self>,
+        E: serde::de::Error,
+    {
+        T::try_from(raw).map_err(E::custom)
+    }
+}
+
+/// Defines a newtype ID for the raw representation of an enum, including the
+/// conversions and comparisons between both.
+macro_rules! impl_id_type {
+    (
+        $(#[$meta:meta])*
+        $id:ident($repr:ty) => $enum:ident { $($variant:ident),+ $(,)? }
+    ) => {
+        $(#[$meta])*
+        #[repr(transparent)]
+        #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $id($repr);
+
+        impl $id {
+            /// Constructor.
+            #[must_use]
+            pub const fn new(val: $repr) -> Self {
+                Self(val)
+            }
+
+            /// Returns the raw value.
+            #[must_use]
+            pub const fn get(&self) -> $repr {
+                self.0
+            }
+        }
+
+        impl Debug for $id {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                match $enum::try_from(*self) {
+                    Ok(val) => Debug::fmt(&val, f),
+                    Err(_) => write!(f, "{}({:#x})", stringify!($id), self.0),
+                }
+            }
+        }
+
+        impl From<$repr> for $id {
+            fn from(value: $repr) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$id> for $repr {
+            fn from(value: $id) -> Self {
+                value.0
+            }
+        }
+
+        impl From<$enum> for $id {
+            fn from(value: $enum) -> Self {
+                Self(value as $repr)
+            }
+        }
+
+        impl TryFrom<$id> for $enum {
+            type Error = UnknownHeaderValue;
+
+            fn try_from(value: $id) -> Result<Self, Self::Error> {
+                $(
+                    if value.0 == Self::$variant as $repr {
+                        return Ok(Self::$variant);
+                    }
+                )+
+                Err(UnknownHeaderValue(value.0.into()))
+            }
+        }
+
+        impl PartialEq<$enum> for $id {
+            fn eq(&self, other: &$enum) -> bool {
+                self.0 == *other as $repr
+            }
+        }
+
+        impl PartialEq<$id> for $enum {
+            fn eq(&self, other: &$id) -> bool {
+                other.eq(self)
+            }
+        }
+    };
+}
 
 /// ISA/ARCH in Multiboot2 header.
 #[repr(u32)]
@@ -17,6 +132,14 @@ pub enum HeaderTagISA {
     MIPS32 = 4,
 }
 
+impl_id_type! {
+    /// Raw form of [`HeaderTagISA`] as found in the `architecture` field of
+    /// the [`Multiboot2BasicHeader`].
+    ///
+    /// [`Multiboot2BasicHeader`]: crate::Multiboot2BasicHeader
+    HeaderTagISAId(u32) => HeaderTagISA { I386, MIPS32 }
+}
+
 /// Possible types for header tags of a Multiboot2 header.
 ///
 /// The names and values are taken from the example C code at the bottom of the
@@ -57,6 +180,33 @@ impl HeaderTagType {
     }
 }
 
+impl_id_type! {
+    /// Raw form of [`HeaderTagType`] as found in the `typ` field of the
+    /// [`HeaderTagHeader`]. Unknown values belong to tags this crate doesn't
+    /// know.
+    HeaderTagTypeId(u16) => HeaderTagType {
+        End,
+        InformationRequest,
+        Address,
+        EntryAddress,
+        ConsoleFlags,
+        Framebuffer,
+        ModuleAlign,
+        EfiBS,
+        EntryAddressEFI32,
+        EntryAddressEFI64,
+        Relocatable,
+    }
+}
+
+impl HeaderTagTypeId {
+    /// Returns whether the type corresponds to a [`HeaderTagType`].
+    #[must_use]
+    pub fn is_known(&self) -> bool {
+        HeaderTagType::try_from(*self).is_ok()
+    }
+}
+
 /// Flags for Multiboot2 header tags.
 #[repr(u16)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -68,13 +218,44 @@ pub enum HeaderTagFlag {
     Optional = 1,
 }
 
+impl_id_type! {
+    /// Raw form of [`HeaderTagFlag`] as found in the `flags` field of the
+    /// [`HeaderTagHeader`].
+    HeaderTagFlagId(u16) => HeaderTagFlag { Required, Optional }
+}
+
+impl HeaderTagFlagId {
+    /// Returns whether the optional bit (bit 0) is set. If not, the
+    /// bootloader must refuse to boot if it can't satisfy the tag.
+    #[must_use]
+    pub const fn is_optional(&self) -> bool {
+        self.0 & 1 != 0
+    }
+}
+
+impl_id_type! {
+    /// Raw form of [`ConsoleHeaderTagFlags`] as found in the `console_flags`
+    /// field of the [`ConsoleHeaderTag`].
+    ///
+    /// [`ConsoleHeaderTag`]: crate::ConsoleHeaderTag
+    ConsoleHeaderTagFlagsId(u32) => ConsoleHeaderTagFlags { ConsoleRequired, EgaTextSupported }
+}
+
+impl_id_type! {
+    /// Raw form of [`RelocatableHeaderTagPreference`] as found in the
+    /// `preference` field of the [`RelocatableHeaderTag`].
+    ///
+    /// [`RelocatableHeaderTag`]: crate::RelocatableHeaderTag
+    RelocatableHeaderTagPreferenceId(u32) => RelocatableHeaderTagPreference { None, Low, High }
+}
+
 /// The common header that all header tags share. Specific tags may have
 /// additional fields that depend on the `typ` and the `size` field.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C, align(8))]
 pub struct HeaderTagHeader {
-    typ: HeaderTagType,   /* u16 */
-    flags: HeaderTagFlag, /* u16 */
+    typ: HeaderTagTypeId,   /* u16 */
+    flags: HeaderTagFlagId, /* u16 */
     size: u32,
     // Followed by optional additional tag-specific fields.
 }
@@ -83,18 +264,29 @@ impl HeaderTagHeader {
     /// Creates a new header.
     #[must_use]
     pub const fn new(typ: HeaderTagType, flags: HeaderTagFlag, size: u32) -> Self {
+        Self::new_raw(
+            HeaderTagTypeId::new(typ as u16),
+            HeaderTagFlagId::new(flags as u16),
+            size,
+        )
+    }
+
+    /// Creates a new header from raw IDs, e.g., for tags unknown to this
+    /// crate.
+    #[must_use]
+    pub const fn new_raw(typ: HeaderTagTypeId, flags: HeaderTagFlagId, size: u32) -> Self {
         Self { typ, flags, size }
     }
 
-    /// Returns the [`HeaderTagType`].
+    /// Returns the [`HeaderTagTypeId`].
     #[must_use]
-    pub const fn typ(&self) -> HeaderTagType {
+    pub const fn typ(&self) -> HeaderTagTypeId {
         self.typ
     }
 
-    /// Returns the [`HeaderTagFlag`]s.
+    /// Returns the [`HeaderTagFlagId`].
     #[must_use]
-    pub const fn flags(&self) -> HeaderTagFlag {
+    pub const fn flags(&self) -> HeaderTagFlagId {
         self.flags
     }
 
@@ -0,0 +1,32 @@
+//! Various test utilities.
+
+use elf::abi::{PT_LOAD, PT_NOTE};
+
+/// Writes a minimal ELF32 file with a `PT_NOTE` segment and a `PT_LOAD`
+/// segment covering the file bytes `0x100..0x200` at the physical address
+/// `0x200000` and the virtual address `0xc0200000`.
+pub fn write_elf(image: &mut [u8]) {
+    image[..4].copy_from_slice(b"\x7fELF");
+    image[4..7].copy_from_slice(&[1, 1, 1]);
+    let fields: [(usize, &[u8]); 14] = [
+        (16, &2_u16.to_le_bytes()),
+        (18, &3_u16.to_le_bytes()),
+        (20, &1_u32.to_le_bytes()),
+        (28, &52_u32.to_le_bytes()),
+        (40, &52_u16.to_le_bytes()),
+        (42, &32_u16.to_le_bytes()),
+        (44, &2_u16.to_le_bytes()),
+        // Program header 0
+        (52, &PT_NOTE.to_le_bytes()),
+        (52 + 4, &0x100_u32.to_le_bytes()),
+        (52 + 16, &0x100_u32.to_le_bytes()),
+        // Program header 1
+        (84, &PT_LOAD.to_le_bytes()),
+        (84 + 4, &[0x00, 0x01, 0, 0, 0x00, 0x00, 0x20, 0xc0]),
+        (84 + 12, &0x200000_u32.to_le_bytes()),
+        (84 + 16, &[0x00, 0x01, 0, 0, 0x00, 0x02, 0, 0]),
+    ];
+    for (offset, bytes) in fields {
+        image[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use crate::{HeaderTagFlag, HeaderTagFlagId, HeaderTagHeader, HeaderTagType, HeaderTagTypeId};
 use multiboot2_common::{MaybeDynSized, Tag};
 
 /// This tag indicates that payload supports starting without terminating UEFI boot services.
@@ -17,15 +17,15 @@ impl EfiBootServiceHeaderTag {
         Self { header }
     }
 
-    /// Returns the [`HeaderTagType`].
+    /// Returns the [`HeaderTagTypeId`].
     #[must_use]
-    pub const fn typ(&self) -> HeaderTagType {
+    pub const fn typ(&self) -> HeaderTagTypeId {
         self.header.typ()
     }
 
-    /// Returns the [`HeaderTagFlag`]s.
+    /// Returns the [`HeaderTagFlagId`].
     #[must_use]
-    pub const fn flags(&self) -> HeaderTagFlag {
+    pub const fn flags(&self) -> HeaderTagFlagId {
         self.header.flags()
     }
 
@@ -47,6 +47,32 @@ impl Tag for EfiBootServiceHeaderTag {
     const ID: HeaderTagType = HeaderTagType::EfiBS;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for EfiBootServiceHeaderTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("EfiBootServiceHeaderTag", 1)?;
+        state.serialize_field("flags", &self.flags().get())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EfiBootServiceHeaderTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "EfiBootServiceHeaderTag")]
+        struct Fields {
+            flags: u16,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(crate::UnknownHeaderValue::try_convert(
+            HeaderTagFlagId::new(fields.flags),
+        )?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::EfiBootServiceHeaderTag;
@@ -0,0 +1,426 @@
+//! Module for finding headers in unaligned buffers, such as files read by
+//! bootloaders or host tools. See [`HeaderScratch`].
+
+use crate::{
+    HEADER_SEARCH_LIMIT, Header, HeaderTagHeader, HeaderTagType, LoadError, MAGIC,
+    Multiboot2BasicHeader,
+};
+use core::fmt::{Debug, Formatter};
+use multiboot2_common::{ALIGNMENT, ByteOrder, ByteSwapper, MemoryError};
+#[cfg(feature = "alloc")]
+use {
+    alloc::boxed::Box,
+    multiboot2_common::{DynSizedStructure, new_boxed},
+};
+
+/// Aligned scratch buffer into which [`Header::load_unaligned`] and
+/// [`Header::find_header_unaligned`] copy a header candidate.
+///
+/// As a header must be within the first [`HEADER_SEARCH_LIMIT`] bytes of an
+/// image, this is large enough for every header. It may be placed on the
+/// stack or in a `static`.
+#[derive(Clone)]
+#[repr(C, align(8))]
+pub struct HeaderScratch([u8; HEADER_SEARCH_LIMIT]);
+
+impl HeaderScratch {
+    /// Creates a new zeroed scratch buffer.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self([0; HEADER_SEARCH_LIMIT])
+    }
+}
+
+impl Default for HeaderScratch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for HeaderScratch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HeaderScratch").finish_non_exhaustive()
+    }
+}
+
+/// Performs the basic checks of the header candidate at `offset`, which uses
+/// `byte_order`, and returns its length.
+fn candidate_len(buffer: &[u8], offset: usize, byte_order: ByteOrder) -> Result<usize, LoadError> {
+    let search_len = buffer.len().min(HEADER_SEARCH_LIMIT);
+    let candidate = buffer
+        .get(offset..search_len)
+        .filter(|candidate| candidate.len() >= size_of::<Multiboot2BasicHeader>())
+        .ok_or(LoadError::Memory(MemoryError::ShorterThanHeader))?;
+    let read_u32 = |idx: usize| byte_order.read_u32(candidate[idx..idx + 4].try_into().unwrap());
+
+    if read_u32(0) != MAGIC {
+        return Err(LoadError::MagicNotFound);
+    }
+    // The header must be 64-bit aligned relative to the image.
+    if offset % ALIGNMENT != 0 {
+        return Err(LoadError::Memory(MemoryError::WrongAlignment));
+    }
+
+    let header_size = read_u32(8) as usize;
+    let min_size = size_of::<Multiboot2BasicHeader>() + size_of::<HeaderTagHeader>();
+    if header_size < min_size {
+        return Err(LoadError::Memory(MemoryError::SizeInsufficient(
+            header_size,
+            min_size,
+        )));
+    }
+    if candidate.len() < header_size {
+        return Err(LoadError::Memory(MemoryError::InvalidReportedTotalSize(
+            header_size,
+            candidate.len(),
+        )));
+    }
+    Ok(header_size)
+}
+
+/// Returns the offsets of all [`MAGIC`] values in `byte_order` within the
+/// first [`HEADER_SEARCH_LIMIT`] bytes of the image, independent of their
+/// alignment.
+fn magic_offsets(buffer: &[u8], byte_order: ByteOrder) -> impl Iterator<Item = usize> + '_ {
+    let magic = match byte_order {
+        ByteOrder::Little => MAGIC.to_le_bytes(),
+        ByteOrder::Big => MAGIC.to_be_bytes(),
+    };
+    let search_len = buffer.len().min(HEADER_SEARCH_LIMIT);
+    (0..search_len.saturating_sub(size_of::<u32>() - 1))
+        .filter(move |&idx| buffer[idx..idx + 4] == magic)
+}
+
+/// Returns the offsets of the [`MAGIC`] values at which a header may start,
+/// i.e., the 64-bit aligned ones.
+fn aligned_magic_offsets(buffer: &[u8], byte_order: ByteOrder) -> impl Iterator<Item = usize> + '_ {
+    magic_offsets(buffer, byte_order).filter(|offset| offset % ALIGNMENT == 0)
+}
+
+/// Reverses the byte order of the integer fields of the basic header and all
+/// tags, which currently use `byte_order`.
+fn swap_header(bytes: &mut [u8], byte_order: ByteOrder) {
+    ByteSwapper::new(bytes).swap_n(4, 4);
+    let mut offset = size_of::<Multiboot2BasicHeader>();
+    while let Some(header) = bytes.get_mut(offset..offset + size_of::<HeaderTagHeader>()) {
+        let typ = byte_order.read_u16(header[0..2].try_into().unwrap());
+        let size = byte_order.read_u32(header[4..8].try_into().unwrap()) as usize;
+        ByteSwapper::new(header).swap_n(2, 2).swap(4);
+        let Some(tag) = bytes.get_mut(offset..offset.saturating_add(size)) else {
+            break;
+        };
+        if size < size_of::<HeaderTagHeader>() || typ == HeaderTagType::End as u16 {
+            break;
+        }
+
+        let mut swapper = ByteSwapper::new(tag);
+        swapper.skip(size_of::<HeaderTagHeader>());
+        let fields = match typ {
+            x if x == HeaderTagType::InformationRequest as u16 => swapper.remaining() / 4,
+            x if x == HeaderTagType::Address as u16 || x == HeaderTagType::Relocatable as u16 => 4,
+            x if x == HeaderTagType::Framebuffer as u16 => 3,
+            x if x == HeaderTagType::EntryAddress as u16
+                || x == HeaderTagType::ConsoleFlags as u16
+                || x == HeaderTagType::EntryAddressEFI32 as u16
+                || x == HeaderTagType::EntryAddressEFI64 as u16 =>
+            {
+                1
+            }
+            // No fields or unknown layout
+            _ => 0,
+        };
+        swapper.swap_n(4, fields);
+        offset = offset.saturating_add(size.next_multiple_of(ALIGNMENT));
+    }
+}
+
+impl Header<'_> {
+    /// Returns the offsets of all [`MAGIC`] values within the first
+    /// [`HEADER_SEARCH_LIMIT`] bytes of the image, including the ones that
+    /// are not 64-bit aligned.
+    ///
+    /// Unlike [`Header::find_header`], the buffer doesn't need to be aligned;
+    /// the offsets are relative to its beginning, i.e., to the beginning of the
+    /// image. Each offset may be passed to [`Header::load_unaligned`], e.g., to
+    /// diagnose images with multiple, misaligned, or false-positive headers.
+    /// Misaligned headers are rejected with [`MemoryError::WrongAlignment`].
+    ///
+    /// The header is expected in little-endian byte order.
+    pub fn magic_matches(buffer: &[u8]) -> impl Iterator<Item = usize> + '_ {
+        magic_offsets(buffer, ByteOrder::Little)
+    }
+
+    /// Loads the header at `offset` of a possibly unaligned buffer by copying
+    /// it into the `scratch` buffer.
+    ///
+    /// The same checks as in [`Header::find_header`] are performed. The header
+    /// is expected in little-endian byte order and converted to the native
+    /// byte order while copying.
+    pub fn load_unaligned<'b>(
+        buffer: &[u8],
+        offset: usize,
+        scratch: &'b mut HeaderScratch,
+    ) -> Result<Header<'b>, LoadError> {
+        let len = candidate_len(buffer, offset, ByteOrder::Little)?;
+        scratch.0[..len].copy_from_slice(&buffer[offset..offset + len]);
+        if !ByteOrder::Little.is_native() {
+            swap_header(&mut scratch.0[..len], ByteOrder::Little);
+        }
+        // SAFETY: The scratch buffer is aligned, contains the complete header,
+        // and is borrowed as long as the header.
+        unsafe { Header::load(scratch.0.as_ptr().cast()) }
+    }
+
+    /// Like [`Header::find_header`] but for possibly unaligned buffers. The
+    /// header is copied into the `scratch` buffer.
+    ///
+    /// Unlike [`Header::find_header`], this skips invalid header candidates
+    /// and returns the first valid header and its offset. If there is none,
+    /// it returns the error of the first candidate.
+    pub fn find_header_unaligned<'b>(
+        buffer: &[u8],
+        scratch: &'b mut HeaderScratch,
+    ) -> Result<(Header<'b>, usize /* index in buffer */), LoadError> {
+        let mut first_err = None;
+        let offset = aligned_magic_offsets(buffer, ByteOrder::Little)
+            .find(
+                |&offset| match Self::load_unaligned(buffer, offset, scratch) {
+                    Ok(_) => true,
+                    Err(e) => {
+                        first_err.get_or_insert(e);
+                        false
+                    }
+                },
+            )
+            .ok_or_else(|| first_err.unwrap_or(LoadError::MagicNotFound))?;
+        let header = Self::load_unaligned(buffer, offset, scratch)?;
+        Ok((header, offset))
+    }
+}
+
+/// A header copied from a possibly unaligned buffer into an owned heap
+/// allocation of the exact size.
+///
+/// This is the `alloc` variant of [`Header::load_unaligned`] and
+/// [`Header::find_header_unaligned`]. Unlike these, it also supports headers
+/// in big-endian byte order, independent of the byte order of the host.
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct OwnedHeader(Box<DynSizedStructure<Multiboot2BasicHeader>>);
+
+#[cfg(feature = "alloc")]
+impl OwnedHeader {
+    /// Loads the little-endian header at `offset` of a possibly unaligned
+    /// buffer.
+    ///
+    /// The same checks as in [`Header::find_header`] are performed.
+    pub fn load(buffer: &[u8], offset: usize) -> Result<Self, LoadError> {
+        Self::load_with_byte_order(buffer, offset, ByteOrder::Little)
+    }
+
+    /// Like [`OwnedHeader::load`] but for a header in the given `byte_order`.
+    /// All integer fields of the known tags are converted to the native byte
+    /// order while copying.
+    pub fn load_with_byte_order(
+        buffer: &[u8],
+        offset: usize,
+        byte_order: ByteOrder,
+    ) -> Result<Self, LoadError> {
+        let len = candidate_len(buffer, offset, byte_order)?;
+        let mut bytes = buffer[offset..offset + len].to_vec();
+        if !byte_order.is_native() {
+            swap_header(&mut bytes, byte_order);
+        }
+        // SAFETY: `bytes` is longer than the basic header, which consists
+        // only of integers.
+        let basic_header = unsafe {
+            bytes
+                .as_ptr()
+                .cast::<Multiboot2BasicHeader>()
+                .read_unaligned()
+        };
+        // Check before `new_boxed` recomputes the checksum.
+        basic_header
+            .verify_checksum()
+            .map_err(|x| LoadError::ChecksumMismatch(x.0, x.1))?;
+        let payload = &bytes[size_of::<Multiboot2BasicHeader>()..];
+        let this = Self(new_boxed(basic_header, &[payload]));
+        // Validates the tags.
+        // SAFETY: The allocation is aligned and contains the complete header.
+        unsafe { Header::load(this.as_ptr()) }?;
+        Ok(this)
+    }
+
+    /// Like [`Header::find_header_unaligned`] but copies the header into an
+    /// owned allocation.
+    pub fn find_header(buffer: &[u8]) -> Result<(Self, usize /* index in buffer */), LoadError> {
+        Self::find_header_with_byte_order(buffer, ByteOrder::Little)
+    }
+
+    /// Like [`OwnedHeader::find_header`] but for a header in the given
+    /// `byte_order`. See [`OwnedHeader::load_with_byte_order`].
+    pub fn find_header_with_byte_order(
+        buffer: &[u8],
+        byte_order: ByteOrder,
+    ) -> Result<(Self, usize /* index in buffer */), LoadError> {
+        let mut first_err = None;
+        aligned_magic_offsets(buffer, byte_order)
+            .find_map(
+                |offset| match Self::load_with_byte_order(buffer, offset, byte_order) {
+                    Ok(header) => Some((header, offset)),
+                    Err(e) => {
+                        first_err.get_or_insert(e);
+                        None
+                    }
+                },
+            )
+            .ok_or_else(|| first_err.unwrap_or(LoadError::MagicNotFound))
+    }
+
+    const fn as_ptr(&self) -> *const Multiboot2BasicHeader {
+        (&raw const *self.0).cast()
+    }
+
+    /// Returns the parsed header.
+    #[must_use]
+    pub fn header(&self) -> Header<'_> {
+        // SAFETY: The header was validated on construction.
+        unsafe { Header::load(self.as_ptr()) }.expect("should be a valid header")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstBuilder, HeaderTagFlag, HeaderTagISA, ModuleAlignHeaderTag};
+    use std::vec::Vec;
+
+    /// Returns an image with the header at offset 0x1000 and the byte buffer
+    /// starting at an odd address.
+    fn image() -> Vec<u8> {
+        const HEADER: [u8; 32] = *ConstBuilder::new(HeaderTagISA::I386)
+            .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required))
+            .build::<32>()
+            .as_bytes();
+        let mut image = vec![0; 0x2001];
+        // False positive without a valid header
+        image[0x801..0x805].copy_from_slice(&MAGIC.to_le_bytes());
+        // Unaligned relative to the image
+        image[0x805..0x809].copy_from_slice(&MAGIC.to_le_bytes());
+        image[0x1001..0x1021].copy_from_slice(&HEADER);
+        image
+    }
+
+    #[test]
+    fn magic_matches() {
+        let image = image();
+        let buffer = &image[1..];
+        assert_eq!(
+            Header::magic_matches(buffer).collect::<Vec<_>>(),
+            [0x800, 0x804, 0x1000]
+        );
+        assert_eq!(Header::magic_matches(&buffer[..0x1003]).count(), 2);
+        assert_eq!(Header::magic_matches(&buffer[..0x1004]).count(), 3);
+    }
+
+    #[test]
+    fn find_header_unaligned() {
+        let image = image();
+        let buffer = &image[1..];
+        let mut scratch = HeaderScratch::new();
+
+        assert_eq!(
+            Header::load_unaligned(buffer, 0x800, &mut scratch),
+            Err(LoadError::Memory(MemoryError::SizeInsufficient(0, 24)))
+        );
+        assert_eq!(
+            Header::load_unaligned(buffer, 0x804, &mut scratch),
+            Err(LoadError::Memory(MemoryError::WrongAlignment))
+        );
+
+        let (header, offset) = Header::find_header_unaligned(buffer, &mut scratch).unwrap();
+        assert_eq!(offset, 0x1000);
+        assert!(header.module_align_tag().is_some());
+
+        assert_eq!(
+            Header::find_header_unaligned(&buffer[..0x1000], &mut scratch),
+            Err(LoadError::Memory(MemoryError::SizeInsufficient(0, 24)))
+        );
+        assert_eq!(
+            Header::find_header_unaligned(&buffer[..0x800], &mut scratch),
+            Err(LoadError::MagicNotFound)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn owned_header() {
+        let mut image = image();
+        let buffer = &image[1..];
+
+        let (owned, offset) = OwnedHeader::find_header(buffer).unwrap();
+        assert_eq!(offset, 0x1000);
+        assert!(owned.header().module_align_tag().is_some());
+        assert_eq!(owned.header().length(), 32);
+
+        // Corrupt the checksum
+        image[0x100d] ^= 1;
+        assert!(matches!(
+            OwnedHeader::load(&image[1..], 0x1000),
+            Err(LoadError::ChecksumMismatch(..))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn owned_header_with_byte_order() {
+        use crate::{EntryAddressHeaderTag, FramebufferHeaderTag, MbiTagTypeId};
+        use multiboot2_common::MaybeDynSized;
+
+        const HEADER: [u8; 80] = *ConstBuilder::new(HeaderTagISA::I386)
+            .information_request_tag(
+                HeaderTagFlag::Required,
+                &[MbiTagTypeId::new(1), MbiTagTypeId::new(6)],
+            )
+            .entry_tag(EntryAddressHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x10_0000,
+            ))
+            .framebuffer_tag(FramebufferHeaderTag::new(
+                HeaderTagFlag::Optional,
+                1024,
+                768,
+                32,
+            ))
+            .build::<80>()
+            .as_bytes();
+        let foreign_byte_order = match ByteOrder::NATIVE {
+            ByteOrder::Little => ByteOrder::Big,
+            ByteOrder::Big => ByteOrder::Little,
+        };
+        let mut foreign = HEADER;
+        swap_header(&mut foreign, ByteOrder::NATIVE);
+        assert_ne!(foreign, HEADER);
+
+        let mut image = vec![0; 0x1051];
+        image[0x1001..].copy_from_slice(&foreign);
+        let buffer = &image[1..];
+        assert_eq!(
+            OwnedHeader::find_header_with_byte_order(buffer, ByteOrder::NATIVE).unwrap_err(),
+            LoadError::MagicNotFound
+        );
+
+        let (owned, offset) =
+            OwnedHeader::find_header_with_byte_order(buffer, foreign_byte_order).unwrap();
+        assert_eq!(offset, 0x1000);
+        let header = owned.header();
+        assert_eq!(header.length(), 80);
+        assert_eq!(
+            header.information_request_tag().unwrap().requests(),
+            [MbiTagTypeId::new(1), MbiTagTypeId::new(6)]
+        );
+        assert_eq!(header.entry_address_tag().unwrap().entry_addr(), 0x10_0000);
+        assert_eq!(header.framebuffer_tag().unwrap().width(), 1024);
+        assert_eq!(owned.0.as_bytes().as_ref(), HEADER);
+    }
+}
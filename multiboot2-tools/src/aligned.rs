@@ -0,0 +1,57 @@
+//! Module for [`AlignedBuffer`].
+
+use multiboot2_common::ALIGNMENT;
+
+/// Unit of an [`AlignedBuffer`]. Unlike `u64`, it is 8-byte aligned on all
+/// platforms.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C, align(8))]
+struct Word([u8; ALIGNMENT]);
+
+/// A copy of bytes with the [alignment](ALIGNMENT) required to parse
+/// Multiboot2 structures in place, e.g., an MBI read from a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AlignedBuffer {
+    words: Vec<Word>,
+    len: usize,
+}
+
+impl AlignedBuffer {
+    /// Copies `bytes` into a new aligned buffer.
+    #[must_use]
+    pub fn new(bytes: &[u8]) -> Self {
+        let words = bytes
+            .chunks(ALIGNMENT)
+            .map(|chunk| {
+                let mut word = Word([0; ALIGNMENT]);
+                word.0[..chunk.len()].copy_from_slice(chunk);
+                word
+            })
+            .collect();
+        Self {
+            words,
+            len: bytes.len(),
+        }
+    }
+
+    /// Returns the aligned bytes.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: The words are initialized and span at least `len` bytes.
+        unsafe { core::slice::from_raw_parts(self.words.as_ptr().cast(), self.len) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned() {
+        let bytes = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+        let buffer = AlignedBuffer::new(&bytes[1..]);
+        assert_eq!(buffer.as_bytes(), &bytes[1..]);
+        assert_eq!(buffer.as_bytes().as_ptr().align_offset(ALIGNMENT), 0);
+        assert!(AlignedBuffer::new(&[]).as_bytes().is_empty());
+    }
+}
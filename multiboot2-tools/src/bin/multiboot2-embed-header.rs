@@ -0,0 +1,174 @@
+//! Embeds a Multiboot2 header into an existing ELF file. See
+//! [`multiboot2_tools::embed_header`] and [`multiboot2_tools::flat_binary`].
+
+use multiboot2_header::{
+    Builder, ConsoleHeaderTag, ConsoleHeaderTagFlags, EfiBootServiceHeaderTag,
+    FramebufferHeaderTag, HeaderTagFlag, HeaderTagISA, InformationRequestHeaderTag, MbiTagTypeId,
+    ModuleAlignHeaderTag,
+};
+use multiboot2_tools::{embed_header, flat_binary};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: multiboot2-embed-header <input> <output> [options]
+
+Embeds a Multiboot2 header into an existing ELF file.
+
+Options:
+  --arch <i386|mips32>      Architecture of the header (default: i386)
+  --request <id,...>        Add a required information request for the given
+                            MBI tag type IDs
+  --module-align            Add a required module alignment tag
+  --framebuffer <WxHxD>     Add an optional framebuffer tag
+  --console                 Add an optional console tag requiring a console
+  --efi-bs                  Add an optional EFI boot services tag
+  --flat                    Write a flat binary with address and entry tags
+                            (a.out kludge) instead of an ELF file
+  -h, --help                Print this help";
+
+/// Parsed command-line arguments.
+#[derive(Debug)]
+struct Args {
+    input: String,
+    output: String,
+    flat: bool,
+    builder: Builder,
+}
+
+fn parse_u32(value: &str) -> Result<u32, String> {
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|e| format!("invalid number '{value}': {e}"))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut paths = Vec::new();
+    let mut arch = HeaderTagISA::I386;
+    let mut requests = Vec::new();
+    let mut module_align = false;
+    let mut framebuffer = None;
+    let mut console = false;
+    let mut efi_bs = false;
+    let mut flat = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for '{arg}'"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--arch" => {
+                arch = match value()?.as_str() {
+                    "i386" => HeaderTagISA::I386,
+                    "mips32" => HeaderTagISA::MIPS32,
+                    other => return Err(format!("unknown architecture '{other}'")),
+                };
+            }
+            "--request" => {
+                for id in value()?.split(',') {
+                    requests.push(MbiTagTypeId::new(parse_u32(id)?));
+                }
+            }
+            "--module-align" => module_align = true,
+            "--framebuffer" => {
+                let value = value()?;
+                let dims = value
+                    .split('x')
+                    .map(parse_u32)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let [width, height, depth] = dims[..] else {
+                    return Err(format!("invalid framebuffer '{value}', expected WxHxD"));
+                };
+                framebuffer = Some(FramebufferHeaderTag::new(
+                    HeaderTagFlag::Optional,
+                    width,
+                    height,
+                    depth,
+                ));
+            }
+            "--console" => console = true,
+            "--efi-bs" => efi_bs = true,
+            "--flat" => flat = true,
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{arg}'")),
+            _ => paths.push(arg),
+        }
+    }
+
+    let [input, output] = <[String; 2]>::try_from(paths)
+        .map_err(|_| "expected exactly one input and one output file".to_string())?;
+    let mut builder = Builder::new(arch);
+    if !requests.is_empty() {
+        builder = builder.information_request_tag(InformationRequestHeaderTag::new(
+            HeaderTagFlag::Required,
+            &requests,
+        ));
+    }
+    if module_align {
+        builder = builder.module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required));
+    }
+    if let Some(framebuffer) = framebuffer {
+        builder = builder.framebuffer_tag(framebuffer);
+    }
+    if console {
+        builder = builder.console_tag(ConsoleHeaderTag::new(
+            HeaderTagFlag::Optional,
+            ConsoleHeaderTagFlags::ConsoleRequired,
+        ));
+    }
+    if efi_bs {
+        builder = builder.efi_bs_tag(EfiBootServiceHeaderTag::new(HeaderTagFlag::Optional));
+    }
+    Ok(Some(Args {
+        input,
+        output,
+        flat,
+        builder,
+    }))
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let elf =
+        std::fs::read(&args.input).map_err(|e| format!("failed to read '{}': {e}", args.input))?;
+    let image = if args.flat {
+        let flat = flat_binary(&elf, args.builder).map_err(|e| e.to_string())?;
+        let tag = flat.address_tag();
+        println!(
+            "flat binary: header_addr={:#x} load_addr={:#x} load_end_addr={:#x} bss_end_addr={:#x} entry_addr={:#x}",
+            tag.header_addr(),
+            tag.load_addr(),
+            tag.load_end_addr(),
+            tag.bss_end_addr(),
+            flat.entry_tag().entry_addr()
+        );
+        flat.into_image()
+    } else {
+        let embedded = embed_header(&elf, args.builder).map_err(|e| e.to_string())?;
+        println!(
+            "embedded header at file offset {:#x} ({:?})",
+            embedded.offset(),
+            embedded.placement()
+        );
+        embedded.into_image()
+    };
+    std::fs::write(&args.output, image)
+        .map_err(|e| format!("failed to write '{}': {e}", args.output))?;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => run(args),
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => Err(format!("{e}\n\n{USAGE}")),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
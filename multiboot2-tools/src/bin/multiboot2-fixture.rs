@@ -0,0 +1,155 @@
+//! Builds binary Multiboot2 boot information (MBI) fixtures from TOML or JSON
+//! descriptions and vice versa. See [`multiboot2_tools::MbiDescription`].
+
+use multiboot2::MaybeDynSized;
+use multiboot2_tools::MbiDescription;
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: multiboot2-fixture build <description> <output>
+       multiboot2-fixture decompile <mbi> [<output>] [--json]
+
+Commands:
+  build                     Build a binary MBI from a TOML or JSON description.
+                            Descriptions ending with `.json` are JSON, all
+                            others TOML. Files are relative to the description.
+  decompile                 Describe a binary MBI as TOML or, with `--json` or
+                            an output ending with `.json`, as JSON. Without an
+                            output, the description is printed.
+
+Options:
+  --json                    Write JSON instead of TOML
+  -h, --help                Print this help";
+
+/// Command to run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Command {
+    Build,
+    Decompile,
+}
+
+/// Parsed command-line arguments.
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    input: String,
+    output: Option<String>,
+    json: bool,
+}
+
+fn is_json(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut positional = Vec::new();
+    let mut json = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--json" => json = true,
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{arg}'")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let command = match positional.next().as_deref() {
+        Some("build") => Command::Build,
+        Some("decompile") => Command::Decompile,
+        Some(other) => return Err(format!("unknown command '{other}'")),
+        None => return Err("missing command".to_string()),
+    };
+    let input = positional.next().ok_or("missing input file")?;
+    let output = positional.next();
+    if positional.next().is_some() {
+        return Err("too many arguments".to_string());
+    }
+    match command {
+        Command::Build if output.is_none() => return Err("missing output file".to_string()),
+        Command::Build if json => return Err("'--json' only applies to 'decompile'".to_string()),
+        _ => {}
+    }
+    Ok(Some(Args {
+        command,
+        input,
+        output,
+        json,
+    }))
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let input =
+        std::fs::read(&args.input).map_err(|e| format!("failed to read '{}': {e}", args.input))?;
+    let write = |path: &str, bytes: &[u8]| {
+        std::fs::write(path, bytes).map_err(|e| format!("failed to write '{path}': {e}"))
+    };
+    match args.command {
+        Command::Build => {
+            let text = String::from_utf8(input)
+                .map_err(|_| format!("'{}' is not valid UTF-8", args.input))?;
+            let description = if is_json(&args.input) {
+                MbiDescription::from_json(&text)
+            } else {
+                MbiDescription::from_toml(&text)
+            };
+            let base_dir = Path::new(&args.input).parent().unwrap_or(Path::new(""));
+            let mbi = description
+                .and_then(|description| description.build(base_dir))
+                .map_err(|e| describe(&e))?;
+            let output = args.output.as_deref().expect("checked by parse_args");
+            write(output, &mbi.as_bytes())
+        }
+        Command::Decompile => {
+            let description = MbiDescription::from_bytes(&input).map_err(|e| describe(&e))?;
+            let json = args.json || args.output.as_deref().is_some_and(is_json);
+            let text = if json {
+                description.to_json()
+            } else {
+                description.to_toml().map_err(|e| describe(&e))?
+            };
+            match args.output {
+                Some(output) => write(&output, text.as_bytes()),
+                None => {
+                    print!("{text}");
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+/// Formats an error with all its sources, e.g., the position of a syntax
+/// error.
+fn describe(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+        source = error.source();
+    }
+    message
+}
+
+fn main() -> ExitCode {
+    let result = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => run(args),
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => Err(format!("{e}\n\n{USAGE}")),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
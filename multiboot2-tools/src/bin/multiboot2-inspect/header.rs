@@ -0,0 +1,315 @@
+//! `header` command: Locates, prints, and checks the Multiboot2 header of a
+//! kernel image.
+
+use multiboot2_header::{
+    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, ElfHeaderError, ElfHeaderLocation,
+    EndHeaderTag, EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag,
+    FramebufferHeaderTag, GenericHeaderTag, Header, HeaderTagType, HeaderTagTypeId,
+    InformationRequestHeaderTag, MaybeDynSized, MbiTagTypeId, ModuleAlignHeaderTag, OwnedHeader,
+    RelocatableHeaderTag, Violation,
+};
+use serde_json::{Map, Value, json};
+use std::fmt::Write;
+
+/// All header tag types. The checks assume a bootloader that supports the
+/// whole spec, so only semantic violations are reported.
+const SUPPORTED: [HeaderTagType; 11] = [
+    HeaderTagType::End,
+    HeaderTagType::InformationRequest,
+    HeaderTagType::Address,
+    HeaderTagType::EntryAddress,
+    HeaderTagType::ConsoleFlags,
+    HeaderTagType::Framebuffer,
+    HeaderTagType::ModuleAlign,
+    HeaderTagType::EfiBS,
+    HeaderTagType::EntryAddressEFI32,
+    HeaderTagType::EntryAddressEFI64,
+    HeaderTagType::Relocatable,
+];
+
+/// Finds the header in an ELF file or, if the image is not an ELF file, in
+/// the raw image.
+fn find(image: &[u8]) -> Result<(OwnedHeader, usize, Option<ElfHeaderLocation>), String> {
+    match OwnedHeader::find_in_elf(image) {
+        Ok((header, location)) => Ok((header, location.file_offset(), Some(location))),
+        Err(ElfHeaderError::InvalidElf) => OwnedHeader::find_header(image)
+            .map(|(header, offset)| (header, offset, None))
+            .map_err(|e| format!("no valid multiboot2 header found: {e}")),
+        Err(ElfHeaderError::Header(e)) => Err(format!("no valid multiboot2 header found: {e}")),
+    }
+}
+
+/// Checks the size of a tag of a known type, so that casting it doesn't
+/// panic.
+fn validate_tag(tag: &GenericHeaderTag) -> Result<(), String> {
+    let Ok(typ) = HeaderTagType::try_from(tag.header().typ()) else {
+        return Ok(());
+    };
+    let size = tag.header().size() as usize;
+    let valid_size = match typ {
+        HeaderTagType::InformationRequest => {
+            let base_size = InformationRequestHeaderTag::BASE_SIZE;
+            size >= base_size && (size - base_size) % size_of::<MbiTagTypeId>() == 0
+        }
+        HeaderTagType::End => size == EndHeaderTag::BASE_SIZE,
+        HeaderTagType::Address => size == AddressHeaderTag::BASE_SIZE,
+        HeaderTagType::EntryAddress => size == EntryAddressHeaderTag::BASE_SIZE,
+        HeaderTagType::ConsoleFlags => size == ConsoleHeaderTag::BASE_SIZE,
+        HeaderTagType::Framebuffer => size == FramebufferHeaderTag::BASE_SIZE,
+        HeaderTagType::ModuleAlign => size == ModuleAlignHeaderTag::BASE_SIZE,
+        HeaderTagType::EfiBS => size == EfiBootServiceHeaderTag::BASE_SIZE,
+        HeaderTagType::EntryAddressEFI32 => size == EntryEfi32HeaderTag::BASE_SIZE,
+        HeaderTagType::EntryAddressEFI64 => size == EntryEfi64HeaderTag::BASE_SIZE,
+        HeaderTagType::Relocatable => size == RelocatableHeaderTag::BASE_SIZE,
+    };
+    if valid_size {
+        Ok(())
+    } else {
+        Err(format!("invalid size {size:#x}"))
+    }
+}
+
+/// Checks all tags before they are decoded and reports the first malformed
+/// one.
+fn validate(header: &Header) -> Result<(), String> {
+    header.iter().enumerate().try_for_each(|(index, tag)| {
+        validate_tag(tag).map_err(|e| {
+            let typ = tag.header().typ();
+            format!("malformed tag #{index} ({typ:?}): {e}")
+        })
+    })
+}
+
+/// Returns the fields of a tag that are specific to its type.
+fn tag_fields(tag: &GenericHeaderTag) -> Vec<(&'static str, Value)> {
+    match HeaderTagType::try_from(tag.header().typ()) {
+        Ok(HeaderTagType::InformationRequest) => {
+            let requests = tag
+                .cast::<InformationRequestHeaderTag>()
+                .requests()
+                .iter()
+                .map(|&id| json!({ "type": format!("{id:?}"), "id": u32::from(id) }))
+                .collect::<Vec<_>>();
+            vec![("requests", requests.into())]
+        }
+        Ok(HeaderTagType::Address) => {
+            let tag = tag.cast::<AddressHeaderTag>();
+            vec![
+                ("header_addr", tag.header_addr().into()),
+                ("load_addr", tag.load_addr().into()),
+                ("load_end_addr", tag.load_end_addr().into()),
+                ("bss_end_addr", tag.bss_end_addr().into()),
+            ]
+        }
+        Ok(HeaderTagType::EntryAddress) => {
+            vec![(
+                "entry_addr",
+                tag.cast::<EntryAddressHeaderTag>().entry_addr().into(),
+            )]
+        }
+        Ok(HeaderTagType::EntryAddressEFI32) => {
+            vec![(
+                "entry_addr",
+                tag.cast::<EntryEfi32HeaderTag>().entry_addr().into(),
+            )]
+        }
+        Ok(HeaderTagType::EntryAddressEFI64) => {
+            vec![(
+                "entry_addr",
+                tag.cast::<EntryEfi64HeaderTag>().entry_addr().into(),
+            )]
+        }
+        Ok(HeaderTagType::ConsoleFlags) => {
+            let flags = tag.cast::<ConsoleHeaderTag>().console_flags();
+            vec![("console_flags", format!("{flags:?}").into())]
+        }
+        Ok(HeaderTagType::Framebuffer) => {
+            let tag = tag.cast::<FramebufferHeaderTag>();
+            vec![
+                ("width", tag.width().into()),
+                ("height", tag.height().into()),
+                ("depth", tag.depth().into()),
+            ]
+        }
+        Ok(HeaderTagType::Relocatable) => {
+            let tag = tag.cast::<RelocatableHeaderTag>();
+            vec![
+                ("min_addr", tag.min_addr().into()),
+                ("max_addr", tag.max_addr().into()),
+                ("align", tag.align().into()),
+                ("preference", format!("{:?}", tag.preference()).into()),
+            ]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn to_json(
+    header: &Header,
+    offset: usize,
+    location: Option<&ElfHeaderLocation>,
+    violations: &[Violation],
+) -> Value {
+    let tags = header
+        .iter()
+        .map(|tag| {
+            let mut object = Map::new();
+            let typ = tag.header().typ();
+            object.insert("type".into(), format!("{typ:?}").into());
+            object.insert("type_id".into(), typ.get().into());
+            object.insert("optional".into(), tag.header().flags().is_optional().into());
+            object.insert("size".into(), tag.header().size().into());
+            object.extend(
+                tag_fields(tag)
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value)),
+            );
+            Value::Object(object)
+        })
+        .collect::<Vec<_>>();
+    let elf = location.map(|location| {
+        location.segment().map_or(Value::Null, |segment| {
+            json!({
+                "segment": segment.index,
+                "header_paddr": segment.header_paddr(),
+                "header_vaddr": segment.header_vaddr(),
+            })
+        })
+    });
+    let mut object = json!({
+        "offset": offset,
+        "arch": format!("{:?}", header.arch()),
+        "length": header.length(),
+        "checksum": header.checksum(),
+        "checksum_valid": header.verify_checksum().is_ok(),
+        "tags": tags,
+        "violations": violations.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        "bootable": violations.is_empty(),
+    });
+    if let Some(elf) = elf {
+        object["elf"] = elf;
+    }
+    object
+}
+
+fn to_text(
+    header: &Header,
+    offset: usize,
+    location: Option<&ElfHeaderLocation>,
+    violations: &[Violation],
+) -> String {
+    let mut text = format!("header at file offset {offset:#x}\n");
+    match location.map(ElfHeaderLocation::segment) {
+        Some(Some(segment)) => {
+            let _ = writeln!(
+                text,
+                "loaded by PT_LOAD segment {} at paddr {:#x} (vaddr {:#x})",
+                segment.index,
+                segment.header_paddr(),
+                segment.header_vaddr()
+            );
+        }
+        Some(None) => text.push_str("not loaded by any PT_LOAD segment\n"),
+        None => text.push_str("not an ELF file\n"),
+    }
+    let _ = writeln!(text, "{header:#?}");
+    if violations.is_empty() {
+        text.push_str("bootable: yes");
+    } else {
+        text.push_str("bootable: no");
+        for violation in violations {
+            let _ = write!(text, "\n  - {violation}");
+        }
+    }
+    text
+}
+
+/// Inspects the header of the kernel image and returns the output.
+pub fn inspect(image: &[u8], json: bool) -> Result<String, String> {
+    let (header, offset, location) = find(image)?;
+    let header = header.header();
+    // Casting tags panics on invalid sizes, e.g., in the `Debug` output.
+    validate(&header)?;
+    let violations = header
+        .check(offset, image, &SUPPORTED.map(HeaderTagTypeId::from))
+        .violations()
+        .collect::<Vec<_>>();
+    if json {
+        let value = to_json(&header, offset, location.as_ref(), &violations);
+        Ok(serde_json::to_string_pretty(&value).expect("should serialize JSON"))
+    } else {
+        Ok(to_text(&header, offset, location.as_ref(), &violations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiboot2_common::new_boxed;
+    use multiboot2_header::{Builder, HeaderTagFlag, HeaderTagHeader, HeaderTagISA};
+
+    fn image(builder: Builder) -> Vec<u8> {
+        let mut image = vec![0xff; 16];
+        image.extend_from_slice(&builder.build().as_bytes());
+        image.resize(0x1000, 0);
+        image
+    }
+
+    #[test]
+    fn json() {
+        let builder = Builder::new(HeaderTagISA::I386)
+            .information_request_tag(InformationRequestHeaderTag::new(
+                HeaderTagFlag::Required,
+                &[MbiTagTypeId::from(6)],
+            ))
+            .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Optional));
+        let output = inspect(&image(builder), true).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["offset"], 16);
+        assert_eq!(value["arch"], "I386");
+        assert_eq!(value["checksum_valid"], true);
+        assert_eq!(value["bootable"], true);
+        assert!(value.get("elf").is_none());
+        let tags = value["tags"].as_array().unwrap();
+        assert_eq!(tags.len(), 3);
+        assert_eq!(tags[0]["type"], "InformationRequest");
+        assert_eq!(tags[0]["optional"], false);
+        assert_eq!(tags[0]["requests"][0]["type"], "Mmap");
+        assert_eq!(tags[1]["type"], "ModuleAlign");
+        assert_eq!(tags[1]["optional"], true);
+        assert_eq!(tags[2]["type"], "End");
+    }
+
+    #[test]
+    fn violations() {
+        let builder = Builder::new(HeaderTagISA::I386).address_tag(AddressHeaderTag::new(
+            HeaderTagFlag::Required,
+            0x1000,
+            0x1000,
+            0x2000,
+            0x3000,
+        ));
+        let output = inspect(&image(builder), false).unwrap();
+        assert!(output.starts_with("header at file offset 0x10\nnot an ELF file\n"));
+        assert!(output.contains("bootable: no\n"));
+        assert!(output.contains("\n  - address tag without entry address tag"));
+        assert!(inspect(&[0; 0x100], false).is_err());
+    }
+
+    #[test]
+    fn malformed_tag() {
+        let builder =
+            Builder::new(HeaderTagISA::I386).add_custom_tag(new_boxed::<GenericHeaderTag>(
+                HeaderTagHeader::new_raw(42.into(), HeaderTagFlag::Required.into(), 0),
+                &[&[0; 8]],
+            ));
+        let mut image = image(builder);
+        // Turn the custom tag into an address tag with only 16 instead of 24
+        // bytes.
+        image[32..34].copy_from_slice(&(HeaderTagType::Address as u16).to_ne_bytes());
+        assert_eq!(
+            inspect(&image, false).unwrap_err(),
+            "malformed tag #0 (Address): invalid size 0x10"
+        );
+    }
+}
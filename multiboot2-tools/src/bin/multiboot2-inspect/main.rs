@@ -0,0 +1,149 @@
+//! Inspects the Multiboot2 header of a kernel image or a dump of a Multiboot2
+//! boot information (MBI).
+
+mod header;
+mod mbi;
+
+use multiboot2::ByteOrder;
+use multiboot2_tools::parse_hexdump;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: multiboot2-inspect header <kernel> [--json]
+       multiboot2-inspect mbi <dump> [options]
+
+Commands:
+  header                    Locate, print, and check the Multiboot2 header of a
+                            kernel image (ELF file or flat binary)
+  mbi                       Decode a dump of a Multiboot2 boot information, e.g.,
+                            created with QEMU's `pmemsave`
+
+Options:
+  --json                    Print JSON instead of text
+  --hexdump                 (mbi) The dump is a textual hexdump (xxd, hexdump -C,
+                            or plain hexadecimal bytes) instead of raw bytes
+  --offset <n>              (mbi) Offset of the MBI in the dump (default: 0)
+  --base <addr>             (mbi) Physical address of the start of the dump, used
+                            to resolve ELF section names from the dump
+  --byte-order <order>      (mbi) Byte order of the dump, 'little' or 'big'
+                            (default: little)
+  -h, --help                Print this help";
+
+/// Command to run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Command {
+    Header,
+    Mbi,
+}
+
+/// Parsed command-line arguments.
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    path: String,
+    json: bool,
+    hexdump: bool,
+    offset: usize,
+    base: Option<u64>,
+    byte_order: Option<ByteOrder>,
+}
+
+fn parse_u64(value: &str) -> Result<u64, String> {
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|e| format!("invalid number '{value}': {e}"))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut positional = Vec::new();
+    let mut json = false;
+    let mut hexdump = false;
+    let mut offset = 0;
+    let mut base = None;
+    let mut byte_order = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for '{arg}'"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--json" => json = true,
+            "--hexdump" => hexdump = true,
+            "--offset" => {
+                offset = usize::try_from(parse_u64(&value()?)?)
+                    .map_err(|_| "offset is too large".to_string())?;
+            }
+            "--base" => base = Some(parse_u64(&value()?)?),
+            "--byte-order" => {
+                byte_order = Some(match value()?.as_str() {
+                    "little" => ByteOrder::Little,
+                    "big" => ByteOrder::Big,
+                    other => return Err(format!("invalid byte order '{other}'")),
+                });
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{arg}'")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let [command, path] = <[String; 2]>::try_from(positional)
+        .map_err(|_| "expected a command and one file".to_string())?;
+    let command = match command.as_str() {
+        "header" => Command::Header,
+        "mbi" => Command::Mbi,
+        other => return Err(format!("unknown command '{other}'")),
+    };
+    if command == Command::Header
+        && (hexdump || offset != 0 || base.is_some() || byte_order.is_some())
+    {
+        return Err(
+            "'--hexdump', '--offset', '--base', and '--byte-order' only apply to 'mbi'".to_string(),
+        );
+    }
+    Ok(Some(Args {
+        command,
+        path,
+        json,
+        hexdump,
+        offset,
+        base,
+        byte_order,
+    }))
+}
+
+fn run(args: Args) -> Result<String, String> {
+    let bytes =
+        std::fs::read(&args.path).map_err(|e| format!("failed to read '{}': {e}", args.path))?;
+    match args.command {
+        Command::Header => header::inspect(&bytes, args.json),
+        Command::Mbi => {
+            let bytes = if args.hexdump {
+                let text = String::from_utf8_lossy(&bytes);
+                parse_hexdump(&text).map_err(|e| e.to_string())?
+            } else {
+                bytes
+            };
+            let byte_order = args.byte_order.unwrap_or(ByteOrder::Little);
+            mbi::inspect(&bytes, args.offset, args.base, byte_order, args.json)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let result = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => run(args),
+        Ok(None) => Ok(USAGE.to_string()),
+        Err(e) => Err(format!("{e}\n\n{USAGE}")),
+    };
+    match result {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
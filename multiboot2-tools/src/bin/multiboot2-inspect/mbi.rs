@@ -0,0 +1,558 @@
+//! `mbi` command: Decodes a dump of a Multiboot2 boot information (MBI).
+
+use elf::section::SectionHeader;
+use multiboot2::{
+    ApmTag, BasicMemoryInfoTag, BootInformation, BootInformationHeader, BootLoaderNameTag,
+    BootdevTag, ByteOrder, CommandLineTag, DynSizedStructure, EFIImageHandle32Tag,
+    EFIImageHandle64Tag, EFIMemoryDesc, EFIMemoryMapTag, EFISdt32Tag, EFISdt64Tag, ElfSectionExt,
+    ElfSectionsTag, FramebufferTag, FramebufferType, ImageLoadPhysAddrTag, MaybeDynSized,
+    MemoryArea, MemoryAreaType, MemoryMapTag, ModuleTag, OwnedBootInformation, RsdpV1Tag,
+    RsdpV2Tag, SmbiosTag, TagHeader, TagType, VBEInfoTag,
+};
+use serde_json::{Map, Value, json};
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+/// Size of [`BootInformationHeader`].
+const MBI_HEADER_SIZE: usize = size_of::<BootInformationHeader>();
+
+/// Resolves ELF section names from the string table in the dump, if the dump
+/// contains it.
+#[derive(Debug)]
+struct SectionNames<'a> {
+    dump: &'a [u8],
+    /// Physical address of the first byte of the dump.
+    base: Option<u64>,
+}
+
+impl SectionNames<'_> {
+    fn resolve(&self, tag: &ElfSectionsTag, section: &SectionHeader) -> Option<String> {
+        let base = self.base?;
+        let strtab = tag.sections().nth(tag.shndx() as usize)?;
+        let start = usize::try_from(strtab.sh_addr.checked_sub(base)?).ok()?;
+        let end = start.checked_add(usize::try_from(strtab.sh_size).ok()?)?;
+        let strtab = self.dump.get(start..end)?;
+        let name = strtab.get(section.sh_name as usize..)?;
+        let name = core::ffi::CStr::from_bytes_until_nul(name).ok()?;
+        Some(name.to_string_lossy().into_owned())
+    }
+}
+
+fn string(result: Result<&str, impl ToString>) -> Value {
+    result
+        .map_or_else(|e| format!("<{}>", e.to_string()), str::to_string)
+        .into()
+}
+
+fn elf_sections(tag: &ElfSectionsTag, names: &SectionNames) -> Value {
+    tag.sections()
+        .map(|section| {
+            json!({
+                "name": names.resolve(tag, &section),
+                "type": format!("{:?}", section.section_type()),
+                "flags": section.flags().bits(),
+                "addr": section.sh_addr,
+                "size": section.sh_size,
+            })
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn framebuffer(tag: &FramebufferTag) -> Vec<(&'static str, Value)> {
+    let mut fields = vec![
+        ("address", tag.address().into()),
+        ("pitch", tag.pitch().into()),
+        ("width", tag.width().into()),
+        ("height", tag.height().into()),
+        ("bpp", tag.bpp().into()),
+    ];
+    match tag.buffer_type() {
+        Ok(FramebufferType::Indexed { palette }) => {
+            fields.push(("buffer_type", "Indexed".into()));
+            let palette = palette
+                .iter()
+                .map(|color| json!([color.red, color.green, color.blue]))
+                .collect::<Vec<_>>();
+            fields.push(("palette", palette.into()));
+        }
+        Ok(FramebufferType::RGB { red, green, blue }) => {
+            fields.push(("buffer_type", "RGB".into()));
+            for (name, field) in [("red", red), ("green", green), ("blue", blue)] {
+                fields.push((
+                    name,
+                    json!({ "position": field.position, "size": field.size }),
+                ));
+            }
+        }
+        Ok(FramebufferType::Text) => fields.push(("buffer_type", "Text".into())),
+        Err(e) => fields.push(("buffer_type", format!("<{e}>").into())),
+    }
+    fields
+}
+
+/// Returns the valid range of the `size` of the tags of type `typ`, or `None`
+/// for tags that are never cast.
+///
+/// Tags of sized types must contain all of their fields, but may omit the
+/// padding to the size of their type. Dynamically sized tags must contain
+/// their fixed fields.
+fn size_range(typ: TagType) -> Option<RangeInclusive<usize>> {
+    /// Size of the tag header followed by `fields` bytes of fields, up to the
+    /// size of the type `T`.
+    const fn sized<T: MaybeDynSized>(fields: usize) -> RangeInclusive<usize> {
+        size_of::<TagHeader>() + fields..=T::BASE_SIZE
+    }
+    /// At least the `BASE_SIZE` of the type `T`.
+    const fn dst<T: MaybeDynSized + ?Sized>() -> RangeInclusive<usize> {
+        T::BASE_SIZE..=usize::MAX
+    }
+    Some(match typ {
+        TagType::Cmdline => dst::<CommandLineTag>(),
+        TagType::BootLoaderName => dst::<BootLoaderNameTag>(),
+        TagType::Module => dst::<ModuleTag>(),
+        TagType::BasicMeminfo => sized::<BasicMemoryInfoTag>(8),
+        TagType::Bootdev => sized::<BootdevTag>(12),
+        TagType::Mmap => dst::<MemoryMapTag>(),
+        TagType::Vbe => sized::<VBEInfoTag>(8 + 512 + 256),
+        TagType::Framebuffer => dst::<FramebufferTag>(),
+        TagType::ElfSections => dst::<ElfSectionsTag>(),
+        TagType::Apm => sized::<ApmTag>(20),
+        TagType::Efi32 => sized::<EFISdt32Tag>(4),
+        TagType::Efi64 => sized::<EFISdt64Tag>(8),
+        TagType::Smbios => dst::<SmbiosTag>(),
+        TagType::AcpiV1 => sized::<RsdpV1Tag>(20),
+        TagType::AcpiV2 => sized::<RsdpV2Tag>(36),
+        TagType::EfiMmap => dst::<EFIMemoryMapTag>(),
+        TagType::Efi32Ih => sized::<EFIImageHandle32Tag>(4),
+        TagType::Efi64Ih => sized::<EFIImageHandle64Tag>(8),
+        TagType::LoadBaseAddr => sized::<ImageLoadPhysAddrTag>(4),
+        TagType::End | TagType::Network | TagType::EfiBs | TagType::Custom(_) => return None,
+    })
+}
+
+/// Reads the native-endian `u32` at `offset` in the payload of a tag.
+fn payload_u32(tag: &DynSizedStructure<TagHeader>, offset: usize) -> Option<u32> {
+    let bytes = tag.payload().get(offset..offset + 4)?;
+    Some(u32::from_ne_bytes(bytes.try_into().unwrap()))
+}
+
+/// Checks the fields that the getters of `multiboot2` assert, so that
+/// decoding the tag doesn't panic.
+fn validate_tag(tag: &DynSizedStructure<TagHeader>) -> Result<(), String> {
+    let typ = TagType::from(tag.header().typ);
+    let Some(range) = size_range(typ) else {
+        return Ok(());
+    };
+    let size = tag.header().size as usize;
+    if !range.contains(&size) {
+        return Err(format!("invalid size {size:#x}"));
+    }
+    match typ {
+        TagType::Module => {
+            let tag = tag.cast::<ModuleTag>();
+            if tag.end_address() < tag.start_address() {
+                return Err(format!(
+                    "end address {:#x} is below start address {:#x}",
+                    tag.end_address(),
+                    tag.start_address()
+                ));
+            }
+        }
+        TagType::Mmap => {
+            // The payload starts with `entry_size`.
+            let entry_size = payload_u32(tag, 0).unwrap();
+            if entry_size as usize != size_of::<MemoryArea>() {
+                return Err(format!("unsupported entry size {entry_size:#x}"));
+            }
+            if (size - MemoryMapTag::BASE_SIZE) % size_of::<MemoryArea>() != 0 {
+                return Err(format!("invalid size {size:#x}"));
+            }
+        }
+        TagType::ElfSections => {
+            let entry_size = tag.cast::<ElfSectionsTag>().entry_size();
+            if entry_size != 40 && entry_size != 64 {
+                return Err(format!("unsupported entry size {entry_size:#x}"));
+            }
+        }
+        TagType::EfiMmap => {
+            // The payload starts with `desc_size` and `desc_version`.
+            let desc_size = payload_u32(tag, 0).unwrap() as usize;
+            let desc_version = payload_u32(tag, 4).unwrap();
+            if desc_version != EFIMemoryDesc::VERSION {
+                return Err(format!("unsupported descriptor version {desc_version}"));
+            }
+            if desc_size < size_of::<EFIMemoryDesc>()
+                || desc_size % align_of::<EFIMemoryDesc>() != 0
+            {
+                return Err(format!("unsupported descriptor size {desc_size:#x}"));
+            }
+        }
+        TagType::Framebuffer => {
+            // The type-specific buffer follows the fixed fields.
+            let buffer = &tag.payload()[FramebufferTag::BASE_SIZE - size_of::<TagHeader>()..];
+            let required = match tag.payload()[21] {
+                // Indexed: number of colors and the palette
+                0 => buffer
+                    .get(..2)
+                    .map(|n| 2 + 3 * usize::from(u16::from_ne_bytes(n.try_into().unwrap()))),
+                // RGB: position and size of the three fields
+                1 => Some(6),
+                _ => Some(0),
+            };
+            if required.is_none_or(|required| required > buffer.len()) {
+                return Err("color info exceeds the tag".to_string());
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Checks all tags before they are decoded and reports the first malformed
+/// one.
+fn validate(mbi: &BootInformation) -> Result<(), String> {
+    mbi.tags().enumerate().try_for_each(|(index, tag)| {
+        validate_tag(tag).map_err(|e| {
+            let typ = TagType::from(tag.header().typ);
+            format!("malformed tag #{index} ({typ:?}): {e}")
+        })
+    })
+}
+
+/// Returns the fields of a tag that are specific to its type.
+fn tag_fields(
+    tag: &DynSizedStructure<TagHeader>,
+    names: &SectionNames,
+) -> Vec<(&'static str, Value)> {
+    match TagType::from(tag.header().typ) {
+        TagType::Cmdline => vec![("cmdline", string(tag.cast::<CommandLineTag>().cmdline()))],
+        TagType::BootLoaderName => vec![("name", string(tag.cast::<BootLoaderNameTag>().name()))],
+        TagType::Module => {
+            let tag = tag.cast::<ModuleTag>();
+            vec![
+                ("start_address", tag.start_address().into()),
+                ("end_address", tag.end_address().into()),
+                ("cmdline", string(tag.cmdline())),
+            ]
+        }
+        TagType::BasicMeminfo => {
+            let tag = tag.cast::<BasicMemoryInfoTag>();
+            vec![
+                ("memory_lower", tag.memory_lower().into()),
+                ("memory_upper", tag.memory_upper().into()),
+            ]
+        }
+        TagType::Bootdev => {
+            let tag = tag.cast::<BootdevTag>();
+            vec![
+                ("biosdev", tag.biosdev().into()),
+                ("slice", tag.slice().into()),
+                ("part", tag.part().into()),
+            ]
+        }
+        TagType::Mmap => {
+            let areas = tag
+                .cast::<MemoryMapTag>()
+                .memory_areas()
+                .iter()
+                .map(|area| {
+                    json!({
+                        "start_address": area.start_address(),
+                        "size": area.size(),
+                        "type": format!("{:?}", MemoryAreaType::from(area.typ())),
+                    })
+                })
+                .collect::<Vec<_>>();
+            vec![("areas", areas.into())]
+        }
+        TagType::Vbe => {
+            let tag = tag.cast::<VBEInfoTag>();
+            vec![
+                ("mode", tag.mode().into()),
+                ("interface_segment", tag.interface_segment().into()),
+                ("interface_offset", tag.interface_offset().into()),
+                ("interface_length", tag.interface_length().into()),
+            ]
+        }
+        TagType::Framebuffer => framebuffer(tag.cast::<FramebufferTag>()),
+        TagType::ElfSections => {
+            let tag = tag.cast::<ElfSectionsTag>();
+            vec![
+                ("entry_size", tag.entry_size().into()),
+                ("shndx", tag.shndx().into()),
+                ("sections", elf_sections(tag, names)),
+            ]
+        }
+        TagType::Apm => {
+            let tag = tag.cast::<ApmTag>();
+            vec![
+                ("version", tag.version().into()),
+                ("cseg", tag.cseg().into()),
+                ("offset", tag.offset().into()),
+                ("cseg_16", tag.cset_16().into()),
+                ("dseg", tag.dseg().into()),
+                ("flags", tag.flags().into()),
+                ("cseg_len", tag.cseg_len().into()),
+                ("cseg_16_len", tag.cseg_16_len().into()),
+                ("dseg_len", tag.dseg_len().into()),
+            ]
+        }
+        TagType::Efi32 => vec![(
+            "sdt_address",
+            tag.cast::<EFISdt32Tag>().sdt_address().into(),
+        )],
+        TagType::Efi64 => vec![(
+            "sdt_address",
+            tag.cast::<EFISdt64Tag>().sdt_address().into(),
+        )],
+        TagType::Smbios => {
+            let tag = tag.cast::<SmbiosTag>();
+            vec![
+                ("major", tag.major().into()),
+                ("minor", tag.minor().into()),
+                ("tables_len", tag.tables().len().into()),
+            ]
+        }
+        TagType::AcpiV1 => {
+            let tag = tag.cast::<RsdpV1Tag>();
+            vec![
+                ("signature", string(tag.signature())),
+                ("oem_id", string(tag.oem_id())),
+                ("revision", tag.revision().into()),
+                ("rsdt_address", tag.rsdt_address().into()),
+                ("checksum_valid", tag.checksum_is_valid().into()),
+            ]
+        }
+        TagType::AcpiV2 => {
+            let tag = tag.cast::<RsdpV2Tag>();
+            vec![
+                ("signature", string(tag.signature())),
+                ("oem_id", string(tag.oem_id())),
+                ("revision", tag.revision().into()),
+                ("xsdt_address", tag.xsdt_address().into()),
+                ("checksum_valid", tag.checksum_is_valid().into()),
+            ]
+        }
+        TagType::EfiMmap => {
+            let descs = tag
+                .cast::<EFIMemoryMapTag>()
+                .memory_areas()
+                .map(|desc| {
+                    json!({
+                        "type": desc.ty.0,
+                        "phys_start": desc.phys_start,
+                        "virt_start": desc.virt_start,
+                        "page_count": desc.page_count,
+                        "attributes": desc.att.bits(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            vec![("descriptors", descs.into())]
+        }
+        TagType::Efi32Ih => {
+            let handle = tag.cast::<EFIImageHandle32Tag>().image_handle();
+            vec![("image_handle", handle.into())]
+        }
+        TagType::Efi64Ih => {
+            let handle = tag.cast::<EFIImageHandle64Tag>().image_handle();
+            vec![("image_handle", handle.into())]
+        }
+        TagType::LoadBaseAddr => {
+            let addr = tag.cast::<ImageLoadPhysAddrTag>().load_base_addr();
+            vec![("load_base_addr", addr.into())]
+        }
+        TagType::End | TagType::Network | TagType::EfiBs | TagType::Custom(_) => Vec::new(),
+    }
+}
+
+fn to_json(mbi: &BootInformation, names: &SectionNames) -> Value {
+    let tags = mbi
+        .tags()
+        .map(|tag| {
+            let mut object = Map::new();
+            let typ = TagType::from(tag.header().typ);
+            object.insert("type".into(), format!("{typ:?}").into());
+            object.insert("type_id".into(), typ.val().into());
+            object.insert("size".into(), tag.header().size.into());
+            object.extend(
+                tag_fields(tag, names)
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value)),
+            );
+            Value::Object(object)
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "total_size": mbi.total_size(),
+        "tags": tags,
+    })
+}
+
+fn to_text(mbi: &BootInformation, names: &SectionNames) -> String {
+    let mut text = format!("{mbi:#?}");
+    if let Some(tag) = mbi.rsdp_v1_tag() {
+        let _ = write!(
+            text,
+            "\nRSDP v1 checksum valid: {}",
+            tag.checksum_is_valid()
+        );
+    }
+    if let Some(tag) = mbi.rsdp_v2_tag() {
+        let _ = write!(
+            text,
+            "\nRSDP v2 checksum valid: {}",
+            tag.checksum_is_valid()
+        );
+    }
+    if let Some(tag) = mbi.elf_sections_tag() {
+        text.push_str("\nELF sections:");
+        for section in tag.sections() {
+            let name = names.resolve(tag, &section);
+            let _ = write!(
+                text,
+                "\n  {:<20} {:<24} addr={:#x} size={:#x}",
+                name.as_deref().unwrap_or("?"),
+                format!("{:?}", section.section_type()),
+                section.sh_addr,
+                section.sh_size
+            );
+        }
+    }
+    text
+}
+
+/// Decodes the MBI at `offset` in the dump and returns the output.
+///
+/// `base` is the physical address of the first byte of the dump. If it is
+/// known, ELF section names are resolved from the dump. `byte_order` is the
+/// byte order of the machine that created the dump.
+pub fn inspect(
+    dump: &[u8],
+    offset: usize,
+    base: Option<u64>,
+    byte_order: ByteOrder,
+    json: bool,
+) -> Result<String, String> {
+    let bytes = dump
+        .get(offset..)
+        .filter(|bytes| bytes.len() >= MBI_HEADER_SIZE)
+        .ok_or("dump is too short for an MBI at the given offset")?;
+    let total_size = byte_order.read_u32(bytes[..4].try_into().unwrap()) as usize;
+    if total_size < MBI_HEADER_SIZE {
+        return Err(format!(
+            "MBI has an invalid total size of {total_size:#x} bytes"
+        ));
+    }
+    if total_size > bytes.len() {
+        return Err(format!(
+            "MBI has a total size of {total_size:#x} bytes but the dump has only {:#x} bytes",
+            bytes.len()
+        ));
+    }
+    let owned =
+        OwnedBootInformation::load(bytes, byte_order).map_err(|e| format!("invalid MBI: {e}"))?;
+    let mbi = owned.boot_information();
+    // Some getters panic on malformed tags, e.g., on unknown entry sizes.
+    validate(&mbi)?;
+    let names = SectionNames { dump, base };
+    if json {
+        Ok(serde_json::to_string_pretty(&to_json(&mbi, &names)).expect("should serialize JSON"))
+    } else {
+        Ok(to_text(&mbi, &names))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiboot2::{Builder, MaybeDynSized, MemoryArea, MemoryAreaTypeId};
+
+    fn dump() -> Vec<u8> {
+        let mbi = Builder::new()
+            .cmdline(CommandLineTag::new("console=ttyS0"))
+            .mmap(MemoryMapTag::new(&[MemoryArea::new(
+                0x100000,
+                0x7f00000,
+                MemoryAreaTypeId::from(1),
+            )]))
+            .rsdpv1(RsdpV1Tag::new(*b"OEMOEM", 0, 0x7fe0000))
+            .build();
+        // Place the MBI behind some other memory, as in a memory dump.
+        let mut dump = vec![0; 0x100];
+        dump.extend_from_slice(&mbi.as_bytes());
+        dump
+    }
+
+    #[test]
+    fn json() {
+        let output = inspect(&dump(), 0x100, None, ByteOrder::NATIVE, true).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+        let tags = value["tags"].as_array().unwrap();
+        assert_eq!(tags[0]["type"], "Cmdline");
+        assert_eq!(tags[0]["cmdline"], "console=ttyS0");
+        assert_eq!(tags[1]["type"], "Mmap");
+        assert_eq!(tags[1]["areas"][0]["start_address"], 0x100000);
+        assert_eq!(tags[1]["areas"][0]["type"], "Available");
+        assert_eq!(tags[2]["type"], "AcpiV1");
+        assert_eq!(tags[2]["signature"], "RSD PTR ");
+        assert_eq!(tags[2]["checksum_valid"], true);
+    }
+
+    #[test]
+    fn text() {
+        let output = inspect(&dump(), 0x100, None, ByteOrder::NATIVE, false).unwrap();
+        assert!(output.starts_with("BootInformation {"));
+        assert!(output.contains("console=ttyS0"));
+        assert!(output.ends_with("RSDP v1 checksum valid: true"));
+    }
+
+    #[test]
+    fn errors() {
+        let dump = dump();
+        assert!(inspect(&dump, dump.len(), None, ByteOrder::NATIVE, false).is_err());
+        assert!(
+            inspect(
+                &dump[..dump.len() - 8],
+                0x100,
+                None,
+                ByteOrder::NATIVE,
+                false
+            )
+            .is_err()
+        );
+        assert!(inspect(&dump, 0, None, ByteOrder::NATIVE, false).is_err());
+    }
+
+    #[test]
+    fn malformed_tag() {
+        let mut patched = dump();
+        // The memory map follows the MBI header and the 24-byte cmdline tag;
+        // its payload starts with the entry size.
+        let entry_size = 0x100 + 8 + 24 + 8;
+        patched[entry_size..entry_size + 4].copy_from_slice(&16_u32.to_ne_bytes());
+        let err = inspect(&patched, 0x100, None, ByteOrder::NATIVE, false).unwrap_err();
+        assert_eq!(err, "malformed tag #1 (Mmap): unsupported entry size 0x10");
+
+        // Shrink the memory map by four bytes, which keeps the next tag in
+        // place due to the padding.
+        let mut shrunk = dump();
+        let size = 0x100 + 8 + 24 + 4;
+        shrunk[size..size + 4].copy_from_slice(&36_u32.to_ne_bytes());
+        for json in [false, true] {
+            let err = inspect(&shrunk, 0x100, None, ByteOrder::NATIVE, json).unwrap_err();
+            assert_eq!(err, "malformed tag #1 (Mmap): invalid size 0x24");
+        }
+    }
+
+    #[test]
+    fn size_ranges() {
+        for typ in (0..=21).map(TagType::from) {
+            if let Some(range) = size_range(typ) {
+                assert!(!range.is_empty(), "{typ:?}");
+            }
+        }
+        // Without `memory_upper`
+        assert!(!size_range(TagType::BasicMeminfo).unwrap().contains(&12));
+    }
+}
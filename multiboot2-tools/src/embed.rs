@@ -0,0 +1,866 @@
+//! Module for [`embed_header`].
+
+use elf::ElfBytes;
+use elf::abi::{PT_LOAD, SHF_ALLOC, SHN_UNDEF, SHT_NOBITS, SHT_PROGBITS};
+use elf::endian::AnyEndian;
+use elf::file::Class;
+use elf::section::SectionHeader;
+use elf::segment::ProgramHeader;
+use multiboot2_header::{
+    Builder, HEADER_SEARCH_LIMIT, HeaderBuildError, MaybeDynSized, OwnedHeader,
+};
+use thiserror::Error;
+
+/// Name of the section that [`embed_header`] creates for the header.
+pub const HEADER_SECTION_NAME: &str = ".multiboot2_header";
+
+/// Alignment of a Multiboot2 header in the image.
+const HEADER_ALIGNMENT: usize = 8;
+
+/// Lower bound of the reserved section indices. From this number of sections
+/// on, the ELF file uses extended section numbering.
+const SHN_LORESERVE: usize = 0xff00;
+
+/// Errors that may occur in [`embed_header`].
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum EmbedError {
+    /// The builder created an invalid header.
+    #[error("invalid multiboot2 header")]
+    Build(#[from] HeaderBuildError),
+    /// The image is not a valid ELF file.
+    #[error("image is not a valid ELF file")]
+    InvalidElf,
+    /// The ELF file has no `PT_LOAD` segment.
+    #[error("ELF file has no PT_LOAD segment")]
+    NoLoadSegment,
+    /// There is no room for the header in the first
+    /// [`HEADER_SEARCH_LIMIT`] bytes of the first `PT_LOAD` segment, and no
+    /// room can be made without relocating code.
+    #[error("no room for the header at the beginning of the first PT_LOAD segment")]
+    NoSpace,
+}
+
+/// Describes where [`embed_header`] placed the header.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Placement {
+    /// An existing header was large enough and was replaced in place.
+    Replaced,
+    /// The header was written into an existing [`HEADER_SECTION_NAME`]
+    /// section.
+    ExistingSection,
+    /// The header was written into unused zero bytes of the first `PT_LOAD`
+    /// segment, e.g., between the program headers and the first section, or
+    /// directly after it. In the latter case, the segment was extended.
+    Gap,
+    /// The header was inserted in front of the first `PT_LOAD` segment, which
+    /// was extended downwards to include it. All following file contents
+    /// were moved.
+    Inserted,
+}
+
+/// The result of [`embed_header`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmbeddedHeader {
+    image: Vec<u8>,
+    offset: usize,
+    placement: Placement,
+}
+
+impl EmbeddedHeader {
+    /// Returns the patched ELF file.
+    #[must_use]
+    pub fn image(&self) -> &[u8] {
+        &self.image
+    }
+
+    /// Returns the patched ELF file.
+    #[must_use]
+    pub fn into_image(self) -> Vec<u8> {
+        self.image
+    }
+
+    /// Returns the offset of the header in the patched ELF file.
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns where the header was placed.
+    #[must_use]
+    pub const fn placement(&self) -> Placement {
+        self.placement
+    }
+}
+
+/// Writes ELF structures with the class and endianness of the file.
+#[derive(Copy, Clone, Debug)]
+struct ElfWriter {
+    class: Class,
+    endian: AnyEndian,
+}
+
+impl ElfWriter {
+    fn put(self, image: &mut [u8], offset: usize, value: u64, width: usize) {
+        let bytes = match self.endian {
+            AnyEndian::Little => value.to_le_bytes(),
+            AnyEndian::Big => value.to_be_bytes(),
+        };
+        let bytes = match self.endian {
+            AnyEndian::Little => &bytes[..width],
+            AnyEndian::Big => &bytes[bytes.len() - width..],
+        };
+        image[offset..offset + width].copy_from_slice(bytes);
+    }
+
+    /// Writes a field that has the size of an address in the file's class.
+    fn put_word(self, image: &mut [u8], offset: usize, value: u64) {
+        match self.class {
+            Class::ELF32 => self.put(image, offset, value, 4),
+            Class::ELF64 => self.put(image, offset, value, 8),
+        }
+    }
+
+    fn write_shoff(self, image: &mut [u8], shoff: u64) {
+        match self.class {
+            Class::ELF32 => self.put_word(image, 32, shoff),
+            Class::ELF64 => self.put_word(image, 40, shoff),
+        }
+    }
+
+    fn write_shnum(self, image: &mut [u8], shnum: u16) {
+        match self.class {
+            Class::ELF32 => self.put(image, 48, shnum.into(), 2),
+            Class::ELF64 => self.put(image, 60, shnum.into(), 2),
+        }
+    }
+
+    fn write_phdr(self, image: &mut [u8], at: usize, phdr: &ProgramHeader) {
+        let fields: [(usize, u64, usize); 8] = match self.class {
+            Class::ELF32 => [
+                (0, phdr.p_type.into(), 4),
+                (4, phdr.p_offset, 4),
+                (8, phdr.p_vaddr, 4),
+                (12, phdr.p_paddr, 4),
+                (16, phdr.p_filesz, 4),
+                (20, phdr.p_memsz, 4),
+                (24, phdr.p_flags.into(), 4),
+                (28, phdr.p_align, 4),
+            ],
+            Class::ELF64 => [
+                (0, phdr.p_type.into(), 4),
+                (4, phdr.p_flags.into(), 4),
+                (8, phdr.p_offset, 8),
+                (16, phdr.p_vaddr, 8),
+                (24, phdr.p_paddr, 8),
+                (32, phdr.p_filesz, 8),
+                (40, phdr.p_memsz, 8),
+                (48, phdr.p_align, 8),
+            ],
+        };
+        for (offset, value, width) in fields {
+            self.put(image, at + offset, value, width);
+        }
+    }
+
+    fn write_shdr(self, image: &mut [u8], at: usize, shdr: &SectionHeader) {
+        let fields: [(usize, u64, usize); 10] = match self.class {
+            Class::ELF32 => [
+                (0, shdr.sh_name.into(), 4),
+                (4, shdr.sh_type.into(), 4),
+                (8, shdr.sh_flags, 4),
+                (12, shdr.sh_addr, 4),
+                (16, shdr.sh_offset, 4),
+                (20, shdr.sh_size, 4),
+                (24, shdr.sh_link.into(), 4),
+                (28, shdr.sh_info.into(), 4),
+                (32, shdr.sh_addralign, 4),
+                (36, shdr.sh_entsize, 4),
+            ],
+            Class::ELF64 => [
+                (0, shdr.sh_name.into(), 4),
+                (4, shdr.sh_type.into(), 4),
+                (8, shdr.sh_flags, 8),
+                (16, shdr.sh_addr, 8),
+                (24, shdr.sh_offset, 8),
+                (32, shdr.sh_size, 8),
+                (40, shdr.sh_link.into(), 4),
+                (44, shdr.sh_info.into(), 4),
+                (48, shdr.sh_addralign, 8),
+                (56, shdr.sh_entsize, 8),
+            ],
+        };
+        for (offset, value, width) in fields {
+            self.put(image, at + offset, value, width);
+        }
+    }
+}
+
+/// The parsed tables of the ELF file that [`embed_header`] may modify.
+#[derive(Debug)]
+struct ElfLayout {
+    writer: ElfWriter,
+    ehsize: u64,
+    phoff: u64,
+    phentsize: u64,
+    phdrs: Vec<ProgramHeader>,
+    shoff: u64,
+    shentsize: u64,
+    shdrs: Vec<SectionHeader>,
+    /// Index of the section name string table, if sections can be added.
+    shstrndx: Option<usize>,
+    /// Names of the sections in the order of `shdrs`.
+    names: Vec<String>,
+}
+
+impl ElfLayout {
+    fn parse(elf: &[u8]) -> Result<Self, EmbedError> {
+        let file = ElfBytes::<AnyEndian>::minimal_parse(elf).map_err(|_| EmbedError::InvalidElf)?;
+        let phdrs = file
+            .segments()
+            .map(|phdrs| phdrs.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let (shdrs, strtab) = file
+            .section_headers_with_strtab()
+            .map_err(|_| EmbedError::InvalidElf)?;
+        let shdrs = shdrs
+            .map(|shdrs| shdrs.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let names = shdrs
+            .iter()
+            .map(|shdr| {
+                strtab
+                    .and_then(|strtab| strtab.get(shdr.sh_name as usize).ok())
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect();
+        let shstrndx = usize::from(file.ehdr.e_shstrndx);
+        // Extended section numbering is not supported when adding sections.
+        let shstrndx = (strtab.is_some()
+            && shstrndx != usize::from(SHN_UNDEF)
+            && shstrndx < shdrs.len()
+            && shdrs.len() + 1 < SHN_LORESERVE
+            && file.ehdr.e_shnum != 0)
+            .then_some(shstrndx);
+        Ok(Self {
+            writer: ElfWriter {
+                class: file.ehdr.class,
+                endian: file.ehdr.endianness,
+            },
+            ehsize: file.ehdr.e_ehsize.into(),
+            phoff: file.ehdr.e_phoff,
+            phentsize: file.ehdr.e_phentsize.into(),
+            phdrs,
+            shoff: file.ehdr.e_shoff,
+            shentsize: file.ehdr.e_shentsize.into(),
+            shdrs,
+            shstrndx,
+            names,
+        })
+    }
+
+    /// Returns the index of the `PT_LOAD` segment with the lowest file offset.
+    fn first_load_segment(&self) -> Option<usize> {
+        self.phdrs
+            .iter()
+            .enumerate()
+            .filter(|(_, phdr)| phdr.p_type == PT_LOAD)
+            .min_by_key(|(_, phdr)| phdr.p_offset)
+            .map(|(index, _)| index)
+    }
+
+    /// Returns the end of the ELF header and the program header table.
+    fn headers_end(&self) -> u64 {
+        let phdrs_end = self.phoff + self.phentsize * self.phdrs.len() as u64;
+        self.ehsize.max(phdrs_end)
+    }
+
+    /// Returns the file ranges that are in use, except for the file bytes of
+    /// the segment `load`.
+    fn occupied(&self, load: usize) -> Vec<(u64, u64)> {
+        let mut ranges = vec![(0, self.headers_end())];
+        if !self.shdrs.is_empty() {
+            ranges.push((
+                self.shoff,
+                self.shoff + self.shentsize * self.shdrs.len() as u64,
+            ));
+        }
+        ranges.extend(
+            self.shdrs
+                .iter()
+                .filter(|shdr| shdr.sh_type != SHT_NOBITS)
+                .map(|shdr| (shdr.sh_offset, shdr.sh_offset + shdr.sh_size)),
+        );
+        ranges.extend(
+            self.phdrs
+                .iter()
+                .enumerate()
+                .filter(|&(index, _)| index != load)
+                .map(|(_, phdr)| (phdr.p_offset, phdr.p_offset + phdr.p_filesz)),
+        );
+        ranges
+    }
+
+    /// Returns whether the memory ranges of `size` bytes at `vaddr` and
+    /// `paddr` overlap any `PT_LOAD` segment other than `load`.
+    fn overlaps_load_segment(&self, load: usize, vaddr: u64, paddr: u64, size: u64) -> bool {
+        self.phdrs
+            .iter()
+            .enumerate()
+            .filter(|&(index, other)| index != load && other.p_type == PT_LOAD)
+            .any(|(_, other)| {
+                let overlaps = |a: u64, b: u64| a < b + other.p_memsz && b < a + size;
+                overlaps(vaddr, other.p_vaddr) || overlaps(paddr, other.p_paddr)
+            })
+    }
+
+    /// Returns whether the section headers prove that the file range up to
+    /// `end` is not part of the contents of the segment `load`, i.e., whether
+    /// an allocated section of the segment starts at or behind `end`.
+    ///
+    /// Together with [`Self::occupied`], this ensures that a range within the
+    /// segment is padding, e.g., between the program headers and the first
+    /// section. Without section headers, zero bytes may be code or data.
+    fn unused_in_segment(&self, load: usize, end: u64) -> bool {
+        let phdr = &self.phdrs[load];
+        self.shdrs.iter().any(|shdr| {
+            shdr.sh_flags & u64::from(SHF_ALLOC) != 0
+                && shdr.sh_type != SHT_NOBITS
+                && shdr.sh_offset >= end
+                && shdr.sh_offset < phdr.p_offset + phdr.p_filesz
+        })
+    }
+
+    /// Writes the program header table.
+    fn write_phdrs(&self, image: &mut [u8]) {
+        for (index, phdr) in self.phdrs.iter().enumerate() {
+            let at = (self.phoff + self.phentsize * index as u64) as usize;
+            self.writer.write_phdr(image, at, phdr);
+        }
+    }
+
+    /// Points the [`HEADER_SECTION_NAME`] section to the header. If there is
+    /// none, it is added by appending a new section name string table and a
+    /// new section header table to the image.
+    fn set_header_section(&mut self, image: &mut Vec<u8>, offset: u64, addr: u64, size: u64) {
+        let section = SectionHeader {
+            sh_name: 0,
+            sh_type: SHT_PROGBITS,
+            sh_flags: SHF_ALLOC.into(),
+            sh_addr: addr,
+            sh_offset: offset,
+            sh_size: size,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: HEADER_ALIGNMENT as u64,
+            sh_entsize: 0,
+        };
+        if let Some(index) = self
+            .names
+            .iter()
+            .position(|name| name == HEADER_SECTION_NAME)
+        {
+            let shdr = &mut self.shdrs[index];
+            *shdr = SectionHeader {
+                sh_name: shdr.sh_name,
+                ..section
+            };
+            return;
+        }
+        let Some(shstrndx) = self.shstrndx else {
+            return;
+        };
+        let strtab = &self.shdrs[shstrndx];
+        let mut names =
+            image[strtab.sh_offset as usize..(strtab.sh_offset + strtab.sh_size) as usize].to_vec();
+        let name = names.len() as u32;
+        names.extend_from_slice(HEADER_SECTION_NAME.as_bytes());
+        names.push(0);
+
+        let strtab_offset = image.len() as u64;
+        image.extend_from_slice(&names);
+        self.shdrs[shstrndx].sh_offset = strtab_offset;
+        self.shdrs[shstrndx].sh_size = names.len() as u64;
+        self.shdrs.push(SectionHeader {
+            sh_name: name,
+            ..section
+        });
+
+        image.resize(image.len().next_multiple_of(HEADER_ALIGNMENT), 0);
+        self.shoff = image.len() as u64;
+        image.resize(
+            image.len() + (self.shentsize as usize) * self.shdrs.len(),
+            0,
+        );
+        self.writer.write_shoff(image, self.shoff);
+        self.writer.write_shnum(image, self.shdrs.len() as u16);
+    }
+
+    /// Writes the section header table.
+    fn write_shdrs(&self, image: &mut [u8]) {
+        for (index, shdr) in self.shdrs.iter().enumerate() {
+            let at = (self.shoff + self.shentsize * index as u64) as usize;
+            self.writer.write_shdr(image, at, shdr);
+        }
+    }
+}
+
+/// Replaces the first valid existing header that is large enough and
+/// invalidates all others by clearing their magic value. Returns the offset
+/// of the replaced header.
+fn replace_existing(image: &mut [u8], header: &[u8]) -> Option<usize> {
+    let existing = multiboot2_header::Header::magic_matches(image)
+        .filter_map(|offset| {
+            OwnedHeader::load(image, offset)
+                .ok()
+                .map(|old| (offset, old.header().length() as usize))
+        })
+        .collect::<Vec<_>>();
+    let mut replaced = None;
+    for (offset, len) in existing {
+        if replaced.is_none() && len >= header.len() {
+            image[offset..offset + header.len()].copy_from_slice(header);
+            image[offset + header.len()..offset + len].fill(0);
+            replaced = Some(offset);
+        } else {
+            image[offset..offset + 4].fill(0);
+        }
+    }
+    replaced
+}
+
+/// Writes the header into an existing [`HEADER_SECTION_NAME`] section of type
+/// `SHT_PROGBITS` and returns its offset.
+fn write_existing_section(image: &mut [u8], layout: &ElfLayout, header: &[u8]) -> Option<usize> {
+    let shdr = layout
+        .shdrs
+        .iter()
+        .zip(&layout.names)
+        .find(|(shdr, name)| *name == HEADER_SECTION_NAME && shdr.sh_type == SHT_PROGBITS)
+        .map(|(shdr, _)| shdr)?;
+    let offset = usize::try_from(shdr.sh_offset).ok()?;
+    let size = usize::try_from(shdr.sh_size).ok()?;
+    if offset % HEADER_ALIGNMENT != 0
+        || size < header.len()
+        || offset + header.len() > HEADER_SEARCH_LIMIT
+        || offset + size > image.len()
+    {
+        return None;
+    }
+    image[offset..offset + header.len()].copy_from_slice(header);
+    image[offset + header.len()..offset + size].fill(0);
+    Some(offset)
+}
+
+/// Finds unused zero bytes for the header in the file bytes of the segment
+/// `load` or directly after them.
+///
+/// Zero bytes within the segment are only used if the section headers prove
+/// that they are unused, see [`ElfLayout::unused_in_segment`]. Otherwise, the
+/// segment is extended, which requires that it has no zero-initialized part
+/// and that the extended segment doesn't overlap other `PT_LOAD` segments in
+/// memory.
+fn find_gap(image: &[u8], layout: &mut ElfLayout, load: usize, len: usize) -> Option<usize> {
+    let phdr = layout.phdrs[load];
+    let start = usize::try_from(phdr.p_offset).ok()?;
+    let end = if phdr.p_filesz == phdr.p_memsz {
+        image.len()
+    } else {
+        usize::try_from(phdr.p_offset + phdr.p_filesz).ok()?
+    };
+    let end = end.min(HEADER_SEARCH_LIMIT).min(image.len());
+    let occupied = layout.occupied(load);
+    let segment_end = phdr.p_offset + phdr.p_filesz;
+    let offset = (start.next_multiple_of(HEADER_ALIGNMENT)..end.saturating_sub(len - 1))
+        .step_by(HEADER_ALIGNMENT)
+        .find(|&offset| {
+            // When extending the segment, the bytes between its end and the
+            // header become part of it as well.
+            let gap_start = (offset as u64).min(segment_end);
+            let gap_end = (offset + len) as u64;
+            let filesz = gap_end - phdr.p_offset;
+            occupied
+                .iter()
+                .all(|&(start, end)| start == end || end <= gap_start || gap_end <= start)
+                && image[gap_start as usize..gap_end as usize]
+                    .iter()
+                    .all(|&byte| byte == 0)
+                && (offset as u64 >= segment_end || layout.unused_in_segment(load, gap_end))
+                && (filesz <= phdr.p_filesz
+                    || !layout.overlaps_load_segment(load, phdr.p_vaddr, phdr.p_paddr, filesz))
+        })?;
+
+    let filesz = (offset + len) as u64 - phdr.p_offset;
+    if filesz > phdr.p_filesz {
+        layout.phdrs[load].p_filesz = filesz;
+        layout.phdrs[load].p_memsz = filesz;
+    }
+    Some(offset)
+}
+
+/// Inserts room for the header in front of the segment `load` by moving all
+/// following file contents and extending the segment downwards. Returns the
+/// size of the inserted room.
+fn insert_room(
+    image: &mut Vec<u8>,
+    layout: &mut ElfLayout,
+    load: usize,
+    len: usize,
+) -> Option<u64> {
+    let at = layout.phdrs[load].p_offset;
+    // File offsets and addresses of moved segments and sections must stay
+    // congruent modulo their alignment.
+    let alignment = layout
+        .phdrs
+        .iter()
+        .filter(|phdr| phdr.p_type == PT_LOAD)
+        .map(|phdr| phdr.p_align)
+        .chain(layout.shdrs.iter().map(|shdr| shdr.sh_addralign))
+        .fold(HEADER_ALIGNMENT as u64, u64::max);
+    let size = (len as u64).next_multiple_of(alignment);
+
+    let crosses = |start: u64, size: u64| start < at && at < start + size;
+    let phdr = layout.phdrs[load];
+    if at < layout.headers_end()
+        || at % HEADER_ALIGNMENT as u64 != 0
+        || at + len as u64 > HEADER_SEARCH_LIMIT as u64
+        || phdr.p_vaddr < size
+        || phdr.p_paddr < size
+        || layout.overlaps_load_segment(load, phdr.p_vaddr - size, phdr.p_paddr - size, size)
+        || crosses(layout.shoff, layout.shentsize * layout.shdrs.len() as u64)
+        || layout
+            .phdrs
+            .iter()
+            .any(|phdr| crosses(phdr.p_offset, phdr.p_filesz))
+        || layout
+            .shdrs
+            .iter()
+            .filter(|shdr| shdr.sh_type != SHT_NOBITS)
+            .any(|shdr| crosses(shdr.sh_offset, shdr.sh_size))
+    {
+        return None;
+    }
+
+    for (index, phdr) in layout.phdrs.iter_mut().enumerate() {
+        if index == load {
+            phdr.p_vaddr -= size;
+            phdr.p_paddr -= size;
+            phdr.p_filesz += size;
+            phdr.p_memsz += size;
+        } else if phdr.p_offset >= at {
+            phdr.p_offset += size;
+        }
+    }
+    for shdr in &mut layout.shdrs {
+        if shdr.sh_offset >= at {
+            shdr.sh_offset += size;
+        }
+    }
+    if !layout.shdrs.is_empty() && layout.shoff >= at {
+        layout.shoff += size;
+        layout.writer.write_shoff(image, layout.shoff);
+    }
+    let tail = image.split_off(at as usize);
+    image.resize(image.len() + size as usize, 0);
+    image.extend_from_slice(&tail);
+    Some(size)
+}
+
+/// Embeds the Multiboot2 header created by `builder` into an ELF file.
+///
+/// The header is placed, in this order of preference:
+/// 1. in place of an existing valid header that is large enough,
+/// 2. in an existing [`HEADER_SECTION_NAME`] section that is large enough,
+/// 3. in unused zero bytes in or directly after the first `PT_LOAD` segment,
+///    where bytes within the segment count as unused only if the section
+///    headers prove it,
+/// 4. in front of the first `PT_LOAD` segment, which is extended downwards.
+///
+/// Existing headers that are not replaced are invalidated. In the last two
+/// cases, the [`HEADER_SECTION_NAME`] section is updated or, if the file has
+/// section headers, added. The header is always within the first
+/// [`HEADER_SEARCH_LIMIT`] bytes of the file and, except for the first two
+/// cases, part of the first `PT_LOAD` segment, so that an
+/// [`AddressHeaderTag`] can refer to it.
+///
+/// The header is built with [`Builder::try_build`].
+///
+/// [`AddressHeaderTag`]: multiboot2_header::AddressHeaderTag
+pub fn embed_header(elf: &[u8], builder: Builder) -> Result<EmbeddedHeader, EmbedError> {
+    let header = builder.try_build()?;
+    let header = header.as_bytes().as_ref();
+    let mut layout = ElfLayout::parse(elf)?;
+    let load = layout
+        .first_load_segment()
+        .ok_or(EmbedError::NoLoadSegment)?;
+
+    let mut image = elf.to_vec();
+    if let Some(offset) = replace_existing(&mut image, header) {
+        return Ok(EmbeddedHeader {
+            image,
+            offset,
+            placement: Placement::Replaced,
+        });
+    }
+    if let Some(offset) = write_existing_section(&mut image, &layout, header) {
+        return Ok(EmbeddedHeader {
+            image,
+            offset,
+            placement: Placement::ExistingSection,
+        });
+    }
+
+    let (offset, placement) =
+        if let Some(offset) = find_gap(&image, &mut layout, load, header.len()) {
+            (offset, Placement::Gap)
+        } else {
+            insert_room(&mut image, &mut layout, load, header.len()).ok_or(EmbedError::NoSpace)?;
+            (layout.phdrs[load].p_offset as usize, Placement::Inserted)
+        };
+    image[offset..offset + header.len()].copy_from_slice(header);
+
+    let phdr = layout.phdrs[load];
+    let addr = phdr.p_vaddr + (offset as u64 - phdr.p_offset);
+    layout.set_header_section(&mut image, offset as u64, addr, header.len() as u64);
+    layout.write_phdrs(&mut image);
+    layout.write_shdrs(&mut image);
+    Ok(EmbeddedHeader {
+        image,
+        offset,
+        placement,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiboot2_header::{
+        Header, HeaderTagFlag, HeaderTagISA, InformationRequestHeaderTag, MbiTagType,
+        ModuleAlignHeaderTag,
+    };
+
+    /// Writes a minimal ELF32 file with one `PT_LOAD` segment and a `.text`
+    /// section at the file offset `0x1000` and the address `0x201000`. If
+    /// `load_headers` is set, the segment starts at file offset 0 and also
+    /// contains the ELF and program headers.
+    fn elf(load_headers: bool) -> Vec<u8> {
+        let mut image = vec![0; 0x1200];
+        image[..4].copy_from_slice(b"\x7fELF");
+        image[4..7].copy_from_slice(&[1, 1, 1]);
+        let (offset, addr, size) = if load_headers {
+            (0_u32, 0x200000_u32, 0x1100_u32)
+        } else {
+            (0x1000, 0x201000, 0x100)
+        };
+        let shstrtab = b"\0.text\0.shstrtab\0";
+        let fields: [(usize, &[u8]); 31] = [
+            (16, &2_u16.to_le_bytes()),
+            (18, &3_u16.to_le_bytes()),
+            (20, &1_u32.to_le_bytes()),
+            (24, &0x201000_u32.to_le_bytes()),
+            (28, &52_u32.to_le_bytes()),
+            (32, &0x1120_u32.to_le_bytes()),
+            (40, &52_u16.to_le_bytes()),
+            (42, &32_u16.to_le_bytes()),
+            (44, &1_u16.to_le_bytes()),
+            (46, &40_u16.to_le_bytes()),
+            (48, &3_u16.to_le_bytes()),
+            (50, &2_u16.to_le_bytes()),
+            // Program header 0
+            (52, &PT_LOAD.to_le_bytes()),
+            (52 + 4, &offset.to_le_bytes()),
+            (52 + 8, &addr.to_le_bytes()),
+            (52 + 12, &addr.to_le_bytes()),
+            (52 + 16, &size.to_le_bytes()),
+            (52 + 20, &size.to_le_bytes()),
+            (52 + 28, &0x1000_u32.to_le_bytes()),
+            // .text
+            (0x1000, &[0xcc; 0x100]),
+            (0x1148, &1_u32.to_le_bytes()),
+            (0x1148 + 4, &SHT_PROGBITS.to_le_bytes()),
+            (
+                0x1148 + 8,
+                &(SHF_ALLOC | elf::abi::SHF_EXECINSTR).to_le_bytes(),
+            ),
+            (0x1148 + 12, &0x201000_u32.to_le_bytes()),
+            (0x1148 + 16, &0x1000_u32.to_le_bytes()),
+            (0x1148 + 20, &0x100_u32.to_le_bytes()),
+            // .shstrtab
+            (0x1100, shstrtab),
+            (0x1170, &7_u32.to_le_bytes()),
+            (0x1170 + 4, &elf::abi::SHT_STRTAB.to_le_bytes()),
+            (0x1170 + 16, &0x1100_u32.to_le_bytes()),
+            (0x1170 + 20, &(shstrtab.len() as u32).to_le_bytes()),
+        ];
+        for (offset, bytes) in fields {
+            image[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+        image
+    }
+
+    fn builder() -> Builder {
+        Builder::new(HeaderTagISA::I386)
+            .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required))
+    }
+
+    fn large_builder() -> Builder {
+        builder().information_request_tag(InformationRequestHeaderTag::new(
+            HeaderTagFlag::Required,
+            &[MbiTagType::Cmdline.into(), MbiTagType::Mmap.into()],
+        ))
+    }
+
+    /// Returns the offsets of all valid headers.
+    fn valid_headers(image: &[u8]) -> Vec<usize> {
+        Header::magic_matches(image)
+            .filter(|&offset| OwnedHeader::load(image, offset).is_ok())
+            .collect()
+    }
+
+    /// Returns the address and offset of the header section.
+    fn header_section(image: &[u8]) -> (u64, u64) {
+        let file = ElfBytes::<AnyEndian>::minimal_parse(image).unwrap();
+        let shdr = file
+            .section_header_by_name(HEADER_SECTION_NAME)
+            .unwrap()
+            .unwrap();
+        (shdr.sh_addr, shdr.sh_offset)
+    }
+
+    #[test]
+    fn inserted() {
+        let embedded = embed_header(&elf(false), builder()).unwrap();
+        assert_eq!(embedded.placement(), Placement::Inserted);
+        assert_eq!(embedded.offset(), 0x1000);
+        let image = embedded.image();
+        assert_eq!(valid_headers(image), [0x1000]);
+
+        let file = ElfBytes::<AnyEndian>::minimal_parse(image).unwrap();
+        let phdr = file.segments().unwrap().get(0).unwrap();
+        assert_eq!(phdr.p_offset, 0x1000);
+        assert_eq!(phdr.p_vaddr, 0x200000);
+        assert_eq!(phdr.p_filesz, 0x1100);
+        let text = file.section_header_by_name(".text").unwrap().unwrap();
+        assert_eq!(text.sh_offset, 0x2000);
+        assert_eq!(text.sh_addr, 0x201000);
+        assert_eq!(image[0x2000..0x2100], [0xcc; 0x100]);
+        assert_eq!(header_section(image), (0x200000, 0x1000));
+    }
+
+    #[test]
+    fn gap() {
+        let embedded = embed_header(&elf(true), builder()).unwrap();
+        assert_eq!(embedded.placement(), Placement::Gap);
+        // After the ELF and program headers
+        assert_eq!(embedded.offset(), 0x58);
+        assert_eq!(valid_headers(embedded.image()), [0x58]);
+        assert_eq!(header_section(embedded.image()), (0x200058, 0x58));
+        assert_eq!(embedded.image()[0x1000..0x1100], [0xcc; 0x100]);
+    }
+
+    #[test]
+    fn gap_after_segment() {
+        let mut image = elf(true);
+        // The segment only contains the ELF and program headers.
+        image[52 + 16..52 + 24].copy_from_slice(&[0x54, 0, 0, 0, 0x54, 0, 0, 0]);
+        let embedded = embed_header(&image, builder()).unwrap();
+        assert_eq!(embedded.placement(), Placement::Gap);
+        assert_eq!(embedded.offset(), 0x58);
+
+        let file = ElfBytes::<AnyEndian>::minimal_parse(embedded.image()).unwrap();
+        let phdr = file.segments().unwrap().get(0).unwrap();
+        assert_eq!(phdr.p_filesz, 0x78);
+        assert_eq!(phdr.p_memsz, 0x78);
+    }
+
+    #[test]
+    fn no_gap_without_sections() {
+        let mut image = elf(true);
+        // Without section headers, the zero bytes in the segment may be data.
+        image[32..36].fill(0);
+        image[48..50].fill(0);
+        image[50..52].fill(0);
+        assert_eq!(embed_header(&image, builder()), Err(EmbedError::NoSpace));
+        assert!(image[0x58..0x1000].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn existing_note_section() {
+        let embedded = embed_header(&elf(true), large_builder()).unwrap();
+        let mut image = embedded.into_image();
+        // Invalidate the header and turn its section into a note.
+        image[0x58..0x5c].fill(0);
+        let file = ElfBytes::<AnyEndian>::minimal_parse(&image).unwrap();
+        let shoff = file.ehdr.e_shoff as usize;
+        let index = file.ehdr.e_shnum as usize - 1;
+        let at = shoff + 40 * index + 4;
+        image[at..at + 4].copy_from_slice(&elf::abi::SHT_NOTE.to_le_bytes());
+
+        let embedded = embed_header(&image, builder()).unwrap();
+        assert_ne!(embedded.placement(), Placement::ExistingSection);
+    }
+
+    #[test]
+    fn replaced() {
+        let embedded = embed_header(&elf(true), large_builder()).unwrap();
+        let image = embedded.into_image();
+
+        // The smaller header fits in place of the larger one.
+        let embedded = embed_header(&image, builder()).unwrap();
+        assert_eq!(embedded.placement(), Placement::Replaced);
+        assert_eq!(embedded.offset(), 0x58);
+        let header = OwnedHeader::load(embedded.image(), 0x58).unwrap();
+        assert_eq!(header.header().length(), 32);
+        assert!(header.header().information_request_tag().is_none());
+    }
+
+    #[test]
+    fn existing_too_small() {
+        let embedded = embed_header(&elf(true), builder()).unwrap();
+        let image = embedded.into_image();
+
+        // The old header is invalidated, and its section is too small.
+        let embedded = embed_header(&image, large_builder()).unwrap();
+        assert_eq!(embedded.placement(), Placement::Gap);
+        assert_eq!(embedded.offset(), 0x78);
+        assert_eq!(valid_headers(embedded.image()), [0x78]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            embed_header(&[0; 0x100], builder()),
+            Err(EmbedError::InvalidElf)
+        );
+
+        let mut image = elf(false);
+        // PT_NULL instead of PT_LOAD
+        image[52..56].fill(0);
+        assert_eq!(
+            embed_header(&image, builder()),
+            Err(EmbedError::NoLoadSegment)
+        );
+
+        let mut image = elf(false);
+        // The segment is at an address too low to be extended.
+        image[60..68].copy_from_slice(&[0; 8]);
+        assert_eq!(embed_header(&image, builder()), Err(EmbedError::NoSpace));
+
+        let mut image = elf(false);
+        // A second segment directly below the first one in memory, which
+        // the extended segment would overlap.
+        image[44..46].copy_from_slice(&2_u16.to_le_bytes());
+        let fields: [(usize, u32); 6] = [
+            (84, PT_LOAD),
+            (84 + 4, 0x1100),
+            (84 + 8, 0x200800),
+            (84 + 12, 0x200800),
+            (84 + 20, 0x800),
+            (84 + 28, 0x1000),
+        ];
+        for (offset, value) in fields {
+            image[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        assert_eq!(embed_header(&image, builder()), Err(EmbedError::NoSpace));
+    }
+}
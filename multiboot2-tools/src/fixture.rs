@@ -0,0 +1,985 @@
+//! Module for [`MbiDescription`].
+
+use crate::{AlignedBuffer, HexdumpError, parse_hex};
+use multiboot2::{
+    ApmTag, BasicMemoryInfoTag, BootInformation, BootInformationHeader, BootLoaderNameTag,
+    BootdevTag, BuildError, Builder, CommandLineTag, DynSizedStructure,
+    EFIBootServicesNotExitedTag, EFIImageHandle32Tag, EFIImageHandle64Tag, EFIMemoryMapTag,
+    EFISdt32Tag, EFISdt64Tag, ElfSectionsTag, FramebufferColor, FramebufferField, FramebufferTag,
+    FramebufferType, ImageLoadPhysAddrTag, LoadError, MemoryArea, MemoryMapTag, ModuleTag,
+    NetworkTag, RsdpV1Tag, RsdpV2Tag, SmbiosTag, TagHeader, TagType,
+};
+use multiboot2_common::{NewBoxedError, try_new_boxed};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Size of the fixed fields of an [`ElfSectionsTag`] before the sections.
+const ELF_SECTIONS_FIELDS_SIZE: usize = 12;
+/// Size of the fixed fields of an [`EFIMemoryMapTag`] before the descriptors.
+const EFI_MMAP_FIELDS_SIZE: usize = 8;
+
+/// Errors of [`MbiDescription`].
+#[derive(Debug, Error)]
+pub enum FixtureError {
+    /// The TOML document is not a valid description.
+    #[error("invalid TOML description")]
+    Toml(#[from] toml::de::Error),
+    /// The description can't be represented as TOML.
+    #[error("failed to serialize description as TOML")]
+    TomlSerialize(#[from] toml::ser::Error),
+    /// The JSON document is not a valid description.
+    #[error("invalid JSON description")]
+    Json(#[from] serde_json::Error),
+    /// A file referenced by a [`Blob::File`] can't be read.
+    #[error("failed to read '{}'", path.display())]
+    Read {
+        /// The path of the file.
+        path: PathBuf,
+        /// The underlying error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// A [`Blob::Hex`] is not valid hexadecimal data.
+    #[error("invalid hexadecimal data")]
+    Hex(#[from] HexdumpError),
+    /// An OEM ID of an RSDP tag is not exactly six bytes long.
+    #[error("OEM ID {0:?} is not exactly six bytes long")]
+    InvalidOemId(String),
+    /// A module needs exactly one of `end` and `file` and must not be empty.
+    #[error("module at 0x{0:x} has no valid end")]
+    InvalidModuleEnd(u32),
+    /// The descriptor size of an EFI memory map tag is zero.
+    #[error("EFI memory map has a descriptor size of zero")]
+    InvalidEfiDescriptorSize,
+    /// A tag type that the boot information contains at most once is
+    /// described more than once.
+    #[error("tag {0:?} is described more than once")]
+    DuplicateTag(TagType),
+    /// The type of a [`TagDescription::Custom`] is not a custom type.
+    #[error("type {0} of custom tag is not a custom type")]
+    NotCustom(u32),
+    /// A custom tag can't be created.
+    #[error("failed to create custom tag")]
+    CustomTag(#[source] NewBoxedError),
+    /// The boot information can't be built.
+    #[error("failed to build boot information")]
+    Build(#[from] BuildError),
+    /// The bytes don't contain a complete boot information.
+    #[error("boot information is truncated")]
+    Truncated,
+    /// The boot information is invalid.
+    #[error("invalid boot information")]
+    Load(#[source] LoadError),
+    /// A tag contains an invalid string.
+    #[error("tag {0:?} contains an invalid string")]
+    InvalidString(TagType),
+    /// The tag can't be described.
+    #[error("tag {0:?} is not supported")]
+    Unsupported(TagType),
+}
+
+/// Binary data of a tag.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Blob {
+    /// Hexadecimal bytes, see [`parse_hex`].
+    Hex(String),
+    /// The contents of a file. Relative paths are relative to the base
+    /// directory given to [`MbiDescription::build`].
+    File(PathBuf),
+}
+
+impl Blob {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self::Hex(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+    }
+
+    fn load(&self, base_dir: &Path) -> Result<Vec<u8>, FixtureError> {
+        match self {
+            Self::Hex(hex) => Ok(parse_hex(hex)?),
+            Self::File(path) => {
+                let path = base_dir.join(path);
+                std::fs::read(&path).map_err(|source| FixtureError::Read { path, source })
+            }
+        }
+    }
+}
+
+/// Describes a [`MemoryArea`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryAreaDescription {
+    /// Physical start address.
+    pub start: u64,
+    /// Size in bytes.
+    pub size: u64,
+    /// Raw [`MemoryAreaTypeId`](multiboot2::MemoryAreaTypeId), e.g., `1` for
+    /// available memory.
+    #[serde(rename = "type")]
+    pub typ: u32,
+}
+
+/// Describes a [`FramebufferField`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FramebufferFieldDescription {
+    /// Color field position.
+    pub position: u8,
+    /// Color mask size.
+    pub size: u8,
+}
+
+/// Describes a [`FramebufferType`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum FramebufferTypeDescription {
+    /// Indexed color with a palette of RGB colors.
+    Indexed {
+        /// The red, green, and blue components of each color.
+        palette: Vec<[u8; 3]>,
+    },
+    /// Direct RGB color.
+    Rgb {
+        /// Red field.
+        red: FramebufferFieldDescription,
+        /// Green field.
+        green: FramebufferFieldDescription,
+        /// Blue field.
+        blue: FramebufferFieldDescription,
+    },
+    /// EGA text.
+    Text,
+}
+
+/// Describes a tag of a boot information.
+///
+/// The variants correspond to the setters of [`Builder`]. Addresses and
+/// sizes are plain numbers; binary payloads are [`Blob`]s.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TagDescription {
+    /// [`CommandLineTag`].
+    Cmdline {
+        /// The command line.
+        cmdline: String,
+    },
+    /// [`BootLoaderNameTag`].
+    BootLoaderName {
+        /// The name of the bootloader.
+        name: String,
+    },
+    /// [`ModuleTag`]. Exactly one of `end` and `file` must be present.
+    Module {
+        /// Physical start address.
+        start: u32,
+        /// Physical end address (exclusive).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        end: Option<u32>,
+        /// File whose size determines the end address of the module.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file: Option<PathBuf>,
+        /// The command line of the module.
+        #[serde(default)]
+        cmdline: String,
+    },
+    /// [`BasicMemoryInfoTag`].
+    BasicMemoryInfo {
+        /// Amount of lower memory in KiB.
+        memory_lower: u32,
+        /// Amount of upper memory in KiB.
+        memory_upper: u32,
+    },
+    /// [`BootdevTag`].
+    Bootdev {
+        /// BIOS disk device.
+        biosdev: u32,
+        /// Top-level partition number.
+        slice: u32,
+        /// Sub-partition number.
+        part: u32,
+    },
+    /// [`MemoryMapTag`].
+    MemoryMap {
+        /// The memory areas.
+        areas: Vec<MemoryAreaDescription>,
+    },
+    /// [`FramebufferTag`].
+    Framebuffer {
+        /// Physical address of the framebuffer.
+        address: u64,
+        /// Bytes per line.
+        pitch: u32,
+        /// Width in pixels or characters.
+        width: u32,
+        /// Height in pixels or characters.
+        height: u32,
+        /// Bits per pixel.
+        bpp: u8,
+        /// Type of the framebuffer.
+        buffer_type: FramebufferTypeDescription,
+    },
+    /// [`ElfSectionsTag`].
+    ElfSections {
+        /// Number of section headers.
+        number_of_sections: u32,
+        /// Size of a section header.
+        entry_size: u32,
+        /// Index of the section name string table.
+        shndx: u32,
+        /// The section headers.
+        sections: Blob,
+    },
+    /// [`ApmTag`].
+    Apm {
+        /// Version number.
+        version: u16,
+        /// 32-bit code segment.
+        cseg: u16,
+        /// Offset of the entry point.
+        offset: u32,
+        /// 16-bit code segment.
+        cseg_16: u16,
+        /// 16-bit data segment.
+        dseg: u16,
+        /// Flags.
+        flags: u16,
+        /// Length of the 32-bit code segment.
+        cseg_len: u16,
+        /// Length of the 16-bit code segment.
+        cseg_16_len: u16,
+        /// Length of the 16-bit data segment.
+        dseg_len: u16,
+    },
+    /// [`EFISdt32Tag`].
+    EfiSdt32 {
+        /// Physical address of the EFI system table.
+        address: u32,
+    },
+    /// [`EFISdt64Tag`].
+    EfiSdt64 {
+        /// Physical address of the EFI system table.
+        address: u64,
+    },
+    /// [`SmbiosTag`].
+    Smbios {
+        /// Major version.
+        major: u8,
+        /// Minor version.
+        minor: u8,
+        /// The SMBIOS tables.
+        tables: Blob,
+    },
+    /// [`RsdpV1Tag`]. The checksum is computed.
+    RsdpV1 {
+        /// OEM ID of six ASCII characters.
+        oem_id: String,
+        /// ACPI revision.
+        revision: u8,
+        /// Physical address of the RSDT.
+        rsdt_address: u32,
+    },
+    /// [`RsdpV2Tag`]. The checksums are computed.
+    RsdpV2 {
+        /// OEM ID of six ASCII characters.
+        oem_id: String,
+        /// ACPI revision.
+        revision: u8,
+        /// Physical address of the RSDT.
+        rsdt_address: u32,
+        /// Length of the RSDP.
+        length: u32,
+        /// Physical address of the XSDT.
+        xsdt_address: u64,
+    },
+    /// [`NetworkTag`].
+    Network {
+        /// The DHCP ACK packet.
+        dhcpack: Blob,
+    },
+    /// [`EFIMemoryMapTag`].
+    EfiMemoryMap {
+        /// Size of a descriptor.
+        desc_size: u32,
+        /// Version of the descriptors.
+        desc_version: u32,
+        /// The descriptors.
+        descriptors: Blob,
+    },
+    /// [`EFIBootServicesNotExitedTag`].
+    EfiBootServicesNotExited,
+    /// [`EFIImageHandle32Tag`].
+    EfiImageHandle32 {
+        /// The image handle.
+        handle: u32,
+    },
+    /// [`EFIImageHandle64Tag`].
+    EfiImageHandle64 {
+        /// The image handle.
+        handle: u64,
+    },
+    /// [`ImageLoadPhysAddrTag`].
+    LoadBaseAddr {
+        /// Physical load base address of the image.
+        load_base_addr: u32,
+    },
+    /// A custom tag with a type above the types of the spec.
+    Custom {
+        /// The raw tag type.
+        id: u32,
+        /// The payload after the tag header.
+        payload: Blob,
+    },
+}
+
+fn oem_id(oem_id: &str) -> Result<[u8; 6], FixtureError> {
+    oem_id
+        .as_bytes()
+        .try_into()
+        .map_err(|_| FixtureError::InvalidOemId(oem_id.to_string()))
+}
+
+fn string(result: Result<&str, impl Sized>, typ: TagType) -> Result<String, FixtureError> {
+    result
+        .map(str::to_string)
+        .map_err(|_| FixtureError::InvalidString(typ))
+}
+
+impl TagDescription {
+    /// Returns the tag type.
+    #[must_use]
+    pub const fn typ(&self) -> TagType {
+        match self {
+            Self::Cmdline { .. } => TagType::Cmdline,
+            Self::BootLoaderName { .. } => TagType::BootLoaderName,
+            Self::Module { .. } => TagType::Module,
+            Self::BasicMemoryInfo { .. } => TagType::BasicMeminfo,
+            Self::Bootdev { .. } => TagType::Bootdev,
+            Self::MemoryMap { .. } => TagType::Mmap,
+            Self::Framebuffer { .. } => TagType::Framebuffer,
+            Self::ElfSections { .. } => TagType::ElfSections,
+            Self::Apm { .. } => TagType::Apm,
+            Self::EfiSdt32 { .. } => TagType::Efi32,
+            Self::EfiSdt64 { .. } => TagType::Efi64,
+            Self::Smbios { .. } => TagType::Smbios,
+            Self::RsdpV1 { .. } => TagType::AcpiV1,
+            Self::RsdpV2 { .. } => TagType::AcpiV2,
+            Self::Network { .. } => TagType::Network,
+            Self::EfiMemoryMap { .. } => TagType::EfiMmap,
+            Self::EfiBootServicesNotExited => TagType::EfiBs,
+            Self::EfiImageHandle32 { .. } => TagType::Efi32Ih,
+            Self::EfiImageHandle64 { .. } => TagType::Efi64Ih,
+            Self::LoadBaseAddr { .. } => TagType::LoadBaseAddr,
+            Self::Custom { id, .. } => TagType::Custom(*id),
+        }
+    }
+
+    /// Adds the described tag to the builder.
+    fn add_to(&self, builder: Builder, base_dir: &Path) -> Result<Builder, FixtureError> {
+        let builder = match self {
+            Self::Cmdline { cmdline } => builder.cmdline(CommandLineTag::new(cmdline)),
+            Self::BootLoaderName { name } => builder.bootloader(BootLoaderNameTag::new(name)),
+            Self::Module {
+                start,
+                end,
+                file,
+                cmdline,
+            } => {
+                let end = match (end, file) {
+                    (Some(end), None) => Some(*end),
+                    (None, Some(file)) => {
+                        let len = Blob::File(file.clone()).load(base_dir)?.len();
+                        u32::try_from(len)
+                            .ok()
+                            .and_then(|len| start.checked_add(len))
+                    }
+                    _ => None,
+                };
+                let end = end
+                    .filter(|end| end > start)
+                    .ok_or(FixtureError::InvalidModuleEnd(*start))?;
+                builder.add_module(ModuleTag::new(*start, end, cmdline))
+            }
+            Self::BasicMemoryInfo {
+                memory_lower,
+                memory_upper,
+            } => builder.meminfo(BasicMemoryInfoTag::new(*memory_lower, *memory_upper)),
+            Self::Bootdev {
+                biosdev,
+                slice,
+                part,
+            } => builder.bootdev(BootdevTag::new(*biosdev, *slice, *part)),
+            Self::MemoryMap { areas } => {
+                let areas = areas
+                    .iter()
+                    .map(|area| MemoryArea::new(area.start, area.size, area.typ))
+                    .collect::<Vec<_>>();
+                builder.mmap(MemoryMapTag::new(&areas))
+            }
+            Self::Framebuffer {
+                address,
+                pitch,
+                width,
+                height,
+                bpp,
+                buffer_type,
+            } => {
+                let field = |field: &FramebufferFieldDescription| FramebufferField {
+                    position: field.position,
+                    size: field.size,
+                };
+                let palette;
+                let buffer_type = match buffer_type {
+                    FramebufferTypeDescription::Indexed { palette: colors } => {
+                        palette = colors
+                            .iter()
+                            .map(|&[red, green, blue]| FramebufferColor { red, green, blue })
+                            .collect::<Vec<_>>();
+                        FramebufferType::Indexed { palette: &palette }
+                    }
+                    FramebufferTypeDescription::Rgb { red, green, blue } => FramebufferType::RGB {
+                        red: field(red),
+                        green: field(green),
+                        blue: field(blue),
+                    },
+                    FramebufferTypeDescription::Text => FramebufferType::Text,
+                };
+                builder.framebuffer(FramebufferTag::new(
+                    *address,
+                    *pitch,
+                    *width,
+                    *height,
+                    *bpp,
+                    buffer_type,
+                ))
+            }
+            Self::ElfSections {
+                number_of_sections,
+                entry_size,
+                shndx,
+                sections,
+            } => builder.elf_sections(ElfSectionsTag::new(
+                *number_of_sections,
+                *entry_size,
+                *shndx,
+                &sections.load(base_dir)?,
+            )),
+            Self::Apm {
+                version,
+                cseg,
+                offset,
+                cseg_16,
+                dseg,
+                flags,
+                cseg_len,
+                cseg_16_len,
+                dseg_len,
+            } => builder.apm(ApmTag::new(
+                *version,
+                *cseg,
+                *offset,
+                *cseg_16,
+                *dseg,
+                *flags,
+                *cseg_len,
+                *cseg_16_len,
+                *dseg_len,
+            )),
+            Self::EfiSdt32 { address } => builder.efi32(EFISdt32Tag::new(*address)),
+            Self::EfiSdt64 { address } => builder.efi64(EFISdt64Tag::new(*address)),
+            Self::Smbios {
+                major,
+                minor,
+                tables,
+            } => builder.add_smbios(SmbiosTag::new(*major, *minor, &tables.load(base_dir)?)),
+            Self::RsdpV1 {
+                oem_id: id,
+                revision,
+                rsdt_address,
+            } => builder.rsdpv1(RsdpV1Tag::new(oem_id(id)?, *revision, *rsdt_address)),
+            Self::RsdpV2 {
+                oem_id: id,
+                revision,
+                rsdt_address,
+                length,
+                xsdt_address,
+            } => builder.rsdpv2(RsdpV2Tag::new(
+                oem_id(id)?,
+                *revision,
+                *rsdt_address,
+                *length,
+                *xsdt_address,
+            )),
+            Self::Network { dhcpack } => {
+                builder.add_network(NetworkTag::new(&dhcpack.load(base_dir)?))
+            }
+            Self::EfiMemoryMap {
+                desc_size,
+                desc_version,
+                descriptors,
+            } => {
+                if *desc_size == 0 {
+                    return Err(FixtureError::InvalidEfiDescriptorSize);
+                }
+                builder.efi_mmap(EFIMemoryMapTag::new_from_map(
+                    *desc_size,
+                    *desc_version,
+                    &descriptors.load(base_dir)?,
+                ))
+            }
+            Self::EfiBootServicesNotExited => builder.efi_bs(EFIBootServicesNotExitedTag::new()),
+            Self::EfiImageHandle32 { handle } => {
+                builder.efi32_ih(EFIImageHandle32Tag::new(*handle))
+            }
+            Self::EfiImageHandle64 { handle } => {
+                builder.efi64_ih(EFIImageHandle64Tag::new(*handle))
+            }
+            Self::LoadBaseAddr { load_base_addr } => {
+                builder.image_load_addr(ImageLoadPhysAddrTag::new(*load_base_addr))
+            }
+            Self::Custom { id, payload } => {
+                if !matches!(TagType::from(*id), TagType::Custom(_)) {
+                    return Err(FixtureError::NotCustom(*id));
+                }
+                let tag = try_new_boxed::<DynSizedStructure<TagHeader>>(
+                    TagHeader::new(*id, 0),
+                    &[&payload.load(base_dir)?],
+                )
+                .map_err(FixtureError::CustomTag)?;
+                builder.add_custom_tag(tag)
+            }
+        };
+        Ok(builder)
+    }
+
+    /// Describes a tag of a boot information.
+    fn from_tag(tag: &DynSizedStructure<TagHeader>) -> Result<Self, FixtureError> {
+        let typ = TagType::from(tag.header().typ);
+        let description = match typ {
+            TagType::Cmdline => Self::Cmdline {
+                cmdline: string(tag.cast::<CommandLineTag>().cmdline(), typ)?,
+            },
+            TagType::BootLoaderName => Self::BootLoaderName {
+                name: string(tag.cast::<BootLoaderNameTag>().name(), typ)?,
+            },
+            TagType::Module => {
+                let tag = tag.cast::<ModuleTag>();
+                Self::Module {
+                    start: tag.start_address(),
+                    end: Some(tag.end_address()),
+                    file: None,
+                    cmdline: string(tag.cmdline(), typ)?,
+                }
+            }
+            TagType::BasicMeminfo => {
+                let tag = tag.cast::<BasicMemoryInfoTag>();
+                Self::BasicMemoryInfo {
+                    memory_lower: tag.memory_lower(),
+                    memory_upper: tag.memory_upper(),
+                }
+            }
+            TagType::Bootdev => {
+                let tag = tag.cast::<BootdevTag>();
+                Self::Bootdev {
+                    biosdev: tag.biosdev(),
+                    slice: tag.slice(),
+                    part: tag.part(),
+                }
+            }
+            TagType::Mmap => {
+                let tag = tag.cast::<MemoryMapTag>();
+                if tag.entry_size() as usize != size_of::<MemoryArea>() {
+                    return Err(FixtureError::Unsupported(typ));
+                }
+                Self::MemoryMap {
+                    areas: tag
+                        .memory_areas()
+                        .iter()
+                        .map(|area| MemoryAreaDescription {
+                            start: area.start_address(),
+                            size: area.size(),
+                            typ: area.typ().into(),
+                        })
+                        .collect(),
+                }
+            }
+            TagType::Framebuffer => {
+                let tag = tag.cast::<FramebufferTag>();
+                let field = |field: FramebufferField| FramebufferFieldDescription {
+                    position: field.position,
+                    size: field.size,
+                };
+                let buffer_type = match tag
+                    .buffer_type()
+                    .map_err(|_| FixtureError::Unsupported(typ))?
+                {
+                    FramebufferType::Indexed { palette } => FramebufferTypeDescription::Indexed {
+                        palette: palette
+                            .iter()
+                            .map(|color| [color.red, color.green, color.blue])
+                            .collect(),
+                    },
+                    FramebufferType::RGB { red, green, blue } => FramebufferTypeDescription::Rgb {
+                        red: field(red),
+                        green: field(green),
+                        blue: field(blue),
+                    },
+                    FramebufferType::Text => FramebufferTypeDescription::Text,
+                };
+                Self::Framebuffer {
+                    address: tag.address(),
+                    pitch: tag.pitch(),
+                    width: tag.width(),
+                    height: tag.height(),
+                    bpp: tag.bpp(),
+                    buffer_type,
+                }
+            }
+            TagType::ElfSections => {
+                let sections = tag
+                    .payload()
+                    .get(ELF_SECTIONS_FIELDS_SIZE..)
+                    .unwrap_or_default();
+                let tag = tag.cast::<ElfSectionsTag>();
+                Self::ElfSections {
+                    number_of_sections: tag.number_of_sections(),
+                    entry_size: tag.entry_size(),
+                    shndx: tag.shndx(),
+                    sections: Blob::from_bytes(sections),
+                }
+            }
+            TagType::Apm => {
+                let tag = tag.cast::<ApmTag>();
+                Self::Apm {
+                    version: tag.version(),
+                    cseg: tag.cseg(),
+                    offset: tag.offset(),
+                    cseg_16: tag.cset_16(),
+                    dseg: tag.dseg(),
+                    flags: tag.flags(),
+                    cseg_len: tag.cseg_len(),
+                    cseg_16_len: tag.cseg_16_len(),
+                    dseg_len: tag.dseg_len(),
+                }
+            }
+            TagType::Efi32 => Self::EfiSdt32 {
+                address: tag.cast::<EFISdt32Tag>().sdt_address() as u32,
+            },
+            TagType::Efi64 => Self::EfiSdt64 {
+                address: tag.cast::<EFISdt64Tag>().sdt_address() as u64,
+            },
+            TagType::Smbios => {
+                let tag = tag.cast::<SmbiosTag>();
+                Self::Smbios {
+                    major: tag.major(),
+                    minor: tag.minor(),
+                    tables: Blob::from_bytes(tag.tables()),
+                }
+            }
+            TagType::AcpiV1 => {
+                let tag = tag.cast::<RsdpV1Tag>();
+                Self::RsdpV1 {
+                    oem_id: string(tag.oem_id(), typ)?,
+                    revision: tag.revision(),
+                    rsdt_address: tag.rsdt_address() as u32,
+                }
+            }
+            TagType::AcpiV2 => {
+                let tag = tag.cast::<RsdpV2Tag>();
+                Self::RsdpV2 {
+                    oem_id: string(tag.oem_id(), typ)?,
+                    revision: tag.revision(),
+                    rsdt_address: tag.rsdt_address() as u32,
+                    length: tag.length(),
+                    xsdt_address: tag.xsdt_address() as u64,
+                }
+            }
+            TagType::Network => Self::Network {
+                dhcpack: Blob::from_bytes(tag.payload()),
+            },
+            TagType::EfiMmap => {
+                let payload = tag.payload();
+                let field = |offset: usize| {
+                    u32::from_ne_bytes(payload[offset..offset + 4].try_into().unwrap())
+                };
+                Self::EfiMemoryMap {
+                    desc_size: field(0),
+                    desc_version: field(4),
+                    descriptors: Blob::from_bytes(&payload[EFI_MMAP_FIELDS_SIZE..]),
+                }
+            }
+            TagType::EfiBs => Self::EfiBootServicesNotExited,
+            TagType::Efi32Ih => Self::EfiImageHandle32 {
+                handle: tag.cast::<EFIImageHandle32Tag>().image_handle() as u32,
+            },
+            TagType::Efi64Ih => Self::EfiImageHandle64 {
+                handle: tag.cast::<EFIImageHandle64Tag>().image_handle() as u64,
+            },
+            TagType::LoadBaseAddr => Self::LoadBaseAddr {
+                load_base_addr: tag.cast::<ImageLoadPhysAddrTag>().load_base_addr(),
+            },
+            TagType::Custom(id) => Self::Custom {
+                id,
+                payload: Blob::from_bytes(tag.payload()),
+            },
+            TagType::Vbe | TagType::End => return Err(FixtureError::Unsupported(typ)),
+        };
+        Ok(description)
+    }
+}
+
+/// Declarative description of a Multiboot2 boot information (MBI), e.g., to
+/// generate binary fixtures for kernel tests.
+///
+/// Descriptions are TOML or JSON documents with a list of tags, each with a
+/// `type` and the fields of the corresponding [`TagDescription`] variant:
+///
+/// ```toml
+/// [[tags]]
+/// type = "cmdline"
+/// cmdline = "console=ttyS0"
+///
+/// [[tags]]
+/// type = "module"
+/// start = 0x200000
+/// file = "initrd.img"
+///
+/// [[tags]]
+/// type = "memory_map"
+/// areas = [{ start = 0x100000, size = 0x7f00000, type = 1 }]
+///
+/// [[tags]]
+/// type = "smbios"
+/// major = 3
+/// minor = 0
+/// tables = { hex = "5f534d33..." }
+/// ```
+///
+/// [`Self::build`] creates the MBI with [`Builder`]. The reverse,
+/// [`Self::from_boot_information`], describes an existing MBI. It doesn't
+/// support VBE tags and recomputes RSDP checksums when built again.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MbiDescription {
+    /// The tags, in any order. The builder determines the order in the MBI.
+    #[serde(default)]
+    pub tags: Vec<TagDescription>,
+}
+
+impl MbiDescription {
+    /// Parses a TOML description.
+    pub fn from_toml(toml: &str) -> Result<Self, FixtureError> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Parses a JSON description.
+    pub fn from_json(json: &str) -> Result<Self, FixtureError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Serializes the description as TOML.
+    pub fn to_toml(&self) -> Result<String, FixtureError> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Serializes the description as JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("should serialize JSON")
+    }
+
+    /// Builds the boot information. Files of [`Blob::File`]s and modules are
+    /// resolved relative to `base_dir`.
+    pub fn build(
+        &self,
+        base_dir: &Path,
+    ) -> Result<Box<DynSizedStructure<BootInformationHeader>>, FixtureError> {
+        let mut builder = Builder::new();
+        for tag in &self.tags {
+            let typ = tag.typ();
+            let multiple = matches!(
+                typ,
+                TagType::Module | TagType::Smbios | TagType::Network | TagType::Custom(_)
+            );
+            if !multiple && builder.has_tag(typ) {
+                return Err(FixtureError::DuplicateTag(typ));
+            }
+            builder = tag.add_to(builder, base_dir)?;
+        }
+        Ok(builder.try_build()?)
+    }
+
+    /// Describes an existing boot information.
+    pub fn from_boot_information(mbi: &BootInformation) -> Result<Self, FixtureError> {
+        let tags = mbi
+            .tags()
+            .filter(|tag| TagType::from(tag.header().typ) != TagType::End)
+            .map(TagDescription::from_tag)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { tags })
+    }
+
+    /// Describes the boot information at the beginning of `bytes`, e.g., of
+    /// a binary fixture or a memory dump.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FixtureError> {
+        let total_size = bytes
+            .get(..4)
+            .map(|size| u32::from_ne_bytes(size.try_into().unwrap()) as usize)
+            .ok_or(FixtureError::Truncated)?;
+        if total_size < size_of::<BootInformationHeader>() || total_size > bytes.len() {
+            return Err(FixtureError::Truncated);
+        }
+        let buffer = AlignedBuffer::new(&bytes[..total_size]);
+        // SAFETY: The buffer is aligned and contains the complete MBI.
+        let mbi = unsafe { BootInformation::load(buffer.as_bytes().as_ptr().cast()) }
+            .map_err(FixtureError::Load)?;
+        Self::from_boot_information(&mbi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiboot2::MaybeDynSized;
+
+    const TOML: &str = r#"
+[[tags]]
+type = "cmdline"
+cmdline = "console=ttyS0"
+
+[[tags]]
+type = "module"
+start = 0x200000
+end = 0x201000
+cmdline = "initrd"
+
+[[tags]]
+type = "memory_map"
+areas = [
+    { start = 0, size = 0x9fc00, type = 1 },
+    { start = 0x100000, size = 0x7f00000, type = 1 },
+]
+
+[[tags]]
+type = "framebuffer"
+address = 0xfd000000
+pitch = 4096
+width = 1024
+height = 768
+bpp = 32
+buffer_type = { type = "rgb", red = { position = 16, size = 8 }, green = { position = 8, size = 8 }, blue = { position = 0, size = 8 } }
+
+[[tags]]
+type = "rsdp_v1"
+oem_id = "BOCHS "
+revision = 0
+rsdt_address = 0x7fe14d2
+
+[[tags]]
+type = "smbios"
+major = 3
+minor = 0
+tables = { hex = "5f534d33 5f" }
+
+[[tags]]
+type = "custom"
+id = 0x1337
+payload = { hex = "01 02 03" }
+"#;
+
+    #[test]
+    fn build() {
+        let description = MbiDescription::from_toml(TOML).unwrap();
+        let mbi = description.build(Path::new(".")).unwrap();
+        let buffer = AlignedBuffer::new(&mbi.as_bytes());
+        // SAFETY: The builder creates a valid MBI.
+        let mbi = unsafe { BootInformation::load(buffer.as_bytes().as_ptr().cast()) }.unwrap();
+
+        assert_eq!(
+            mbi.command_line_tag().unwrap().cmdline(),
+            Ok("console=ttyS0")
+        );
+        let module = mbi.module_tags().next().unwrap();
+        assert_eq!(module.start_address(), 0x200000);
+        assert_eq!(module.end_address(), 0x201000);
+        assert_eq!(mbi.memory_map_tag().unwrap().memory_areas().len(), 2);
+        let framebuffer = mbi.framebuffer_tag().unwrap().unwrap();
+        assert_eq!(framebuffer.width(), 1024);
+        assert!(mbi.rsdp_v1_tag().unwrap().checksum_is_valid());
+        assert_eq!(mbi.smbios_tags().next().unwrap().tables(), b"_SM3_");
+        let custom = mbi
+            .tags()
+            .find(|tag| TagType::from(tag.header().typ) == TagType::Custom(0x1337))
+            .unwrap();
+        assert_eq!(custom.payload(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let description = MbiDescription::from_toml(TOML).unwrap();
+        let mbi = description.build(Path::new(".")).unwrap();
+        let decompiled = MbiDescription::from_bytes(&mbi.as_bytes()).unwrap();
+        let rebuilt = decompiled.build(Path::new(".")).unwrap();
+        assert_eq!(*mbi.as_bytes(), *rebuilt.as_bytes());
+
+        // The serialized descriptions parse again.
+        let toml = decompiled.to_toml().unwrap();
+        assert_eq!(MbiDescription::from_toml(&toml).unwrap(), decompiled);
+        let json = decompiled.to_json();
+        assert_eq!(MbiDescription::from_json(&json).unwrap(), decompiled);
+    }
+
+    #[test]
+    fn module_file() {
+        let dir = std::env::temp_dir().join(format!("multiboot2-fixture-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("module.bin"), [0; 0x123]).unwrap();
+        let description = MbiDescription::from_json(
+            r#"{ "tags": [{ "type": "module", "start": 4096, "file": "module.bin" }] }"#,
+        )
+        .unwrap();
+        let mbi = description.build(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        let decompiled = MbiDescription::from_bytes(&mbi.as_bytes()).unwrap();
+        assert_eq!(
+            decompiled.tags,
+            [TagDescription::Module {
+                start: 0x1000,
+                end: Some(0x1123),
+                file: None,
+                cmdline: String::new()
+            }]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let build = |toml: &str| MbiDescription::from_toml(toml)?.build(Path::new("."));
+        assert!(matches!(
+            build(
+                "[[tags]]\ntype = \"cmdline\"\ncmdline = \"a\"\n[[tags]]\ntype = \"cmdline\"\ncmdline = \"b\""
+            ),
+            Err(FixtureError::DuplicateTag(TagType::Cmdline))
+        ));
+        assert!(matches!(
+            build("[[tags]]\ntype = \"module\"\nstart = 0x1000"),
+            Err(FixtureError::InvalidModuleEnd(0x1000))
+        ));
+        assert!(matches!(
+            build("[[tags]]\ntype = \"rsdp_v1\"\noem_id = \"X\"\nrevision = 0\nrsdt_address = 0"),
+            Err(FixtureError::InvalidOemId(_))
+        ));
+        assert!(matches!(
+            build("[[tags]]\ntype = \"custom\"\nid = 1\npayload = { hex = \"\" }"),
+            Err(FixtureError::NotCustom(1))
+        ));
+        assert!(matches!(
+            build("[[tags]]\ntype = \"cmdline\"\ncmdline = \"a\"\nfoo = 1"),
+            Err(FixtureError::Toml(_))
+        ));
+        assert!(matches!(
+            MbiDescription::from_bytes(&[16, 0, 0, 0]),
+            Err(FixtureError::Truncated)
+        ));
+    }
+}
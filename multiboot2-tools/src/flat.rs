@@ -0,0 +1,419 @@
+//! Module for [`flat_binary`] and [`elf_address_tags`].
+
+use crate::{EmbedError, embed_header};
+use elf::ElfBytes;
+use elf::abi::PT_LOAD;
+use elf::endian::AnyEndian;
+use elf::segment::ProgramHeader;
+use multiboot2_common::ALIGNMENT;
+use multiboot2_header::{
+    AddressHeaderTag, Builder, EntryAddressHeaderTag, HEADER_SEARCH_LIMIT, HeaderTagFlag,
+    HeaderTagHeader, HeaderTagType, LoadError, Multiboot2BasicHeader, OwnedHeader,
+};
+use thiserror::Error;
+
+/// Errors that may occur in [`flat_binary`] and [`elf_address_tags`].
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum FlatBinaryError {
+    /// The header could not be embedded into the ELF file.
+    #[error("failed to embed the header")]
+    Embed(#[from] EmbedError),
+    /// The image is not a valid ELF file.
+    #[error("image is not a valid ELF file")]
+    InvalidElf,
+    /// No valid header was found in the ELF file.
+    #[error("no valid multiboot2 header found")]
+    Header(#[source] LoadError),
+    /// The ELF file has no `PT_LOAD` segment.
+    #[error("ELF file has no PT_LOAD segment")]
+    NoLoadSegment,
+    /// `PT_LOAD` segments overlap in physical memory.
+    #[error("PT_LOAD segments overlap in physical memory")]
+    OverlappingSegments,
+    /// The header is not within the file bytes of a `PT_LOAD` segment, so it
+    /// is not part of the flat binary.
+    #[error("header is not within a PT_LOAD segment")]
+    HeaderNotLoaded,
+    /// The header is not 64-bit aligned or not within the first
+    /// [`HEADER_SEARCH_LIMIT`] bytes of the flat binary.
+    #[error(
+        "header is not aligned or not within the first 0x{HEADER_SEARCH_LIMIT:x} bytes of the flat binary"
+    )]
+    HeaderOutsideSearchLimit,
+    /// The entry point is not within a `PT_LOAD` segment.
+    #[error("entry point 0x{0:x} is not within a PT_LOAD segment")]
+    EntryNotLoaded(u64),
+    /// A physical address doesn't fit into the 32-bit fields of the tags.
+    #[error("physical address 0x{0:x} exceeds 32 bits")]
+    AddressTooLarge(u64),
+}
+
+/// A flat binary (like from `objcopy -O binary`) with a Multiboot2 header
+/// that describes its layout with the a.out kludge, see [`flat_binary`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FlatBinary {
+    image: Vec<u8>,
+    address_tag: AddressHeaderTag,
+    entry_tag: EntryAddressHeaderTag,
+}
+
+impl FlatBinary {
+    /// Returns the flat binary.
+    #[must_use]
+    pub fn image(&self) -> &[u8] {
+        &self.image
+    }
+
+    /// Returns the flat binary.
+    #[must_use]
+    pub fn into_image(self) -> Vec<u8> {
+        self.image
+    }
+
+    /// Returns the [`AddressHeaderTag`] of the header in the flat binary.
+    #[must_use]
+    pub const fn address_tag(&self) -> &AddressHeaderTag {
+        &self.address_tag
+    }
+
+    /// Returns the [`EntryAddressHeaderTag`] of the header in the flat binary.
+    #[must_use]
+    pub const fn entry_tag(&self) -> &EntryAddressHeaderTag {
+        &self.entry_tag
+    }
+}
+
+/// The physical layout of the `PT_LOAD` segments of an ELF file with a
+/// header.
+#[derive(Debug)]
+struct FlatLayout {
+    /// `PT_LOAD` segments sorted by physical address.
+    segments: Vec<ProgramHeader>,
+    load_addr: u64,
+    load_end_addr: u64,
+    bss_end_addr: u64,
+    header_addr: u64,
+    entry_addr: u64,
+}
+
+impl FlatLayout {
+    fn new(elf: &[u8]) -> Result<Self, FlatBinaryError> {
+        let file =
+            ElfBytes::<AnyEndian>::minimal_parse(elf).map_err(|_| FlatBinaryError::InvalidElf)?;
+        // The `elf` crate doesn't check that the ranges of the segments are
+        // within the file and the address space.
+        let valid = |phdr: &ProgramHeader| {
+            let file_end = phdr.p_offset.checked_add(phdr.p_filesz);
+            file_end.is_some_and(|end| end <= elf.len() as u64)
+                && phdr.p_paddr.checked_add(phdr.p_memsz).is_some()
+                && phdr.p_vaddr.checked_add(phdr.p_memsz).is_some()
+                && phdr.p_filesz <= phdr.p_memsz
+        };
+        let mut segments = file
+            .segments()
+            .map(|phdrs| {
+                phdrs
+                    .iter()
+                    .filter(|phdr| phdr.p_type == PT_LOAD && phdr.p_memsz > 0)
+                    .map(|phdr| valid(&phdr).then_some(phdr))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .unwrap_or(Some(Vec::new()))
+            .ok_or(FlatBinaryError::InvalidElf)?;
+        segments.sort_by_key(|phdr| phdr.p_paddr);
+        if segments
+            .windows(2)
+            .any(|pair| pair[0].p_paddr + pair[0].p_memsz > pair[1].p_paddr)
+        {
+            return Err(FlatBinaryError::OverlappingSegments);
+        }
+        let load_addr = segments
+            .first()
+            .ok_or(FlatBinaryError::NoLoadSegment)?
+            .p_paddr;
+        let load_end_addr = segments
+            .iter()
+            .map(|phdr| phdr.p_paddr + phdr.p_filesz)
+            .max()
+            .unwrap_or(load_addr);
+        let bss_end_addr = segments
+            .iter()
+            .map(|phdr| phdr.p_paddr + phdr.p_memsz)
+            .max()
+            .unwrap_or(load_addr);
+
+        let (header, header_offset) =
+            OwnedHeader::find_header(elf).map_err(FlatBinaryError::Header)?;
+        let header_start = header_offset as u64;
+        let header_end = header_start + u64::from(header.header().length());
+        let header_addr = segments
+            .iter()
+            .find(|phdr| {
+                phdr.p_offset <= header_start && header_end <= phdr.p_offset + phdr.p_filesz
+            })
+            .map(|phdr| phdr.p_paddr + (header_start - phdr.p_offset))
+            .ok_or(FlatBinaryError::HeaderNotLoaded)?;
+        let header_in_flat = header_addr - load_addr;
+        if header_in_flat % ALIGNMENT as u64 != 0
+            || header_end - header_start + header_in_flat > HEADER_SEARCH_LIMIT as u64
+        {
+            return Err(FlatBinaryError::HeaderOutsideSearchLimit);
+        }
+
+        let entry = file.ehdr.e_entry;
+        let entry_addr = segments
+            .iter()
+            .find(|phdr| phdr.p_vaddr <= entry && entry < phdr.p_vaddr + phdr.p_memsz)
+            .map(|phdr| phdr.p_paddr + (entry - phdr.p_vaddr))
+            .ok_or(FlatBinaryError::EntryNotLoaded(entry))?;
+
+        let layout = Self {
+            segments,
+            load_addr,
+            load_end_addr,
+            bss_end_addr,
+            header_addr,
+            entry_addr,
+        };
+        for addr in [layout.bss_end_addr, layout.entry_addr] {
+            u32::try_from(addr).map_err(|_| FlatBinaryError::AddressTooLarge(addr))?;
+        }
+        Ok(layout)
+    }
+
+    const fn address_tag(&self) -> AddressHeaderTag {
+        // All addresses are below `bss_end_addr` or the entry point, which
+        // were checked on construction.
+        AddressHeaderTag::new(
+            HeaderTagFlag::Required,
+            self.header_addr as u32,
+            self.load_addr as u32,
+            self.load_end_addr as u32,
+            self.bss_end_addr as u32,
+        )
+    }
+
+    const fn entry_tag(&self) -> EntryAddressHeaderTag {
+        EntryAddressHeaderTag::new(HeaderTagFlag::Required, self.entry_addr as u32)
+    }
+
+    /// Returns the file bytes of all segments at their physical addresses
+    /// relative to `load_addr`. Gaps are filled with zeros.
+    fn flatten(&self, elf: &[u8]) -> Vec<u8> {
+        let mut image = vec![0; (self.load_end_addr - self.load_addr) as usize];
+        for phdr in &self.segments {
+            let src = phdr.p_offset as usize..(phdr.p_offset + phdr.p_filesz) as usize;
+            let dst = (phdr.p_paddr - self.load_addr) as usize;
+            image[dst..dst + src.len()].copy_from_slice(&elf[src]);
+        }
+        image
+    }
+}
+
+/// Computes the [`AddressHeaderTag`] and the [`EntryAddressHeaderTag`] for
+/// booting the flat binary of an ELF file (like from `objcopy -O binary`)
+/// with the a.out kludge.
+///
+/// The values are derived from the physical addresses of the `PT_LOAD`
+/// segments, the location of the existing header, and the ELF entry point:
+/// - `header_addr` is the physical address of the header,
+/// - `load_addr` is the lowest physical address of all segments,
+/// - `load_end_addr` is the end of the file bytes of the last segment,
+/// - `bss_end_addr` is the end of the memory of the last segment,
+/// - `entry_addr` is the physical address of the entry point.
+///
+/// The header must already be part of a `PT_LOAD` segment. Use
+/// [`flat_binary`] to embed a header with these tags and to create the flat
+/// binary in one step.
+pub fn elf_address_tags(
+    elf: &[u8],
+) -> Result<(AddressHeaderTag, EntryAddressHeaderTag), FlatBinaryError> {
+    let layout = FlatLayout::new(elf)?;
+    Ok((layout.address_tag(), layout.entry_tag()))
+}
+
+/// Creates a flat binary of an ELF file (like from `objcopy -O binary`) with
+/// a Multiboot2 header created by `builder`.
+///
+/// The header is embedded into the ELF file with [`embed_header`]. The
+/// [`AddressHeaderTag`] and [`EntryAddressHeaderTag`] of `builder` are
+/// replaced by the values of [`elf_address_tags`]. The flat binary contains
+/// the file bytes of all `PT_LOAD` segments at their physical addresses,
+/// starting at `load_addr`.
+pub fn flat_binary(elf: &[u8], builder: Builder) -> Result<FlatBinary, FlatBinaryError> {
+    // The tags have a fixed size, so the header can be placed before the
+    // addresses are known.
+    let builder = builder
+        .address_tag(AddressHeaderTag::new(HeaderTagFlag::Required, 0, 0, 0, 0))
+        .entry_tag(EntryAddressHeaderTag::new(HeaderTagFlag::Required, 0));
+    let elf = embed_header(elf, builder)?.into_image();
+    let layout = FlatLayout::new(&elf)?;
+    let address_tag = layout.address_tag();
+    let entry_tag = layout.entry_tag();
+
+    let mut image = layout.flatten(&elf);
+    let header = (layout.header_addr - layout.load_addr) as usize;
+    // The builder writes the header in native byte order.
+    let read_u32 =
+        |image: &[u8], at: usize| u32::from_ne_bytes(image[at..at + 4].try_into().unwrap());
+    let mut tag = header + size_of::<Multiboot2BasicHeader>();
+    loop {
+        let typ = image[tag..tag + 2]
+            .try_into()
+            .map(u16::from_ne_bytes)
+            .unwrap();
+        let size = read_u32(&image, tag + 4) as usize;
+        let fields = tag + size_of::<HeaderTagHeader>();
+        if typ == HeaderTagType::End as u16 {
+            break;
+        } else if typ == HeaderTagType::Address as u16 {
+            let values = [
+                address_tag.header_addr(),
+                address_tag.load_addr(),
+                address_tag.load_end_addr(),
+                address_tag.bss_end_addr(),
+            ];
+            for (index, value) in values.into_iter().enumerate() {
+                let at = fields + index * 4;
+                image[at..at + 4].copy_from_slice(&value.to_ne_bytes());
+            }
+        } else if typ == HeaderTagType::EntryAddress as u16 {
+            image[fields..fields + 4].copy_from_slice(&entry_tag.entry_addr().to_ne_bytes());
+        }
+        tag += size.next_multiple_of(ALIGNMENT);
+    }
+
+    Ok(FlatBinary {
+        image,
+        address_tag,
+        entry_tag,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiboot2_header::{HeaderTagISA, ModuleAlignHeaderTag};
+
+    /// Writes a minimal ELF32 file for a higher-half kernel with a text
+    /// segment at the physical address `0x100000` and a data segment with BSS
+    /// at `0x102000`. The entry point is `0xc0100010`.
+    fn elf() -> Vec<u8> {
+        let mut image = vec![0; 0x2010];
+        image[..4].copy_from_slice(b"\x7fELF");
+        image[4..7].copy_from_slice(&[1, 1, 1]);
+        let fields: [(usize, &[u8]); 24] = [
+            (16, &2_u16.to_le_bytes()),
+            (18, &3_u16.to_le_bytes()),
+            (20, &1_u32.to_le_bytes()),
+            (24, &0xc0100010_u32.to_le_bytes()),
+            (28, &52_u32.to_le_bytes()),
+            (40, &52_u16.to_le_bytes()),
+            (42, &32_u16.to_le_bytes()),
+            (44, &2_u16.to_le_bytes()),
+            // Program header 0: text
+            (52, &PT_LOAD.to_le_bytes()),
+            (52 + 4, &0x1000_u32.to_le_bytes()),
+            (52 + 8, &0xc0100000_u32.to_le_bytes()),
+            (52 + 12, &0x100000_u32.to_le_bytes()),
+            (52 + 16, &0x100_u32.to_le_bytes()),
+            (52 + 20, &0x100_u32.to_le_bytes()),
+            (52 + 28, &0x1000_u32.to_le_bytes()),
+            // Program header 1: data and BSS
+            (84, &PT_LOAD.to_le_bytes()),
+            (84 + 4, &0x2000_u32.to_le_bytes()),
+            (84 + 8, &0xc0102000_u32.to_le_bytes()),
+            (84 + 12, &0x102000_u32.to_le_bytes()),
+            (84 + 16, &0x10_u32.to_le_bytes()),
+            (84 + 20, &0x1000_u32.to_le_bytes()),
+            (84 + 28, &0x1000_u32.to_le_bytes()),
+            (0x1000, &[0xcc; 0x100]),
+            (0x2000, &[0xdd; 0x10]),
+        ];
+        for (offset, bytes) in fields {
+            image[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+        image
+    }
+
+    fn builder() -> Builder {
+        Builder::new(HeaderTagISA::I386)
+            .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required))
+    }
+
+    #[test]
+    fn flat() {
+        let flat = flat_binary(&elf(), builder()).unwrap();
+        let tag = flat.address_tag();
+        // The header is placed directly after the text.
+        assert_eq!(tag.header_addr(), 0x100100);
+        assert_eq!(tag.load_addr(), 0x100000);
+        assert_eq!(tag.load_end_addr(), 0x102010);
+        assert_eq!(tag.bss_end_addr(), 0x103000);
+        assert_eq!(flat.entry_tag().entry_addr(), 0x100010);
+
+        let image = flat.image();
+        assert_eq!(image.len(), 0x2010);
+        assert_eq!(image[..0x100], [0xcc; 0x100]);
+        assert_eq!(image[0x2000..], [0xdd; 0x10]);
+
+        let owned = OwnedHeader::load(image, 0x100).unwrap();
+        let header = owned.header();
+        assert_eq!(header.address_tag(), Some(tag));
+        assert_eq!(header.entry_address_tag(), Some(flat.entry_tag()));
+        assert!(header.module_align_tag().is_some());
+        let supported = [
+            HeaderTagType::Address.into(),
+            HeaderTagType::EntryAddress.into(),
+            HeaderTagType::ModuleAlign.into(),
+        ];
+        let report = header.check(0x100, image, &supported);
+        assert!(
+            report.is_ok(),
+            "{:?}",
+            report.violations().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn address_tags() {
+        let elf = embed_header(&elf(), builder()).unwrap().into_image();
+        let (address_tag, entry_tag) = elf_address_tags(&elf).unwrap();
+        assert_eq!(address_tag.header_addr(), 0x100100);
+        assert_eq!(address_tag.load_addr(), 0x100000);
+        assert_eq!(entry_tag.entry_addr(), 0x100010);
+
+        assert!(matches!(
+            elf_address_tags(&self::elf()),
+            Err(FlatBinaryError::Header(_))
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        let mut image = elf();
+        // Entry point outside of the segments
+        image[24..28].copy_from_slice(&0xc0200000_u32.to_le_bytes());
+        assert_eq!(
+            flat_binary(&image, builder()),
+            Err(FlatBinaryError::EntryNotLoaded(0xc0200000))
+        );
+
+        let mut image = elf();
+        // Data segment overlaps the text segment.
+        image[84 + 12..84 + 16].copy_from_slice(&0x100080_u32.to_le_bytes());
+        assert_eq!(
+            flat_binary(&image, builder()),
+            Err(FlatBinaryError::OverlappingSegments)
+        );
+
+        let mut image = elf();
+        // File bytes of the data segment exceed the file.
+        image[84 + 16..84 + 20].copy_from_slice(&0x1000_u32.to_le_bytes());
+        assert_eq!(
+            flat_binary(&image, builder()),
+            Err(FlatBinaryError::InvalidElf)
+        );
+        assert_eq!(elf_address_tags(&image), Err(FlatBinaryError::InvalidElf));
+    }
+}
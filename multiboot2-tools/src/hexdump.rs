@@ -0,0 +1,204 @@
+//! Module for [`parse_hexdump`].
+
+use thiserror::Error;
+
+/// Error of [`parse_hexdump`].
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum HexdumpError {
+    /// A line contains something other than hexadecimal bytes.
+    #[error("line {0}: invalid hexadecimal bytes")]
+    InvalidBytes(usize),
+    /// The offset of a line doesn't match the bytes before it.
+    #[error("line {line}: offset 0x{offset:x} doesn't match the 0x{expected:x} bytes before")]
+    OffsetMismatch {
+        /// The line number, starting at 1.
+        line: usize,
+        /// The offset of the line.
+        offset: usize,
+        /// The number of bytes before the line.
+        expected: usize,
+    },
+}
+
+/// Format of a hexdump, determined by its first line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Format {
+    /// `xxd`: "00000010: 0800 0000 ...  ascii"
+    Xxd,
+    /// `hexdump -C`: "00000010  08 00 00 00 ...  |ascii|"
+    Hexdump,
+    /// Hexadecimal bytes without offsets.
+    Plain,
+}
+
+impl Format {
+    fn detect(line: &str) -> Self {
+        let mut tokens = line
+            .split('|')
+            .next()
+            .unwrap_or_default()
+            .split_whitespace();
+        let first = tokens.next().unwrap_or_default();
+        let mut rest = tokens.peekable();
+        if first.ends_with(':') {
+            Self::Xxd
+        } else if first.len() > 2 && rest.peek().is_some() && rest.all(|token| token.len() == 2) {
+            Self::Hexdump
+        } else {
+            Self::Plain
+        }
+    }
+
+    /// Parses a line into its offset and bytes.
+    fn parse_line(self, line: &str) -> Option<(Option<usize>, Vec<u8>)> {
+        match self {
+            Self::Xxd => {
+                let (offset, rest) = line.split_once(':')?;
+                let offset = usize::from_str_radix(offset, 16).ok()?;
+                // The ASCII column is separated by two spaces.
+                let hex = rest.trim_start().split("  ").next().unwrap_or_default();
+                Some((Some(offset), decode_hex(hex)?))
+            }
+            Self::Hexdump => {
+                let line = line.split('|').next().unwrap_or_default();
+                let (offset, hex) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                let offset = usize::from_str_radix(offset, 16).ok()?;
+                Some((Some(offset), decode_hex(hex)?))
+            }
+            Self::Plain => Some((None, decode_hex(line)?)),
+        }
+    }
+}
+
+/// Decodes a string of hex digits, ignoring whitespace.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let digits = hex
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(16).map(|digit| digit as u8))
+        .collect::<Option<Vec<_>>>()?;
+    (digits.len() % 2 == 0).then(|| {
+        digits
+            .chunks(2)
+            .map(|pair| (pair[0] << 4) | pair[1])
+            .collect()
+    })
+}
+
+/// Returns the non-empty, trimmed lines with their line numbers.
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+/// Parses a textual hexdump, e.g., of a serial console or of a memory dump
+/// that was converted to text.
+///
+/// Supported are the formats of `xxd`, `hexdump -C` (including `*` lines),
+/// and plain hexadecimal bytes. The format is determined by the first line.
+/// Empty lines are ignored. If lines have offsets, they must be consecutive.
+pub fn parse_hexdump(text: &str) -> Result<Vec<u8>, HexdumpError> {
+    let format = lines(text)
+        .next()
+        .map_or(Format::Plain, |(_, line)| Format::detect(line));
+    parse_lines(text, format)
+}
+
+/// Parses plain hexadecimal bytes, ignoring whitespace, e.g., `"5f534d33 5f"`.
+///
+/// Unlike [`parse_hexdump`], this never interprets the first bytes of a line
+/// as an offset.
+pub fn parse_hex(text: &str) -> Result<Vec<u8>, HexdumpError> {
+    parse_lines(text, Format::Plain)
+}
+
+fn parse_lines(text: &str, format: Format) -> Result<Vec<u8>, HexdumpError> {
+    let mut bytes = Vec::new();
+    let mut previous = Vec::new();
+    let mut repeat = false;
+    for (number, line) in lines(text) {
+        if line == "*" && format == Format::Hexdump {
+            repeat = true;
+            continue;
+        }
+        let (offset, line_bytes) = format
+            .parse_line(line)
+            .ok_or(HexdumpError::InvalidBytes(number))?;
+        if let Some(offset) = offset {
+            while repeat && !previous.is_empty() && bytes.len() < offset {
+                bytes.extend_from_slice(&previous);
+            }
+            if offset != bytes.len() {
+                return Err(HexdumpError::OffsetMismatch {
+                    line: number,
+                    offset,
+                    expected: bytes.len(),
+                });
+            }
+        }
+        repeat = false;
+        bytes.extend_from_slice(&line_bytes);
+        previous = line_bytes;
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats() {
+        let xxd = "\
+00000000: 0800 0000 0000 0000 0100 0000 1000 0000  ................
+00000010: 6162                                     ab
+";
+        let hexdump = "\
+00000000  08 00 00 00 00 00 00 00  01 00 00 00 10 00 00 00  |................|
+00000010  61 62                                             |ab|
+00000012
+";
+        let plain = "08000000 00000000\n0100000010000000 6162\n";
+        let expected = [
+            8, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0x10, 0, 0, 0, b'a', b'b',
+        ];
+        assert_eq!(parse_hexdump(xxd).unwrap(), expected);
+        assert_eq!(parse_hexdump(hexdump).unwrap(), expected);
+        assert_eq!(parse_hexdump(plain).unwrap(), expected);
+        assert_eq!(parse_hex(plain).unwrap(), expected);
+        assert_eq!(parse_hex("0800000000 00").unwrap(), [8, 0, 0, 0, 0, 0]);
+        assert!(parse_hexdump("0800000000 00").is_err());
+    }
+
+    #[test]
+    fn repeated_lines() {
+        let hexdump = "\
+00000000  01 01 01 01 01 01 01 01  01 01 01 01 01 01 01 01  |................|
+*
+00000030  02 02                                             |..|
+00000032
+";
+        let bytes = parse_hexdump(hexdump).unwrap();
+        assert_eq!(bytes.len(), 0x32);
+        assert!(bytes[..0x30].iter().all(|&byte| byte == 1));
+        assert_eq!(bytes[0x30..], [2, 2]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse_hexdump("0102\nxyz\n"),
+            Err(HexdumpError::InvalidBytes(2))
+        );
+        assert_eq!(
+            parse_hexdump("00000000: 0102\n00000004: 0304\n"),
+            Err(HexdumpError::OffsetMismatch {
+                line: 2,
+                offset: 4,
+                expected: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Host-side tools for Multiboot2 kernel images.
+//!
+//! Unlike the other crates of this repository, this crate requires `std`. It
+//! is meant for build systems and developer tools, not for bootloaders or
+//! kernels.
+//!
+//! ## Example: Embedding a Header
+//!
+//! ```no_run
+//! use multiboot2_header::{Builder, HeaderTagFlag, HeaderTagISA, ModuleAlignHeaderTag};
+//! use multiboot2_tools::embed_header;
+//!
+//! let elf = std::fs::read("kernel.elf").unwrap();
+//! let builder = Builder::new(HeaderTagISA::I386)
+//!     .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required));
+//! let embedded = embed_header(&elf, builder).unwrap();
+//! std::fs::write("kernel-mb2.elf", embedded.image()).unwrap();
+//! ```
+//!
+//! ## MSRV
+//!
+//! The MSRV is 1.85.1 stable.
+
+// --- BEGIN STYLE CHECKS ---
+#![deny(
+    clippy::all,
+    clippy::cargo,
+    clippy::nursery,
+    clippy::must_use_candidate,
+    // clippy::restriction,
+    // clippy::pedantic
+)]
+// now allow a few rules which are denied by the above statement
+// --> They are either ridiculous, not necessary, or we can't fix them.
+#![allow(clippy::multiple_crate_versions)]
+#![deny(missing_docs)]
+#![deny(missing_debug_implementations)]
+#![deny(rustdoc::all)]
+// --- END STYLE CHECKS ---
+
+mod aligned;
+mod embed;
+mod fixture;
+mod flat;
+mod hexdump;
+
+pub use self::aligned::*;
+pub use self::embed::*;
+pub use self::fixture::*;
+pub use self::flat::*;
+pub use self::hexdump::*;
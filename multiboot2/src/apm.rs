@@ -4,7 +4,7 @@ use crate::{TagHeader, TagType};
 use multiboot2_common::{MaybeDynSized, Tag};
 
 /// The Advanced Power Management (APM) tag.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug)]
 #[repr(C, align(8))]
 pub struct ApmTag {
     header: TagHeader,
@@ -120,3 +120,53 @@ impl Tag for ApmTag {
 
     const ID: TagType = TagType::Apm;
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ApmTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("ApmTag", 9)?;
+        state.serialize_field("version", &self.version)?;
+        state.serialize_field("cseg", &self.cseg)?;
+        state.serialize_field("offset", &self.offset)?;
+        state.serialize_field("cset_16", &self.cset_16)?;
+        state.serialize_field("dseg", &self.dseg)?;
+        state.serialize_field("flags", &self.flags)?;
+        state.serialize_field("cseg_len", &self.cseg_len)?;
+        state.serialize_field("cseg_16_len", &self.cseg_16_len)?;
+        state.serialize_field("dseg_len", &self.dseg_len)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ApmTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "ApmTag")]
+        struct Fields {
+            version: u16,
+            cseg: u16,
+            offset: u32,
+            cset_16: u16,
+            dseg: u16,
+            flags: u16,
+            cseg_len: u16,
+            cseg_16_len: u16,
+            dseg_len: u16,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(
+            fields.version,
+            fields.cseg,
+            fields.offset,
+            fields.cset_16,
+            fields.dseg,
+            fields.flags,
+            fields.cseg_len,
+            fields.cseg_16_len,
+            fields.dseg_len,
+        ))
+    }
+}
@@ -1,13 +1,15 @@
 //! Module for [`BootInformation`].
 
+use crate::custom_tag_decoder::DebugCustomTag;
 use crate::framebuffer::UnknownFramebufferType;
+use crate::summary::Summary;
 use crate::tag::TagHeader;
 use crate::{
-    ApmTag, BasicMemoryInfoTag, BootLoaderNameTag, BootdevTag, CommandLineTag,
+    ApmTag, BasicMemoryInfoTag, BootLoaderNameTag, BootdevTag, CommandLineTag, CustomTagDecoder,
     EFIBootServicesNotExitedTag, EFIImageHandle32Tag, EFIImageHandle64Tag, EFIMemoryMapTag,
     EFISdt32Tag, EFISdt64Tag, ElfSectionIter, ElfSectionsTag, EndTag, FramebufferTag,
     ImageLoadPhysAddrTag, MemoryMapTag, ModuleIter, NetworkTag, RsdpV1Tag, RsdpV2Tag, SmbiosTag,
-    TagIter, TagType, VBEInfoTag, module,
+    TagIter, TagRef, TagType, VBEInfoTag, module,
 };
 use core::fmt;
 use core::ptr::NonNull;
@@ -40,7 +42,7 @@ pub struct BootInformationHeader {
 }
 
 impl BootInformationHeader {
-    #[cfg(feature = "builder")]
+    #[cfg(feature = "alloc")]
     pub(crate) const fn new(total_size: u32) -> Self {
         Self {
             total_size,
@@ -106,7 +108,18 @@ impl<'a> BootInformation<'a> {
         // SAFETY: `ptr` was checked for null and `ref_from_ptr` validates the
         // reported total size before constructing the DST reference.
         let inner = unsafe { DynSizedStructure::ref_from_ptr(ptr).map_err(LoadError::Memory)? };
+        Self::new(inner)
+    }
+
+    /// Loads the [`BootInformation`] from the beginning of `bytes`, which may
+    /// be longer than the boot information.
+    pub(crate) fn from_slice(bytes: &'a [u8]) -> Result<Self, LoadError> {
+        let inner = DynSizedStructure::ref_from_slice(bytes).map_err(LoadError::Memory)?;
+        Self::new(inner)
+    }
 
+    /// Validates the tags of the boot information.
+    fn new(inner: &'a DynSizedStructure<BootInformationHeader>) -> Result<Self, LoadError> {
         let this = Self(inner);
         if !this.has_valid_tag_sequence().map_err(LoadError::Memory)? {
             return Err(LoadError::NoEndTag);
@@ -117,8 +130,8 @@ impl<'a> BootInformation<'a> {
     /// Checks if the MBI has a valid, complete tag sequence.
     fn has_valid_tag_sequence(&self) -> Result<bool, MemoryError> {
         validate_tag_sequence(self.0.payload(), |tag| {
-            let typ = u32::from_le_bytes(tag[0..4].try_into().unwrap());
-            let size = u32::from_le_bytes(tag[4..8].try_into().unwrap()) as usize;
+            let typ = u32::from_ne_bytes(tag[0..4].try_into().unwrap());
+            let size = u32::from_ne_bytes(tag[4..8].try_into().unwrap()) as usize;
 
             typ == TagType::End.val() && size == size_of::<EndTag>()
         })
@@ -370,6 +383,22 @@ impl<'a> BootInformation<'a> {
     /// custom tags from the MBI. If they are dynamically sized (DST), a few more
     /// special handling is required. This is reflected by code-comments.
     ///
+    /// With the `derive` feature, `#[derive(Multiboot2Tag)]` generates these
+    /// trait implementations, checks the layout, and adds a constructor:
+    ///
+    /// ```ignore
+    /// #[derive(multiboot2::Multiboot2Tag)]
+    /// #[tag(id = 0x1337)]
+    /// #[repr(C, align(8))]
+    /// struct CustomTag {
+    ///     header: TagHeader,
+    ///     some_other_prop: u32,
+    ///     name: [u8],
+    /// }
+    /// ```
+    ///
+    /// Without it, implement the traits manually:
+    ///
     /// ```no_run
     /// use std::mem;
     /// use multiboot2::{BootInformation, BootInformationHeader, parse_slice_as_string, StringError, TagHeader, TagType, TagTypeId};    ///
@@ -467,47 +496,161 @@ impl<'a> BootInformation<'a> {
         // SAFETY: We validated the chain of tags beforehand.
         unsafe { TagIter::new(self.0.payload()) }
     }
+
+    /// Returns the bytes of the validated chain of tags.
+    pub(crate) const fn tag_bytes(&self) -> &'a [u8] {
+        self.0.payload()
+    }
+
+    /// Returns an iterator over all tags as [`TagRef`], including the end
+    /// tag, in their original order.
+    ///
+    /// In contrast to [`Self::tags`], this enables exhaustive matching over
+    /// all tag types without manual casts.
+    ///
+    /// # Panics
+    /// Each tag is cast to its type as soon as the iterator yields it, even
+    /// if the caller never matches on it. Hence, this panics like the
+    /// corresponding getter if a tag is malformed, e.g., if a built-in tag has
+    /// an invalid size or the color info of a framebuffer tag exceeds the tag.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use multiboot2::{BootInformation, BootInformationHeader, TagRef};
+    ///
+    /// let mbi_ptr = 0xdeadbeef as *const BootInformationHeader;
+    /// let mbi = unsafe { BootInformation::load(mbi_ptr).unwrap() };
+    /// for tag in mbi.typed_tags() {
+    ///     match tag {
+    ///         TagRef::Cmdline(tag) => println!("cmdline: {:?}", tag.cmdline()),
+    ///         TagRef::Module(tag) => println!("module: {:#x}", tag.start_address()),
+    ///         other => println!("{:?}", other.typ()),
+    ///     }
+    /// }
+    /// ```
+    pub fn typed_tags(&self) -> impl Iterator<Item = TagRef<'_>> + Clone {
+        self.tags().map(TagRef::from)
+    }
+
+    /// Returns a compact, human-readable report of the boot information that
+    /// is stable and suited for logs and snapshot tests.
+    ///
+    /// In contrast to the exhaustive [`Debug`] output, it only contains the
+    /// most relevant information with one line per entry:
+    /// - the memory map with sizes in MiB and type names,
+    /// - all modules with their sizes and command lines,
+    /// - the framebuffer mode,
+    /// - the ACPI revision and OEM,
+    /// - all ELF sections with name, address, size, and flags, and
+    /// - the EFI memory map, grouped by type.
+    ///
+    /// ELF sections are shown with the offsets of their names in the string
+    /// table. Use [`Self::summary_with_string_table`] to show the names.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use multiboot2::{BootInformation, BootInformationHeader};
+    ///
+    /// let mbi_ptr = 0xdeadbeef as *const BootInformationHeader;
+    /// let mbi = unsafe { BootInformation::load(mbi_ptr).unwrap() };
+    /// println!("{}", mbi.summary());
+    /// ```
+    ///
+    /// [`Debug`]: fmt::Debug
+    #[must_use]
+    pub fn summary(&self) -> impl fmt::Display + '_ {
+        self.summary_with_string_table(None)
+    }
+
+    /// Like [`Self::summary`] but resolves the names of the ELF sections
+    /// with the given section name string table.
+    ///
+    /// When the boot information is at its physical address, the string table
+    /// can be obtained with [`ElfSectionsTag::string_table`]. For dumps, it
+    /// must be read from the dump instead.
+    #[must_use]
+    pub fn summary_with_string_table<'b>(
+        &'b self,
+        string_table: Option<&'b [u8]>,
+    ) -> impl fmt::Display + 'b {
+        Summary {
+            info: self,
+            string_table,
+        }
+    }
+
+    /// Returns a [`Debug`] representation like the one of this type that
+    /// additionally shows all custom tags for which a [`CustomTagDecoder`]
+    /// is given. Custom tags without a decoder are shown by their header.
+    ///
+    /// [`Debug`]: fmt::Debug
+    #[must_use]
+    pub fn debug_with<'b>(&'b self, decoders: &'b [CustomTagDecoder]) -> impl fmt::Debug + 'b {
+        DebugBootInformation {
+            info: self,
+            decoders,
+        }
+    }
 }
 
 impl fmt::Debug for BootInformation<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.debug_with(&[]), f)
+    }
+}
+
+/// Formats a [`BootInformation`] with the given custom tag decoders.
+struct DebugBootInformation<'a, 'b> {
+    info: &'b BootInformation<'a>,
+    decoders: &'b [CustomTagDecoder],
+}
+
+impl fmt::Debug for DebugBootInformation<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let info = self.info;
+        let custom_tags = info.tags().filter(|tag| {
+            let id: TagType = tag.header().typ.into();
+            matches!(id, TagType::Custom(_))
+        });
         let mut debug = f.debug_struct("BootInformation");
         debug
-            .field("start_address", &self.start_address())
-            .field("end_address", &self.end_address())
-            .field("total_size", &self.total_size())
+            .field("start_address", &info.start_address())
+            .field("end_address", &info.end_address())
+            .field("total_size", &info.total_size())
             // now tags in alphabetical order
-            .field("apm", &self.apm_tag())
-            .field("basic_memory_info", &(self.basic_memory_info_tag()))
-            .field("boot_loader_name", &self.boot_loader_name_tag())
-            .field("bootdev", &self.bootdev_tag())
-            .field("command_line", &self.command_line_tag())
-            .field("efi_bs_not_exited", &self.efi_bs_not_exited_tag())
-            .field("efi_ih32", &self.efi_ih32_tag())
-            .field("efi_ih64", &self.efi_ih64_tag())
-            .field("efi_memory_map", &self.efi_memory_map_tag())
-            .field("efi_sdt32", &self.efi_sdt32_tag())
-            .field("efi_sdt64", &self.efi_sdt64_tag())
-            .field("elf_sections", &self.elf_sections_tag())
-            .field("framebuffer", &self.framebuffer_tag())
-            .field("load_base_addr", &self.load_base_addr_tag())
-            .field("memory_map", &self.memory_map_tag())
-            .field("modules", &self.module_tags())
-            .field("network", &DebugTags(self.network_tags()))
-            .field("rsdp_v1", &self.rsdp_v1_tag())
-            .field("rsdp_v2", &self.rsdp_v2_tag())
-            .field("smbios", &DebugTags(self.smbios_tags()))
-            .field("vbe_info", &self.vbe_info_tag())
+            .field("apm", &info.apm_tag())
+            .field("basic_memory_info", &(info.basic_memory_info_tag()))
+            .field("boot_loader_name", &info.boot_loader_name_tag())
+            .field("bootdev", &info.bootdev_tag())
+            .field("command_line", &info.command_line_tag())
+            .field("efi_bs_not_exited", &info.efi_bs_not_exited_tag())
+            .field("efi_ih32", &info.efi_ih32_tag())
+            .field("efi_ih64", &info.efi_ih64_tag())
+            .field("efi_memory_map", &info.efi_memory_map_tag())
+            .field("efi_sdt32", &info.efi_sdt32_tag())
+            .field("efi_sdt64", &info.efi_sdt64_tag())
+            .field("elf_sections", &info.elf_sections_tag())
+            .field("framebuffer", &info.framebuffer_tag())
+            .field("load_base_addr", &info.load_base_addr_tag())
+            .field("memory_map", &info.memory_map_tag())
+            .field("modules", &info.module_tags())
+            .field("network", &DebugTags(info.network_tags()))
+            .field("rsdp_v1", &info.rsdp_v1_tag())
+            .field("rsdp_v2", &info.rsdp_v2_tag())
+            .field("smbios", &DebugTags(info.smbios_tags()))
+            .field("vbe_info", &info.vbe_info_tag())
             // computed fields
-            .field("custom_tags_count", &{
-                self.tags()
-                    .filter(|tag| {
-                        let id: TagType = tag.header().typ.into();
-                        matches!(id, TagType::Custom(_))
-                    })
-                    .count()
-            })
-            .field("tag_headers", &DebugTagHeaders(self.tags()))
+            .field("custom_tags_count", &custom_tags.clone().count())
+            .field(
+                "custom_tags",
+                &DebugTags(custom_tags.map(|tag| DebugCustomTag {
+                    tag,
+                    decoders: self.decoders,
+                })),
+            )
+            .field("tag_headers", &DebugTagHeaders(info.tags()))
             .finish()
     }
 }
@@ -535,3 +678,111 @@ impl fmt::Debug for DebugTagHeaders<'_> {
             .finish()
     }
 }
+
+/// Serialized form of a tag, tagged with its type.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum SerializedTag<'a> {
+    Cmdline(&'a CommandLineTag),
+    BootLoaderName(&'a BootLoaderNameTag),
+    Module(&'a module::ModuleTag),
+    BasicMeminfo(&'a BasicMemoryInfoTag),
+    Bootdev(&'a BootdevTag),
+    Mmap(&'a MemoryMapTag),
+    Vbe(&'a VBEInfoTag),
+    #[serde(serialize_with = "serialize_framebuffer")]
+    Framebuffer(Result<&'a FramebufferTag, UnknownFramebufferType>),
+    ElfSections(&'a ElfSectionsTag),
+    Apm(&'a ApmTag),
+    Efi32(&'a EFISdt32Tag),
+    Efi64(&'a EFISdt64Tag),
+    Smbios(&'a SmbiosTag),
+    AcpiV1(&'a RsdpV1Tag),
+    AcpiV2(&'a RsdpV2Tag),
+    Network(&'a NetworkTag),
+    EfiMmap(&'a EFIMemoryMapTag),
+    EfiBs(&'a EFIBootServicesNotExitedTag),
+    Efi32Ih(&'a EFIImageHandle32Tag),
+    Efi64Ih(&'a EFIImageHandle64Tag),
+    LoadBaseAddr(&'a ImageLoadPhysAddrTag),
+    Custom {
+        id: u32,
+        payload: &'a [u8],
+    },
+}
+
+#[cfg(feature = "serde")]
+impl<'a> SerializedTag<'a> {
+    /// Returns the serialized form of the tag. Returns `None` for the end tag.
+    fn new(tag: TagRef<'a>) -> Option<Self> {
+        let tag = match tag {
+            TagRef::End(_) => return None,
+            TagRef::Cmdline(tag) => Self::Cmdline(tag),
+            TagRef::BootLoaderName(tag) => Self::BootLoaderName(tag),
+            TagRef::Module(tag) => Self::Module(tag),
+            TagRef::BasicMeminfo(tag) => Self::BasicMeminfo(tag),
+            TagRef::Bootdev(tag) => Self::Bootdev(tag),
+            TagRef::Mmap(tag) => Self::Mmap(tag),
+            TagRef::Vbe(tag) => Self::Vbe(tag),
+            TagRef::Framebuffer(tag) => Self::Framebuffer(tag),
+            TagRef::ElfSections(tag) => Self::ElfSections(tag),
+            TagRef::Apm(tag) => Self::Apm(tag),
+            TagRef::Efi32(tag) => Self::Efi32(tag),
+            TagRef::Efi64(tag) => Self::Efi64(tag),
+            TagRef::Smbios(tag) => Self::Smbios(tag),
+            TagRef::AcpiV1(tag) => Self::AcpiV1(tag),
+            TagRef::AcpiV2(tag) => Self::AcpiV2(tag),
+            TagRef::Network(tag) => Self::Network(tag),
+            TagRef::EfiMmap(tag) => Self::EfiMmap(tag),
+            TagRef::EfiBs(tag) => Self::EfiBs(tag),
+            TagRef::Efi32Ih(tag) => Self::Efi32Ih(tag),
+            TagRef::Efi64Ih(tag) => Self::Efi64Ih(tag),
+            TagRef::LoadBaseAddr(tag) => Self::LoadBaseAddr(tag),
+            TagRef::Unknown(tag) => Self::Custom {
+                id: tag.header().typ.into(),
+                payload: tag.payload(),
+            },
+        };
+        Some(tag)
+    }
+}
+
+/// Serializes a framebuffer tag. Fails if its framebuffer type is unknown.
+#[cfg(feature = "serde")]
+fn serialize_framebuffer<S: serde::Serializer>(
+    tag: &Result<&FramebufferTag, UnknownFramebufferType>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use serde::Serialize;
+    use serde::ser::Error;
+    tag.map_err(S::Error::custom)?.serialize(serializer)
+}
+
+/// Serializes the boot information as `{ "tags": [...] }`. Each tag is an
+/// object with a snake-case `type` and the fields of the tag. Custom tags
+/// are serialized with their `id` and raw `payload`. The end tag is omitted.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BootInformation<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        /// Serializes the tags as a sequence.
+        struct Tags<'a>(TagIter<'a>);
+
+        impl serde::Serialize for Tags<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(
+                    self.0
+                        .clone()
+                        .map(TagRef::from)
+                        .filter_map(SerializedTag::new),
+                )
+            }
+        }
+
+        let mut state = serializer.serialize_struct("BootInformation", 1)?;
+        state.serialize_field("tags", &Tags(self.tags()))?;
+        state.end()
+    }
+}
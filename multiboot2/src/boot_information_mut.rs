@@ -0,0 +1,453 @@
+//! Module for [`BootInformationMut`].
+
+use crate::{
+    BasicMemoryInfoTag, BootInformation, BootInformationHeader, EndTag, FramebufferTag, LoadError,
+    MemoryMapTag, TagHeader, TagType, TagTypeId,
+};
+use core::fmt;
+use core::ptr;
+use multiboot2_common::{Header, MaybeDynSized, Tag, increase_to_alignment};
+use thiserror::Error;
+
+/// Errors that occur when pushing a tag to a [`BootInformationMut`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+pub enum PushTagError {
+    /// The size reported by the tag header is smaller than the header or
+    /// larger than the tag.
+    #[error("the tag reports an invalid size")]
+    InvalidSize,
+    /// The end tag can't be pushed, as it is always present at the end.
+    #[error("the end tag can't be pushed")]
+    EndTag,
+    /// The tag doesn't fit into the spare capacity of the buffer.
+    #[error("tag of {required} bytes doesn't fit into the spare capacity of {available} bytes")]
+    InsufficientCapacity {
+        /// The required size in bytes, including padding.
+        required: usize,
+        /// The spare capacity in bytes.
+        available: usize,
+    },
+}
+
+/// A mutable Multiboot2 boot information (MBI) in a byte buffer.
+///
+/// This enables bootloaders and shims to patch a boot information in place
+/// before handing it over, e.g., to change the command line, to mark a memory
+/// area as reserved, or to drop tags. The buffer may be larger than the boot
+/// information. Tags are pushed into this spare capacity. All modifications
+/// keep the total size and the end tag correct.
+///
+/// # Example
+///
+/// ```
+/// use multiboot2::{
+///     BootInformationMut, Builder, CommandLineTag, MemoryArea, MemoryAreaType, MemoryMapTag,
+///     MaybeDynSized, TagType,
+/// };
+///
+/// #[repr(C, align(8))]
+/// struct Buffer([u8; 256]);
+///
+/// let mbi = Builder::new()
+///     .cmdline(CommandLineTag::new("quiet"))
+///     .mmap(MemoryMapTag::new(&[MemoryArea::new(0, 0x1000, MemoryAreaType::Available)]))
+///     .build();
+/// let mut buffer = Buffer([0; 256]);
+/// buffer.0[..mbi.as_bytes().len()].copy_from_slice(&mbi.as_bytes());
+///
+/// let mut mbi = BootInformationMut::new(&mut buffer.0).unwrap();
+/// mbi.memory_map_tag_mut().unwrap().memory_areas_mut()[0].set_typ(MemoryAreaType::Reserved);
+/// mbi.remove_tags(TagType::Cmdline);
+/// mbi.push_tag(&*CommandLineTag::new("console=ttyS0")).unwrap();
+///
+/// let mbi = mbi.as_boot_information();
+/// assert_eq!(mbi.command_line_tag().unwrap().cmdline(), Ok("console=ttyS0"));
+/// ```
+pub struct BootInformationMut<'a>(&'a mut [u8]);
+
+impl<'a> BootInformationMut<'a> {
+    /// Wraps the boot information at the beginning of `buffer`.
+    ///
+    /// The buffer must be 8-byte aligned. It returns a [`LoadError`] under the
+    /// same conditions as [`BootInformation::load`].
+    pub fn new(buffer: &'a mut [u8]) -> Result<Self, LoadError> {
+        BootInformation::from_slice(buffer)?;
+        Ok(Self(buffer))
+    }
+
+    /// Returns a [`BootInformation`] to read the current tags.
+    #[must_use]
+    pub fn as_boot_information(&self) -> BootInformation<'_> {
+        BootInformation::from_slice(self.0).expect("tags should stay valid")
+    }
+
+    /// Returns the bytes of the boot information, without the spare
+    /// capacity.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0[..self.total_size()]
+    }
+
+    /// Returns the total size of the boot information.
+    #[must_use]
+    pub fn total_size(&self) -> usize {
+        u32::from_ne_bytes(self.0[..4].try_into().unwrap()) as usize
+    }
+
+    /// Returns the size of the underlying buffer.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the amount of bytes after the boot information that are
+    /// available to push tags.
+    #[must_use]
+    pub fn spare_capacity(&self) -> usize {
+        self.capacity() - self.total_size()
+    }
+
+    fn set_total_size(&mut self, total_size: usize) {
+        self.0[..4].copy_from_slice(&(total_size as u32).to_ne_bytes());
+    }
+
+    /// Returns the offset and the size, including padding, of the first tag
+    /// of type `typ`.
+    fn find(&self, typ: TagTypeId) -> Option<(usize, usize)> {
+        let mut offset = size_of::<BootInformationHeader>();
+        for tag in self.as_boot_information().tags() {
+            let size = increase_to_alignment(tag.header().total_size());
+            if tag.header().typ == typ {
+                return Some((offset, size));
+            }
+            offset += size;
+        }
+        None
+    }
+
+    // ######################################################
+    // ### BEGIN OF TAG GETTERS (in alphabetical order)
+
+    /// Returns the first [`BasicMemoryInfoTag`], if present.
+    #[must_use]
+    pub fn basic_memory_info_tag_mut(&mut self) -> Option<&mut BasicMemoryInfoTag> {
+        self.get_tag_mut::<BasicMemoryInfoTag>()
+    }
+
+    /// Returns the first [`FramebufferTag`], if present.
+    #[must_use]
+    pub fn framebuffer_tag_mut(&mut self) -> Option<&mut FramebufferTag> {
+        self.get_tag_mut::<FramebufferTag>()
+    }
+
+    /// Returns the first [`MemoryMapTag`], if present. Use
+    /// [`MemoryMapTag::memory_areas_mut`] to modify the memory areas.
+    #[must_use]
+    pub fn memory_map_tag_mut(&mut self) -> Option<&mut MemoryMapTag> {
+        self.get_tag_mut::<MemoryMapTag>()
+    }
+
+    // ### END OF TAG GETTERS
+    // ######################################################
+
+    /// Returns the payload of the first custom tag of type `typ`, i.e., the
+    /// bytes after the [`TagHeader`] without padding, if present.
+    ///
+    /// This modifies custom tags in place. The header isn't accessible, so
+    /// the type and size of the tag stay valid. Returns `None` for built-in
+    /// tag types, as their getters rely on the fields of the payload, such as
+    /// the entry size of the [`MemoryMapTag`].
+    #[must_use]
+    pub fn get_tag_payload_mut(&mut self, typ: impl Into<TagTypeId>) -> Option<&mut [u8]> {
+        let typ = typ.into();
+        if !matches!(TagType::from(typ), TagType::Custom(_)) {
+            return None;
+        }
+        let (offset, _) = self.find(typ)?;
+        let size_offset = offset + size_of::<TagTypeId>();
+        let size = u32::from_ne_bytes(self.0[size_offset..size_offset + 4].try_into().unwrap());
+        Some(&mut self.0[offset + size_of::<TagHeader>()..offset + size as usize])
+    }
+
+    /// Returns a mutable reference to the first tag of type `T`, if present.
+    ///
+    /// This is only used for built-in tags, which don't allow modifying their
+    /// header. Otherwise, safe code could corrupt the tag sequence.
+    fn get_tag_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: Tag<IDType = TagType, Header = TagHeader> + ?Sized,
+        T::Metadata: Default,
+    {
+        let (offset, metadata) = {
+            let info = self.as_boot_information();
+            let tag = info.get_tag::<T>()?;
+            let offset = ptr::from_ref(tag).cast::<u8>().addr() - self.0.as_ptr().addr();
+            (offset, ptr_meta::metadata(tag))
+        };
+        let ptr = ptr_meta::from_raw_parts_mut::<T>(self.0[offset..].as_mut_ptr().cast(), metadata);
+        // SAFETY: `ptr` has the same address and metadata as the valid
+        // reference of the tag above, but is derived from the mutable buffer,
+        // which is exclusively borrowed for the lifetime of the result.
+        Some(unsafe { &mut *ptr })
+    }
+
+    /// Removes all tags of type `typ` and moves the following tags to close
+    /// the gaps. Returns the amount of removed tags.
+    ///
+    /// # Panics
+    /// Panics if `typ` is [`TagType::End`].
+    pub fn remove_tags(&mut self, typ: impl Into<TagTypeId>) -> usize {
+        let typ = typ.into();
+        assert_ne!(
+            TagType::from(typ),
+            TagType::End,
+            "the end tag can't be removed"
+        );
+        let mut removed = 0;
+        while let Some((offset, size)) = self.find(typ) {
+            let total_size = self.total_size();
+            self.0.copy_within(offset + size..total_size, offset);
+            self.0[total_size - size..total_size].fill(0);
+            self.set_total_size(total_size - size);
+            removed += 1;
+        }
+        removed
+    }
+
+    /// Appends a tag in front of the end tag, using the spare capacity of
+    /// the buffer.
+    pub fn push_tag<T: MaybeDynSized<Header = TagHeader> + ?Sized>(
+        &mut self,
+        tag: &T,
+    ) -> Result<(), PushTagError> {
+        if TagType::from(tag.header().typ) == TagType::End {
+            return Err(PushTagError::EndTag);
+        }
+        let bytes = tag.as_bytes();
+        let size = tag.header().total_size();
+        if size < size_of::<TagHeader>() || size > bytes.len() {
+            return Err(PushTagError::InvalidSize);
+        }
+        let required = increase_to_alignment(size);
+        let available = self.spare_capacity();
+        if required > available {
+            return Err(PushTagError::InsufficientCapacity {
+                required,
+                available,
+            });
+        }
+
+        // The new tag takes the place of the end tag, which moves behind it.
+        let total_size = self.total_size();
+        let offset = total_size - size_of::<EndTag>();
+        self.0[offset..offset + size].copy_from_slice(&bytes[..size]);
+        self.0[offset + size..offset + required].fill(0);
+        let end_tag = EndTag::default();
+        let end_offset = offset + required;
+        self.0[end_offset..end_offset + size_of::<EndTag>()].copy_from_slice(&end_tag.as_bytes());
+        self.set_total_size(total_size + required);
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BootInformationMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BootInformationMut")
+            .field(&self.as_boot_information())
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{Builder, CommandLineTag, FramebufferType, MemoryArea, MemoryAreaType, ModuleTag};
+    use alloc::vec::Vec;
+    use multiboot2_common::{DynSizedStructure, MemoryError, new_boxed};
+
+    #[repr(C, align(8))]
+    struct Buffer([u8; 512]);
+
+    fn buffer(builder: Builder) -> Buffer {
+        let mbi = builder.build();
+        let mut buffer = Buffer([0xff; 512]);
+        buffer.0[..mbi.as_bytes().len()].copy_from_slice(&mbi.as_bytes());
+        buffer
+    }
+
+    fn builder() -> Builder {
+        Builder::new()
+            .cmdline(CommandLineTag::new("quiet"))
+            .add_module(ModuleTag::new(0x1000, 0x2000, "module 1"))
+            .add_module(ModuleTag::new(0x3000, 0x4000, "module 2"))
+            .meminfo(BasicMemoryInfoTag::new(640, 1024))
+            .mmap(MemoryMapTag::new(&[
+                MemoryArea::new(0, 0x1000, MemoryAreaType::Available),
+                MemoryArea::new(0x1000, 0x1000, MemoryAreaType::Available),
+            ]))
+            .framebuffer(FramebufferTag::new(
+                0x1000,
+                1,
+                1024,
+                768,
+                8,
+                FramebufferType::Text,
+            ))
+    }
+
+    #[test]
+    fn new() {
+        let mut buffer = buffer(builder());
+        assert_eq!(
+            BootInformationMut::new(&mut buffer.0[1..]).unwrap_err(),
+            LoadError::Memory(MemoryError::WrongAlignment)
+        );
+        assert!(BootInformationMut::new(&mut buffer.0[..16]).is_err());
+        let mbi = BootInformationMut::new(&mut buffer.0).unwrap();
+        assert_eq!(mbi.capacity(), 512);
+        assert_eq!(mbi.spare_capacity(), 512 - mbi.total_size());
+        assert_eq!(mbi.as_bytes().len(), mbi.total_size());
+    }
+
+    #[test]
+    fn modify_tags() {
+        let mut buffer = buffer(builder());
+        let mut mbi = BootInformationMut::new(&mut buffer.0).unwrap();
+
+        let meminfo = mbi.basic_memory_info_tag_mut().unwrap();
+        meminfo.set_memory_lower(639);
+        meminfo.set_memory_upper(2048);
+        let framebuffer = mbi.framebuffer_tag_mut().unwrap();
+        framebuffer.set_address(0x2000);
+        framebuffer.set_pitch(2);
+        framebuffer.set_width(800);
+        framebuffer.set_height(600);
+        mbi.memory_map_tag_mut().unwrap().memory_areas_mut()[1].set_typ(MemoryAreaType::Reserved);
+
+        let info = mbi.as_boot_information();
+        let meminfo = info.basic_memory_info_tag().unwrap();
+        assert_eq!(
+            (meminfo.memory_lower(), meminfo.memory_upper()),
+            (639, 2048)
+        );
+        let framebuffer = info.framebuffer_tag().unwrap().unwrap();
+        assert_eq!(framebuffer.address(), 0x2000);
+        assert_eq!(framebuffer.pitch(), 2);
+        assert_eq!((framebuffer.width(), framebuffer.height()), (800, 600));
+        assert_eq!(framebuffer.buffer_type(), Ok(FramebufferType::Text));
+        let areas = info.memory_map_tag().unwrap().memory_areas();
+        assert_eq!(areas[0].typ(), MemoryAreaType::Available);
+        assert_eq!(areas[1].typ(), MemoryAreaType::Reserved);
+    }
+
+    #[test]
+    fn remove_tags() {
+        let mut buffer = buffer(builder());
+        let mut mbi = BootInformationMut::new(&mut buffer.0).unwrap();
+        let total_size = mbi.total_size();
+        let module_sizes = mbi
+            .as_boot_information()
+            .module_tags()
+            .map(|tag| increase_to_alignment(tag.header().total_size()))
+            .sum::<usize>();
+
+        assert_eq!(mbi.remove_tags(TagType::Module), 2);
+        assert_eq!(mbi.remove_tags(TagType::Module), 0);
+        assert_eq!(mbi.remove_tags(TagType::Custom(0x1337)), 0);
+        assert_eq!(mbi.total_size(), total_size - module_sizes);
+        let total_size = mbi.total_size();
+        assert!(
+            buffer.0[total_size..total_size + module_sizes]
+                .iter()
+                .all(|&b| b == 0)
+        );
+
+        let mbi = BootInformationMut::new(&mut buffer.0).unwrap();
+        let info = mbi.as_boot_information();
+        assert_eq!(info.module_tags().count(), 0);
+        assert_eq!(info.command_line_tag().unwrap().cmdline(), Ok("quiet"));
+        assert_eq!(info.memory_map_tag().unwrap().memory_areas().len(), 2);
+        assert!(info.framebuffer_tag().is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "the end tag can't be removed")]
+    fn remove_end_tag() {
+        let mut buffer = buffer(builder());
+        let mut mbi = BootInformationMut::new(&mut buffer.0).unwrap();
+        mbi.remove_tags(TagType::End);
+    }
+
+    #[test]
+    fn push_tag() {
+        let mut buffer = buffer(builder());
+        let mut mbi = BootInformationMut::new(&mut buffer.0).unwrap();
+
+        mbi.remove_tags(TagType::Cmdline);
+        mbi.push_tag(&*CommandLineTag::new("console=ttyS0"))
+            .unwrap();
+        let custom = new_boxed::<DynSizedStructure<TagHeader>>(
+            TagHeader::new(TagType::Custom(0x1337), 0),
+            &[&[1, 2, 3]],
+        );
+        mbi.push_tag(&*custom).unwrap();
+        assert_eq!(mbi.push_tag(&EndTag::default()), Err(PushTagError::EndTag));
+        let spare_capacity = mbi.spare_capacity();
+        let large = new_boxed::<DynSizedStructure<TagHeader>>(
+            TagHeader::new(TagType::Custom(0x1338), 0),
+            &[&[0; 512]],
+        );
+        assert_eq!(
+            mbi.push_tag(&*large),
+            Err(PushTagError::InsufficientCapacity {
+                required: 520,
+                available: spare_capacity
+            })
+        );
+
+        let total_size = mbi.total_size();
+        let mbi = BootInformationMut::new(&mut buffer.0[..total_size]).unwrap();
+        let info = mbi.as_boot_information();
+        assert_eq!(
+            info.command_line_tag().unwrap().cmdline(),
+            Ok("console=ttyS0")
+        );
+        let types = info
+            .tags()
+            .map(|tag| TagType::from(tag.header().typ))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types[types.len() - 3..],
+            [TagType::Cmdline, TagType::Custom(0x1337), TagType::End]
+        );
+        let custom = info
+            .tags()
+            .find(|tag| tag.header().typ == TagType::Custom(0x1337))
+            .unwrap();
+        assert_eq!(custom.payload(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn get_tag_payload_mut() {
+        let custom = new_boxed::<DynSizedStructure<TagHeader>>(
+            TagHeader::new(TagType::Custom(0x1337), 0),
+            &[&[1, 2, 3]],
+        );
+        let mut buffer = buffer(builder().add_custom_tag(custom));
+        let mut mbi = BootInformationMut::new(&mut buffer.0).unwrap();
+
+        assert_eq!(mbi.get_tag_payload_mut(TagType::Custom(0x1338)), None);
+        // Built-in tags are only accessible via their typed getters.
+        assert_eq!(mbi.get_tag_payload_mut(TagType::Mmap), None);
+        let payload = mbi.get_tag_payload_mut(TagType::Custom(0x1337)).unwrap();
+        assert_eq!(payload, [1, 2, 3]);
+        payload.copy_from_slice(&[4, 5, 6]);
+
+        let info = mbi.as_boot_information();
+        let custom = info
+            .tags()
+            .find(|tag| tag.header().typ == TagType::Custom(0x1337))
+            .unwrap();
+        assert_eq!(custom.payload(), [4, 5, 6]);
+    }
+}
@@ -5,7 +5,7 @@ use crate::{StringError, TagType, parse_slice_as_string};
 use core::fmt::{Debug, Formatter};
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
-use {alloc::boxed::Box, multiboot2_common::new_boxed};
+use {crate::NewTagError, alloc::boxed::Box, multiboot2_common::try_new_boxed};
 
 /// The bootloader name tag.
 #[derive(ptr_meta::Pointee, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -18,16 +18,27 @@ pub struct BootLoaderNameTag {
 
 impl BootLoaderNameTag {
     /// Constructs a new tag.
+    ///
+    /// # Panics
+    /// Panics if [`Self::try_new`] fails.
     #[cfg(feature = "builder")]
     #[must_use]
     pub fn new(name: &str) -> Box<Self> {
+        Self::try_new(name).unwrap()
+    }
+
+    /// Fallible variant of [`Self::new`] that reports a too large name or a
+    /// failed allocation.
+    #[cfg(feature = "builder")]
+    pub fn try_new(name: &str) -> Result<Box<Self>, NewTagError> {
         let header = TagHeader::new(Self::ID, 0);
         let bytes = name.as_bytes();
-        if bytes.ends_with(&[0]) {
-            new_boxed(header, &[bytes])
+        let tag = if bytes.ends_with(&[0]) {
+            try_new_boxed(header, &[bytes])
         } else {
-            new_boxed(header, &[bytes, &[0]])
-        }
+            try_new_boxed(header, &[bytes, &[0]])
+        };
+        Ok(tag?)
     }
 
     /// Returns the underlying [`TagType`].
@@ -91,6 +102,31 @@ impl Tag for BootLoaderNameTag {
     const ID: TagType = TagType::BootLoaderName;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for BootLoaderNameTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+        let mut state = serializer.serialize_struct("BootLoaderNameTag", 1)?;
+        state.serialize_field("name", self.name().map_err(S::Error::custom)?)?;
+        state.end()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "builder"))]
+impl<'de> serde::Deserialize<'de> for Box<BootLoaderNameTag> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        use serde::de::Error;
+        #[derive(Deserialize)]
+        #[serde(rename = "BootLoaderNameTag")]
+        struct Fields {
+            name: alloc::string::String,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        BootLoaderNameTag::try_new(&fields.name).map_err(D::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
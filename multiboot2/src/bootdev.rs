@@ -4,7 +4,7 @@ use crate::{TagHeader, TagType};
 use multiboot2_common::{MaybeDynSized, Tag};
 
 /// Identifies the BIOS device and partition that supplied the OS image.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug)]
 #[repr(C, align(8))]
 pub struct BootdevTag {
     header: TagHeader,
@@ -62,3 +62,31 @@ impl Tag for BootdevTag {
 
     const ID: TagType = TagType::Bootdev;
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for BootdevTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("BootdevTag", 3)?;
+        state.serialize_field("biosdev", &self.biosdev)?;
+        state.serialize_field("slice", &self.slice)?;
+        state.serialize_field("part", &self.part)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BootdevTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "BootdevTag")]
+        struct Fields {
+            biosdev: u32,
+            slice: u32,
+            part: u32,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(fields.biosdev, fields.slice, fields.part))
+    }
+}
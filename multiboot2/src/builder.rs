@@ -4,16 +4,42 @@ use crate::apm::ApmTag;
 use crate::bootdev::BootdevTag;
 use crate::network::NetworkTag;
 use crate::{
-    BasicMemoryInfoTag, BootInformationHeader, BootLoaderNameTag, CommandLineTag,
+    BasicMemoryInfoTag, BootInformation, BootInformationHeader, BootLoaderNameTag, CommandLineTag,
     EFIBootServicesNotExitedTag, EFIImageHandle32Tag, EFIImageHandle64Tag, EFIMemoryMapTag,
     EFISdt32Tag, EFISdt64Tag, ElfSectionsTag, EndTag, FramebufferTag, ImageLoadPhysAddrTag,
-    MemoryMapTag, ModuleTag, RsdpV1Tag, RsdpV2Tag, SmbiosTag, TagHeader, TagType, VBEInfoTag,
+    MemoryMapTag, ModuleTag, RsdpV1Tag, RsdpV2Tag, SmbiosTag, TagHeader, TagType, TagTypeId,
+    VBEInfoTag,
 };
 use alloc::boxed::Box;
 use alloc::vec::Vec;
-use multiboot2_common::{DynSizedStructure, MaybeDynSized, new_boxed};
+use multiboot2_common::{
+    DynSizedStructure, MaybeDynSized, NewBoxedError, clone_dyn, increase_to_alignment,
+    try_new_boxed,
+};
+use thiserror::Error;
+
+/// Errors that may occur when building a boot information with
+/// [`Builder::try_build`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+pub enum BuildError {
+    /// The tag occupies more bytes than its header reports, i.e., it is too
+    /// large for the `u32` size field.
+    #[error("tag {0:?} with 0x{1:x} bytes is too large")]
+    TagTooLarge(TagTypeId, usize),
+    /// The total size of the boot information doesn't fit into `u32`.
+    #[error("total size of the boot information overflows")]
+    TotalSizeOverflow,
+    /// The allocation of the given amount of bytes failed.
+    #[error("failed to allocate 0x{0:x} bytes")]
+    AllocationFailed(usize),
+}
 
 /// Builder for Multiboot2 boot information (MBI).
+///
+/// Bootloaders can check that the built boot information satisfies the
+/// information requests of a kernel's Multiboot2 header with
+/// `MbiBuilderExt::check_against` of the `multiboot2-header` crate, which
+/// requires its `mbi-builder` feature.
 #[derive(Debug)]
 pub struct Builder {
     cmdline: Option<Box<CommandLineTag>>,
@@ -76,6 +102,51 @@ impl Builder {
         }
     }
 
+    /// Creates a new builder that contains clones of all tags of the given
+    /// boot information.
+    ///
+    /// This is useful for chainloaders or kexec-like flows that forward a
+    /// modified boot information to the next kernel. The setters replace
+    /// existing tags, and the `remove_*` methods drop them. Custom tags are
+    /// preserved as if added via [`Self::add_custom_tag`].
+    ///
+    /// For tags that the builder holds at most once, only the first
+    /// occurrence is taken. The EFI memory map is kept even if the
+    /// [`EFIBootServicesNotExitedTag`] is present. The tags of the resulting
+    /// boot information follow the order of [`Self::build`], which may
+    /// differ from the order in `mbi`.
+    #[must_use]
+    pub fn from_boot_information(mbi: &BootInformation) -> Self {
+        Self {
+            cmdline: mbi.command_line_tag().map(clone_dyn),
+            bootloader: mbi.boot_loader_name_tag().map(clone_dyn),
+            modules: mbi.module_tags().map(clone_dyn).collect(),
+            meminfo: mbi.basic_memory_info_tag().copied(),
+            bootdev: mbi.bootdev_tag().copied(),
+            mmap: mbi.memory_map_tag().map(clone_dyn),
+            vbe: mbi.vbe_info_tag().copied(),
+            framebuffer: mbi.get_tag::<FramebufferTag>().map(clone_dyn),
+            elf_sections: mbi.elf_sections_tag().map(clone_dyn),
+            apm: mbi.apm_tag().copied(),
+            efi32: mbi.efi_sdt32_tag().copied(),
+            efi64: mbi.efi_sdt64_tag().copied(),
+            smbios: mbi.smbios_tags().map(clone_dyn).collect(),
+            rsdpv1: mbi.rsdp_v1_tag().copied(),
+            rsdpv2: mbi.rsdp_v2_tag().copied(),
+            network: mbi.network_tags().map(clone_dyn).collect(),
+            efi_mmap: mbi.get_tag::<EFIMemoryMapTag>().map(clone_dyn),
+            efi_bs: mbi.efi_bs_not_exited_tag().copied(),
+            efi32_ih: mbi.efi_ih32_tag().copied(),
+            efi64_ih: mbi.efi_ih64_tag().copied(),
+            image_load_addr: mbi.load_base_addr_tag().copied(),
+            custom_tags: mbi
+                .tags()
+                .filter(|tag| matches!(tag.header().typ.into(), TagType::Custom(_)))
+                .map(clone_dyn)
+                .collect(),
+        }
+    }
+
     /// Sets the [`CommandLineTag`] tag.
     #[must_use]
     pub fn cmdline(mut self, cmdline: Box<CommandLineTag>) -> Self {
@@ -83,6 +154,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`CommandLineTag`] tag.
+    #[must_use]
+    pub fn remove_cmdline(mut self) -> Self {
+        self.cmdline = None;
+        self
+    }
+
     /// Sets the [`BootLoaderNameTag`] tag.
     #[must_use]
     pub fn bootloader(mut self, bootloader: Box<BootLoaderNameTag>) -> Self {
@@ -90,6 +168,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`BootLoaderNameTag`] tag.
+    #[must_use]
+    pub fn remove_bootloader(mut self) -> Self {
+        self.bootloader = None;
+        self
+    }
+
     /// Adds a [`ModuleTag`] tag.
     #[must_use]
     pub fn add_module(mut self, module: Box<ModuleTag>) -> Self {
@@ -97,6 +182,13 @@ impl Builder {
         self
     }
 
+    /// Removes all [`ModuleTag`] tags.
+    #[must_use]
+    pub fn remove_modules(mut self) -> Self {
+        self.modules.clear();
+        self
+    }
+
     /// Sets the [`BasicMemoryInfoTag`] tag.
     #[must_use]
     pub const fn meminfo(mut self, meminfo: BasicMemoryInfoTag) -> Self {
@@ -104,6 +196,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`BasicMemoryInfoTag`] tag.
+    #[must_use]
+    pub const fn remove_meminfo(mut self) -> Self {
+        self.meminfo = None;
+        self
+    }
+
     /// Sets the [`BootdevTag`] tag.
     #[must_use]
     pub const fn bootdev(mut self, bootdev: BootdevTag) -> Self {
@@ -111,6 +210,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`BootdevTag`] tag.
+    #[must_use]
+    pub const fn remove_bootdev(mut self) -> Self {
+        self.bootdev = None;
+        self
+    }
+
     /// Sets the [`MemoryMapTag`] tag.
     #[must_use]
     pub fn mmap(mut self, mmap: Box<MemoryMapTag>) -> Self {
@@ -118,6 +224,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`MemoryMapTag`] tag.
+    #[must_use]
+    pub fn remove_mmap(mut self) -> Self {
+        self.mmap = None;
+        self
+    }
+
     /// Sets the [`VBEInfoTag`] tag.
     #[must_use]
     pub const fn vbe(mut self, vbe: VBEInfoTag) -> Self {
@@ -125,6 +238,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`VBEInfoTag`] tag.
+    #[must_use]
+    pub const fn remove_vbe(mut self) -> Self {
+        self.vbe = None;
+        self
+    }
+
     /// Sets the [`FramebufferTag`] tag.
     #[must_use]
     pub fn framebuffer(mut self, framebuffer: Box<FramebufferTag>) -> Self {
@@ -132,6 +252,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`FramebufferTag`] tag.
+    #[must_use]
+    pub fn remove_framebuffer(mut self) -> Self {
+        self.framebuffer = None;
+        self
+    }
+
     /// Sets the [`ElfSectionsTag`] tag.
     #[must_use]
     pub fn elf_sections(mut self, elf_sections: Box<ElfSectionsTag>) -> Self {
@@ -139,6 +266,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`ElfSectionsTag`] tag.
+    #[must_use]
+    pub fn remove_elf_sections(mut self) -> Self {
+        self.elf_sections = None;
+        self
+    }
+
     /// Sets the [`ApmTag`] tag.
     #[must_use]
     pub const fn apm(mut self, apm: ApmTag) -> Self {
@@ -146,6 +280,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`ApmTag`] tag.
+    #[must_use]
+    pub const fn remove_apm(mut self) -> Self {
+        self.apm = None;
+        self
+    }
+
     /// Sets the [`EFISdt32Tag`] tag.
     #[must_use]
     pub const fn efi32(mut self, efi32: EFISdt32Tag) -> Self {
@@ -153,6 +294,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`EFISdt32Tag`] tag.
+    #[must_use]
+    pub const fn remove_efi32(mut self) -> Self {
+        self.efi32 = None;
+        self
+    }
+
     /// Sets the [`EFISdt64Tag`] tag.
     #[must_use]
     pub const fn efi64(mut self, efi64: EFISdt64Tag) -> Self {
@@ -160,6 +308,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`EFISdt64Tag`] tag.
+    #[must_use]
+    pub const fn remove_efi64(mut self) -> Self {
+        self.efi64 = None;
+        self
+    }
+
     /// Adds a [`SmbiosTag`] tag.
     #[must_use]
     pub fn add_smbios(mut self, smbios: Box<SmbiosTag>) -> Self {
@@ -167,6 +322,13 @@ impl Builder {
         self
     }
 
+    /// Removes all [`SmbiosTag`] tags.
+    #[must_use]
+    pub fn remove_smbios(mut self) -> Self {
+        self.smbios.clear();
+        self
+    }
+
     /// Sets the [`RsdpV1Tag`] tag.
     #[must_use]
     pub const fn rsdpv1(mut self, rsdpv1: RsdpV1Tag) -> Self {
@@ -174,6 +336,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`RsdpV1Tag`] tag.
+    #[must_use]
+    pub const fn remove_rsdpv1(mut self) -> Self {
+        self.rsdpv1 = None;
+        self
+    }
+
     /// Sets the [`RsdpV2Tag`] tag.
     #[must_use]
     pub const fn rsdpv2(mut self, rsdpv2: RsdpV2Tag) -> Self {
@@ -181,6 +350,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`RsdpV2Tag`] tag.
+    #[must_use]
+    pub const fn remove_rsdpv2(mut self) -> Self {
+        self.rsdpv2 = None;
+        self
+    }
+
     /// Sets the [`EFIMemoryMapTag`] tag.
     #[must_use]
     pub fn efi_mmap(mut self, efi_mmap: Box<EFIMemoryMapTag>) -> Self {
@@ -188,6 +364,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`EFIMemoryMapTag`] tag.
+    #[must_use]
+    pub fn remove_efi_mmap(mut self) -> Self {
+        self.efi_mmap = None;
+        self
+    }
+
     /// Sets the sole [`NetworkTag`] tag.
     ///
     /// This replaces any network tags previously added with
@@ -208,6 +391,13 @@ impl Builder {
         self
     }
 
+    /// Removes all [`NetworkTag`] tags.
+    #[must_use]
+    pub fn remove_network(mut self) -> Self {
+        self.network.clear();
+        self
+    }
+
     /// Sets the [`EFIBootServicesNotExitedTag`] tag.
     #[must_use]
     pub const fn efi_bs(mut self, efi_bs: EFIBootServicesNotExitedTag) -> Self {
@@ -215,6 +405,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`EFIBootServicesNotExitedTag`] tag.
+    #[must_use]
+    pub const fn remove_efi_bs(mut self) -> Self {
+        self.efi_bs = None;
+        self
+    }
+
     /// Sets the [`EFIImageHandle32Tag`] tag.
     #[must_use]
     pub const fn efi32_ih(mut self, efi32_ih: EFIImageHandle32Tag) -> Self {
@@ -222,6 +419,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`EFIImageHandle32Tag`] tag.
+    #[must_use]
+    pub const fn remove_efi32_ih(mut self) -> Self {
+        self.efi32_ih = None;
+        self
+    }
+
     /// Sets the [`EFIImageHandle64Tag`] tag.
     #[must_use]
     pub const fn efi64_ih(mut self, efi64_ih: EFIImageHandle64Tag) -> Self {
@@ -229,6 +433,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`EFIImageHandle64Tag`] tag.
+    #[must_use]
+    pub const fn remove_efi64_ih(mut self) -> Self {
+        self.efi64_ih = None;
+        self
+    }
+
     /// Sets the [`ImageLoadPhysAddrTag`] tag.
     #[must_use]
     pub const fn image_load_addr(mut self, image_load_addr: ImageLoadPhysAddrTag) -> Self {
@@ -236,6 +447,13 @@ impl Builder {
         self
     }
 
+    /// Removes the [`ImageLoadPhysAddrTag`] tag.
+    #[must_use]
+    pub const fn remove_image_load_addr(mut self) -> Self {
+        self.image_load_addr = None;
+        self
+    }
+
     /// Adds a custom tag.
     #[must_use]
     pub fn add_custom_tag(mut self, custom_tag: Box<DynSizedStructure<TagHeader>>) -> Self {
@@ -247,81 +465,295 @@ impl Builder {
         self
     }
 
+    /// Removes all custom tags of the given type.
+    #[must_use]
+    pub fn remove_custom_tags(mut self, typ: impl Into<TagTypeId>) -> Self {
+        let typ = typ.into();
+        self.custom_tags.retain(|tag| tag.header().typ != typ);
+        self
+    }
+
+    /// Replaces all custom tags of the same type with the given custom tag.
+    #[must_use]
+    pub fn replace_custom_tag(self, custom_tag: Box<DynSizedStructure<TagHeader>>) -> Self {
+        self.remove_custom_tags(custom_tag.header().typ)
+            .add_custom_tag(custom_tag)
+    }
+
+    /// Returns whether the built boot information will contain at least one
+    /// tag of the given type. The end tag is always present.
+    #[must_use]
+    pub fn has_tag(&self, typ: impl Into<TagTypeId>) -> bool {
+        let typ = typ.into();
+        match TagType::from(typ) {
+            TagType::End => true,
+            TagType::Cmdline => self.cmdline.is_some(),
+            TagType::BootLoaderName => self.bootloader.is_some(),
+            TagType::Module => !self.modules.is_empty(),
+            TagType::BasicMeminfo => self.meminfo.is_some(),
+            TagType::Bootdev => self.bootdev.is_some(),
+            TagType::Mmap => self.mmap.is_some(),
+            TagType::Vbe => self.vbe.is_some(),
+            TagType::Framebuffer => self.framebuffer.is_some(),
+            TagType::ElfSections => self.elf_sections.is_some(),
+            TagType::Apm => self.apm.is_some(),
+            TagType::Efi32 => self.efi32.is_some(),
+            TagType::Efi64 => self.efi64.is_some(),
+            TagType::Smbios => !self.smbios.is_empty(),
+            TagType::AcpiV1 => self.rsdpv1.is_some(),
+            TagType::AcpiV2 => self.rsdpv2.is_some(),
+            TagType::Network => !self.network.is_empty(),
+            TagType::EfiMmap => self.efi_mmap.is_some(),
+            TagType::EfiBs => self.efi_bs.is_some(),
+            TagType::Efi32Ih => self.efi32_ih.is_some(),
+            TagType::Efi64Ih => self.efi64_ih.is_some(),
+            TagType::LoadBaseAddr => self.image_load_addr.is_some(),
+            TagType::Custom(_) => self.custom_tags.iter().any(|tag| tag.header().typ == typ),
+        }
+    }
+
     /// Returns properly aligned bytes on the heap representing a valid
     /// Multiboot2 boot information structure.
+    ///
+    /// # Panics
+    /// Panics if [`Self::try_build`] fails.
     #[must_use]
     pub fn build(self) -> Box<DynSizedStructure<BootInformationHeader>> {
+        self.try_build().unwrap()
+    }
+
+    /// Fallible variant of [`Self::build`].
+    ///
+    /// Instead of panicking or aborting, this reports tags that are larger
+    /// than their header can describe, a total size that doesn't fit into
+    /// the `u32` of the [`BootInformationHeader`], and failed allocations.
+    pub fn try_build(self) -> Result<Box<DynSizedStructure<BootInformationHeader>>, BuildError> {
         let header = BootInformationHeader::new(0);
         let mut byte_refs = Vec::new();
         if let Some(tag) = self.cmdline.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.bootloader.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         for i in &self.modules {
-            byte_refs.push(i.as_bytes().as_ref());
+            try_push(&mut byte_refs, i.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.meminfo.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.bootdev.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.mmap.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.vbe.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.framebuffer.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.elf_sections.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.apm.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.efi32.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.efi64.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         for i in &self.smbios {
-            byte_refs.push(i.as_bytes().as_ref());
+            try_push(&mut byte_refs, i.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.rsdpv1.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.rsdpv2.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         for tag in &self.network {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.efi_mmap.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.efi_bs.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.efi32_ih.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.efi64_ih.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         if let Some(tag) = self.image_load_addr.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            try_push(&mut byte_refs, tag.as_bytes().as_ref())?;
         }
         for i in &self.custom_tags {
-            byte_refs.push(i.as_bytes().as_ref());
+            try_push(&mut byte_refs, i.as_bytes().as_ref())?;
         }
         let end_tag = EndTag::default();
-        byte_refs.push(end_tag.as_bytes().as_ref());
-        new_boxed(header, byte_refs.as_slice())
+        try_push(&mut byte_refs, end_tag.as_bytes().as_ref())?;
+
+        let mut total_size = size_of::<BootInformationHeader>();
+        for bytes in &byte_refs {
+            let typ = u32::from_ne_bytes(bytes[0..4].try_into().unwrap());
+            let size = u32::from_ne_bytes(bytes[4..8].try_into().unwrap()) as usize;
+            if increase_to_alignment(size) != bytes.len() {
+                return Err(BuildError::TagTooLarge(typ.into(), bytes.len()));
+            }
+            total_size = total_size
+                .checked_add(bytes.len())
+                .ok_or(BuildError::TotalSizeOverflow)?;
+        }
+        if u32::try_from(total_size).is_err() {
+            return Err(BuildError::TotalSizeOverflow);
+        }
+
+        try_new_boxed(header, byte_refs.as_slice()).map_err(|e| match e {
+            NewBoxedError::SizeOverflow => BuildError::TotalSizeOverflow,
+            NewBoxedError::AllocationFailed(size) => BuildError::AllocationFailed(size),
+        })
+    }
+}
+
+/// Pushes `bytes` to `byte_refs`, reporting a failed allocation instead of
+/// aborting.
+fn try_push<'a>(byte_refs: &mut Vec<&'a [u8]>, bytes: &'a [u8]) -> Result<(), BuildError> {
+    byte_refs
+        .try_reserve(1)
+        .map_err(|_| BuildError::AllocationFailed((byte_refs.len() + 1) * size_of::<&[u8]>()))?;
+    byte_refs.push(bytes);
+    Ok(())
+}
+
+/// Owned counterpart of the serialized tags of a [`BootInformation`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum DeserializedTag {
+    Cmdline(Box<CommandLineTag>),
+    BootLoaderName(Box<BootLoaderNameTag>),
+    Module(Box<ModuleTag>),
+    BasicMeminfo(BasicMemoryInfoTag),
+    Bootdev(BootdevTag),
+    Mmap(Box<MemoryMapTag>),
+    Vbe(Box<VBEInfoTag>),
+    Framebuffer(Box<FramebufferTag>),
+    ElfSections(Box<ElfSectionsTag>),
+    Apm(ApmTag),
+    Efi32(EFISdt32Tag),
+    Efi64(EFISdt64Tag),
+    Smbios(Box<SmbiosTag>),
+    AcpiV1(RsdpV1Tag),
+    AcpiV2(RsdpV2Tag),
+    Network(Box<NetworkTag>),
+    EfiMmap(Box<EFIMemoryMapTag>),
+    EfiBs(EFIBootServicesNotExitedTag),
+    Efi32Ih(EFIImageHandle32Tag),
+    Efi64Ih(EFIImageHandle64Tag),
+    LoadBaseAddr(ImageLoadPhysAddrTag),
+    Custom { id: u32, payload: Vec<u8> },
+}
+
+#[cfg(feature = "serde")]
+impl DeserializedTag {
+    /// Returns the type of the tag.
+    fn typ(&self) -> TagType {
+        match self {
+            Self::Cmdline(_) => TagType::Cmdline,
+            Self::BootLoaderName(_) => TagType::BootLoaderName,
+            Self::Module(_) => TagType::Module,
+            Self::BasicMeminfo(_) => TagType::BasicMeminfo,
+            Self::Bootdev(_) => TagType::Bootdev,
+            Self::Mmap(_) => TagType::Mmap,
+            Self::Vbe(_) => TagType::Vbe,
+            Self::Framebuffer(_) => TagType::Framebuffer,
+            Self::ElfSections(_) => TagType::ElfSections,
+            Self::Apm(_) => TagType::Apm,
+            Self::Efi32(_) => TagType::Efi32,
+            Self::Efi64(_) => TagType::Efi64,
+            Self::Smbios(_) => TagType::Smbios,
+            Self::AcpiV1(_) => TagType::AcpiV1,
+            Self::AcpiV2(_) => TagType::AcpiV2,
+            Self::Network(_) => TagType::Network,
+            Self::EfiMmap(_) => TagType::EfiMmap,
+            Self::EfiBs(_) => TagType::EfiBs,
+            Self::Efi32Ih(_) => TagType::Efi32Ih,
+            Self::Efi64Ih(_) => TagType::Efi64Ih,
+            Self::LoadBaseAddr(_) => TagType::LoadBaseAddr,
+            Self::Custom { id, .. } => TagType::from(*id),
+        }
+    }
+}
+
+/// Deserializes a builder from the format of the [`serde::Serialize`]
+/// implementation of [`BootInformation`].
+///
+/// Tags of which the builder holds at most one must occur at most once.
+/// Custom tags must have an ID of a [`TagType::Custom`].
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Builder {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        use serde::de::Error;
+
+        #[derive(Deserialize)]
+        #[serde(rename = "BootInformation")]
+        struct Fields {
+            tags: Vec<DeserializedTag>,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let mut builder = Self::new();
+        for tag in fields.tags {
+            let typ = tag.typ();
+            match typ {
+                TagType::Module | TagType::Smbios | TagType::Network | TagType::Custom(_) => {}
+                _ if builder.has_tag(typ) => {
+                    return Err(D::Error::custom(format_args!("duplicate {typ:?} tag")));
+                }
+                _ => {}
+            }
+            builder = match tag {
+                DeserializedTag::Cmdline(tag) => builder.cmdline(tag),
+                DeserializedTag::BootLoaderName(tag) => builder.bootloader(tag),
+                DeserializedTag::Module(tag) => builder.add_module(tag),
+                DeserializedTag::BasicMeminfo(tag) => builder.meminfo(tag),
+                DeserializedTag::Bootdev(tag) => builder.bootdev(tag),
+                DeserializedTag::Mmap(tag) => builder.mmap(tag),
+                DeserializedTag::Vbe(tag) => builder.vbe(*tag),
+                DeserializedTag::Framebuffer(tag) => builder.framebuffer(tag),
+                DeserializedTag::ElfSections(tag) => builder.elf_sections(tag),
+                DeserializedTag::Apm(tag) => builder.apm(tag),
+                DeserializedTag::Efi32(tag) => builder.efi32(tag),
+                DeserializedTag::Efi64(tag) => builder.efi64(tag),
+                DeserializedTag::Smbios(tag) => builder.add_smbios(tag),
+                DeserializedTag::AcpiV1(tag) => builder.rsdpv1(tag),
+                DeserializedTag::AcpiV2(tag) => builder.rsdpv2(tag),
+                DeserializedTag::Network(tag) => builder.add_network(tag),
+                DeserializedTag::EfiMmap(tag) => builder.efi_mmap(tag),
+                DeserializedTag::EfiBs(tag) => builder.efi_bs(tag),
+                DeserializedTag::Efi32Ih(tag) => builder.efi32_ih(tag),
+                DeserializedTag::Efi64Ih(tag) => builder.efi64_ih(tag),
+                DeserializedTag::LoadBaseAddr(tag) => builder.image_load_addr(tag),
+                DeserializedTag::Custom { id, payload } => {
+                    if !matches!(typ, TagType::Custom(_)) {
+                        return Err(D::Error::custom(format_args!(
+                            "custom tag ID {id} is a standard tag type"
+                        )));
+                    }
+                    let tag = try_new_boxed(TagHeader::new(typ, 0), &[&payload])
+                        .map_err(D::Error::custom)?;
+                    builder.add_custom_tag(tag)
+                }
+            };
+        }
+        Ok(builder)
     }
 }
 
@@ -331,6 +763,7 @@ mod tests {
     use crate::{
         BootInformation, FramebufferType, MemoryArea, MemoryAreaType, VBEControlInfo, VBEModeInfo,
     };
+    use multiboot2_common::new_boxed;
     use uefi_raw::table::boot::MemoryDescriptor;
 
     #[test]
@@ -409,6 +842,125 @@ mod tests {
         eprintln!("{debug}")
     }
 
+    #[test]
+    fn try_build_matches_build() {
+        let builder = || {
+            Builder::new()
+                .cmdline(CommandLineTag::new("console=ttyS0"))
+                .add_module(ModuleTag::new(0x1000, 0x2000, "module 1"))
+                .meminfo(BasicMemoryInfoTag::new(0x4000, 0x5000))
+                .mmap(MemoryMapTag::try_new(&[]).unwrap())
+                .efi_mmap(EFIMemoryMapTag::try_new_from_map(48, 1, &[0; 48]).unwrap())
+        };
+        let structure = builder().try_build().unwrap();
+        assert_eq!(
+            structure.as_bytes().as_ref(),
+            builder().build().as_bytes().as_ref()
+        );
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+        assert_eq!(info.total_size(), structure.header().total_size() as usize);
+    }
+
+    #[test]
+    fn from_boot_information_roundtrip() {
+        let structure = Builder::new()
+            .cmdline(CommandLineTag::new("console=ttyS0"))
+            .bootloader(BootLoaderNameTag::new("chainloader"))
+            .add_module(ModuleTag::new(0x1000, 0x2000, "module 1"))
+            .meminfo(BasicMemoryInfoTag::new(0x4000, 0x5000))
+            .bootdev(BootdevTag::new(0x80, 0, 0))
+            .mmap(MemoryMapTag::new(&[MemoryArea::new(
+                0x1000000,
+                0x1000,
+                MemoryAreaType::Available,
+            )]))
+            .framebuffer(FramebufferTag::new(
+                0x1000,
+                1,
+                756,
+                1024,
+                8,
+                FramebufferType::Text,
+            ))
+            .apm(ApmTag::new(1, 2, 3, 4, 5, 6, 7, 8, 9))
+            .add_smbios(SmbiosTag::new(3, 0, &[1, 2, 3]))
+            .rsdpv1(RsdpV1Tag::new(*b"abcdef", 5, 6))
+            .add_network(NetworkTag::new(&[1, 2, 3]))
+            .efi_mmap(EFIMemoryMapTag::new_from_descs(&[
+                MemoryDescriptor::default(),
+            ]))
+            .efi_bs(EFIBootServicesNotExitedTag::new())
+            .image_load_addr(ImageLoadPhysAddrTag::new(0x1000))
+            .add_custom_tag(new_boxed::<DynSizedStructure<TagHeader>>(
+                TagHeader::new(TagType::Custom(0x1337), 0),
+                &[&[1, 2, 3]],
+            ))
+            .build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+
+        let rebuilt = Builder::from_boot_information(&info).build();
+        assert_eq!(rebuilt.as_bytes().as_ref(), structure.as_bytes().as_ref());
+    }
+
+    #[test]
+    fn from_boot_information_modify() {
+        let structure = Builder::new()
+            .cmdline(CommandLineTag::new("old"))
+            .add_module(ModuleTag::new(0x1000, 0x2000, "module 1"))
+            .apm(ApmTag::new(1, 2, 3, 4, 5, 6, 7, 8, 9))
+            .add_custom_tag(new_boxed::<DynSizedStructure<TagHeader>>(
+                TagHeader::new(TagType::Custom(0x1337), 0),
+                &[&[1]],
+            ))
+            .add_custom_tag(new_boxed::<DynSizedStructure<TagHeader>>(
+                TagHeader::new(TagType::Custom(0x1338), 0),
+                &[&[2]],
+            ))
+            .build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+
+        let structure = Builder::from_boot_information(&info)
+            .cmdline(CommandLineTag::new("new"))
+            .add_module(ModuleTag::new(0x3000, 0x4000, "module 2"))
+            .remove_apm()
+            .replace_custom_tag(new_boxed::<DynSizedStructure<TagHeader>>(
+                TagHeader::new(TagType::Custom(0x1337), 0),
+                &[&[3, 4]],
+            ))
+            .remove_custom_tags(TagType::Custom(0x1338));
+        assert!(structure.has_tag(TagType::Cmdline));
+        assert!(structure.has_tag(TagType::Module));
+        assert!(!structure.has_tag(TagType::Apm));
+        assert!(structure.has_tag(TagType::Custom(0x1337)));
+        assert!(!structure.has_tag(TagType::Custom(0x1338)));
+        let structure = structure.build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+
+        assert_eq!(info.command_line_tag().unwrap().cmdline(), Ok("new"));
+        assert_eq!(
+            info.module_tags()
+                .map(|tag| tag.cmdline().unwrap())
+                .collect::<Vec<_>>(),
+            ["module 1", "module 2"]
+        );
+        assert!(info.apm_tag().is_none());
+        let custom_tags = info
+            .tags()
+            .filter(|tag| matches!(tag.header().typ.into(), TagType::Custom(_)))
+            .collect::<Vec<_>>();
+        assert_eq!(custom_tags.len(), 1);
+        assert_eq!(custom_tags[0].header().typ, TagType::Custom(0x1337));
+        assert_eq!(custom_tags[0].payload()[..2], [3, 4]);
+    }
+
     #[test]
     fn build_multiple_network_tags_and_reset_them() {
         let structure = Builder::new()
@@ -443,4 +995,204 @@ mod tests {
             [11]
         );
     }
+
+    #[cfg(feature = "serde")]
+    /// Serializes the built boot information, deserializes it as builder, and
+    /// checks that the result serializes the same. Returns the JSON.
+    fn serde_roundtrip(builder: Builder) -> std::string::String {
+        let structure = builder.build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+        let json = serde_json::to_string(&info).unwrap();
+        let structure = serde_json::from_str::<Builder>(&json).unwrap().build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let roundtrip =
+            unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+        assert_eq!(serde_json::to_string(&roundtrip).unwrap(), json);
+        json
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_roundtrip_all_tags() {
+        let section = [
+            &1_u32.to_ne_bytes()[..],
+            &elf::abi::SHT_PROGBITS.to_ne_bytes(),
+            &2_u64.to_ne_bytes(),
+            &0x1000_u64.to_ne_bytes(),
+            &0_u64.to_ne_bytes(),
+            &0x100_u64.to_ne_bytes(),
+            &0_u32.to_ne_bytes(),
+            &0_u32.to_ne_bytes(),
+            &16_u64.to_ne_bytes(),
+            &0_u64.to_ne_bytes(),
+        ]
+        .concat();
+        let builder = Builder::new()
+            .cmdline(CommandLineTag::new("this is a command line"))
+            .bootloader(BootLoaderNameTag::new("this is the bootloader"))
+            .add_module(ModuleTag::new(0x1000, 0x2000, "module 1"))
+            .add_module(ModuleTag::new(0x3000, 0x4000, "module 2"))
+            .meminfo(BasicMemoryInfoTag::new(0x4000, 0x5000))
+            .bootdev(BootdevTag::new(0x80, 0x01, 0x02))
+            .mmap(MemoryMapTag::new(&[MemoryArea::new(
+                0x1000000,
+                0x1000,
+                MemoryAreaType::Available,
+            )]))
+            .vbe(VBEInfoTag::new(
+                42,
+                2,
+                4,
+                9,
+                VBEControlInfo::default(),
+                VBEModeInfo::default(),
+            ))
+            .framebuffer(FramebufferTag::new(
+                0x1000,
+                1,
+                756,
+                1024,
+                8,
+                FramebufferType::Indexed {
+                    palette: &[crate::FramebufferColor {
+                        red: 1,
+                        green: 2,
+                        blue: 3,
+                    }],
+                },
+            ))
+            .elf_sections(ElfSectionsTag::new(1, 64, 0, &section))
+            .apm(ApmTag::new(1, 2, 3, 4, 5, 6, 7, 8, 9))
+            .efi32(EFISdt32Tag::new(0x1000))
+            .efi64(EFISdt64Tag::new(0x1000))
+            .add_smbios(SmbiosTag::new(0, 0, &[1, 2, 3]))
+            .rsdpv1(RsdpV1Tag::new(*b"abcdef", 5, 6))
+            .rsdpv2(RsdpV2Tag::new(*b"abcdef", 5, 6, 5, 4))
+            .efi_mmap(EFIMemoryMapTag::new_from_descs(&[
+                MemoryDescriptor::default(),
+                MemoryDescriptor::default(),
+            ]))
+            .add_network(NetworkTag::new(&[1, 2, 3]))
+            .efi_bs(EFIBootServicesNotExitedTag::new())
+            .efi32_ih(EFIImageHandle32Tag::new(0x1000))
+            .efi64_ih(EFIImageHandle64Tag::new(0x1000))
+            .image_load_addr(ImageLoadPhysAddrTag::new(0x1000))
+            .add_custom_tag(new_boxed::<DynSizedStructure<TagHeader>>(
+                TagHeader::new(TagType::Custom(0x1337), 0),
+                &[&[1, 2]],
+            ));
+
+        let json = serde_roundtrip(builder);
+        assert!(
+            json.starts_with(r#"{"tags":[{"type":"cmdline","cmdline":"this is a command line"}"#)
+        );
+        assert!(json.contains(r#"{"type":"custom","id":4919,"payload":[1,2]}"#));
+        assert!(!json.contains(r#""type":"end""#));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_rgb_framebuffer() {
+        let field = crate::FramebufferField {
+            position: 0,
+            size: 8,
+        };
+        let builder = Builder::new().framebuffer(FramebufferTag::new(
+            0x1000,
+            4,
+            640,
+            480,
+            32,
+            FramebufferType::RGB {
+                red: field,
+                green: field,
+                blue: field,
+            },
+        ));
+        let json = serde_roundtrip(builder);
+        assert!(json.contains(r#""buffer_type":{"type":"rgb","red":"#));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_errors() {
+        let duplicate = r#"{"tags":[
+            {"type":"cmdline","cmdline":"a"},
+            {"type":"cmdline","cmdline":"b"}
+        ]}"#;
+        let error = serde_json::from_str::<Builder>(duplicate).unwrap_err();
+        assert!(std::format!("{error}").contains("duplicate Cmdline tag"));
+
+        let standard_custom = r#"{"tags":[{"type":"custom","id":1,"payload":[]}]}"#;
+        assert!(serde_json::from_str::<Builder>(standard_custom).is_err());
+
+        let elf_sections =
+            r#"{"tags":[{"type":"elf_sections","entry_size":42,"shndx":0,"sections":[]}]}"#;
+        assert!(serde_json::from_str::<Builder>(elf_sections).is_err());
+
+        let module = r#"{"tags":[{"type":"module","start":2,"end":1,"cmdline":""}]}"#;
+        assert!(serde_json::from_str::<Builder>(module).is_err());
+
+        let modules = r#"{"tags":[
+            {"type":"module","start":1,"end":2,"cmdline":"a"},
+            {"type":"module","start":3,"end":4,"cmdline":"b"}
+        ]}"#;
+        let builder = serde_json::from_str::<Builder>(modules).unwrap();
+        assert_eq!(builder.modules.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    fn derive_custom_tags() {
+        use crate::{MaybeDynSized, Multiboot2Tag};
+
+        #[derive(Debug, PartialEq, Eq, Multiboot2Tag)]
+        #[tag(id = 0x1337, crate = "crate")]
+        #[repr(C, align(8))]
+        struct SizedTag {
+            header: TagHeader,
+            a: u32,
+            b: [u16; 2],
+            c: u64,
+        }
+
+        #[derive(Multiboot2Tag)]
+        #[tag(id = 0x1338, crate = "crate")]
+        #[repr(C, align(8))]
+        struct DstTag {
+            header: TagHeader,
+            version: u32,
+            values: [u32],
+        }
+
+        assert_eq!(SizedTag::BASE_SIZE, 8 + 4 + 4 + 8);
+        assert_eq!(DstTag::BASE_SIZE, 8 + 4);
+
+        let sized = SizedTag::new(1, [2, 3], 4);
+        assert_eq!(sized.header.typ, TagType::Custom(0x1337));
+        assert_eq!(sized.header.size, 24);
+        let dst = DstTag::new(7, &[1, 2, 3]);
+        assert_eq!(dst.header.typ, TagType::Custom(0x1338));
+        assert_eq!(dst.header.size, 24);
+
+        let boxed = |bytes: &[u8]| {
+            multiboot2_common::clone_dyn(
+                DynSizedStructure::<TagHeader>::ref_from_slice(bytes).unwrap(),
+            )
+        };
+        let structure = Builder::new()
+            .add_custom_tag(boxed(&sized.as_bytes()))
+            .add_custom_tag(boxed(&dst.as_bytes()))
+            .build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+        assert_eq!(info.get_tag::<SizedTag>(), Some(&sized));
+        let tag = info.get_tag::<DstTag>().unwrap();
+        assert_eq!(tag.version, 7);
+        assert_eq!(&tag.values, [1, 2, 3]);
+    }
 }
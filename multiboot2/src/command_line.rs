@@ -6,7 +6,7 @@ use core::fmt::{Debug, Formatter};
 use core::str;
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
-use {alloc::boxed::Box, multiboot2_common::new_boxed};
+use {crate::NewTagError, alloc::boxed::Box, multiboot2_common::try_new_boxed};
 
 /// This tag contains the command line string.
 ///
@@ -22,16 +22,27 @@ pub struct CommandLineTag {
 
 impl CommandLineTag {
     /// Create a new command line tag from the given string.
+    ///
+    /// # Panics
+    /// Panics if [`Self::try_new`] fails.
     #[cfg(feature = "builder")]
     #[must_use]
     pub fn new(command_line: &str) -> Box<Self> {
+        Self::try_new(command_line).unwrap()
+    }
+
+    /// Fallible variant of [`Self::new`] that reports a too large command
+    /// line or a failed allocation.
+    #[cfg(feature = "builder")]
+    pub fn try_new(command_line: &str) -> Result<Box<Self>, NewTagError> {
         let header = TagHeader::new(Self::ID, 0);
         let bytes = command_line.as_bytes();
-        if bytes.ends_with(&[0]) {
-            new_boxed(header, &[bytes])
+        let tag = if bytes.ends_with(&[0]) {
+            try_new_boxed(header, &[bytes])
         } else {
-            new_boxed(header, &[bytes, &[0]])
-        }
+            try_new_boxed(header, &[bytes, &[0]])
+        };
+        Ok(tag?)
     }
 
     /// Reads the command line of the kernel as Rust string slice without
@@ -85,6 +96,31 @@ impl Tag for CommandLineTag {
     const ID: TagType = TagType::Cmdline;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for CommandLineTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+        let mut state = serializer.serialize_struct("CommandLineTag", 1)?;
+        state.serialize_field("cmdline", self.cmdline().map_err(S::Error::custom)?)?;
+        state.end()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "builder"))]
+impl<'de> serde::Deserialize<'de> for Box<CommandLineTag> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        use serde::de::Error;
+        #[derive(Deserialize)]
+        #[serde(rename = "CommandLineTag")]
+        struct Fields {
+            cmdline: alloc::string::String,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        CommandLineTag::try_new(&fields.cmdline).map_err(D::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
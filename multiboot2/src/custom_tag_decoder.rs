@@ -0,0 +1,170 @@
+//! Module for [`CustomTagDecoder`].
+
+use crate::{TagHeader, TagType, TagTypeId};
+use core::fmt;
+use multiboot2_common::{DynSizedStructure, Tag};
+
+/// Function that formats a custom tag, e.g., by casting it to its specific
+/// type. See [`CustomTagDecoder`].
+pub type CustomTagFormatter =
+    fn(&DynSizedStructure<TagHeader>, &mut fmt::Formatter<'_>) -> fmt::Result;
+
+/// Decoder for custom tags of a specific type, such as vendor tags.
+///
+/// By default, the [`Debug`] output of a [`BootInformation`] only shows the
+/// headers of custom tags. Pass a set of decoders to
+/// [`BootInformation::debug_with`] to fully show them instead. The first
+/// decoder registered for the type of a custom tag is used.
+///
+/// # Example
+///
+/// ```no_run
+/// use multiboot2::{BootInformation, BootInformationHeader, CustomTagDecoder, TagTypeId};
+///
+/// let mbi_ptr = 0xdeadbeef as *const BootInformationHeader;
+/// let mbi = unsafe { BootInformation::load(mbi_ptr).unwrap() };
+/// let decoders = [CustomTagDecoder::new(TagTypeId::new(0x1337), |tag, f| {
+///     write!(f, "VendorTag({:x?})", tag.payload())
+/// })];
+/// println!("{:#?}", mbi.debug_with(&decoders));
+/// ```
+///
+/// [`BootInformation`]: crate::BootInformation
+/// [`BootInformation::debug_with`]: crate::BootInformation::debug_with
+#[derive(Copy, Clone)]
+pub struct CustomTagDecoder {
+    typ: TagTypeId,
+    fmt: CustomTagFormatter,
+}
+
+impl CustomTagDecoder {
+    /// Creates a decoder that formats tags of type `typ` with `fmt`.
+    #[must_use]
+    pub const fn new(typ: TagTypeId, fmt: CustomTagFormatter) -> Self {
+        Self { typ, fmt }
+    }
+
+    /// Creates a decoder that formats tags of type `T::ID` with the [`Debug`]
+    /// implementation of `T`.
+    ///
+    /// # Panics
+    /// Formatting panics if a tag of that type can't be cast to `T`, just
+    /// like [`BootInformation::get_tag`].
+    ///
+    /// [`BootInformation::get_tag`]: crate::BootInformation::get_tag
+    #[must_use]
+    pub fn for_tag<T>() -> Self
+    where
+        T: Tag<IDType = TagType, Header = TagHeader> + fmt::Debug + ?Sized,
+        T::Metadata: Default,
+    {
+        Self::new(T::ID.into(), |tag, f| fmt::Debug::fmt(tag.cast::<T>(), f))
+    }
+
+    /// Returns the type of the tags this decoder formats.
+    #[must_use]
+    pub const fn typ(&self) -> TagTypeId {
+        self.typ
+    }
+}
+
+impl fmt::Debug for CustomTagDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomTagDecoder")
+            .field("typ", &self.typ)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Formats a tag with the first matching decoder, or only its header if
+/// there is none.
+pub struct DebugCustomTag<'a> {
+    pub tag: &'a DynSizedStructure<TagHeader>,
+    pub decoders: &'a [CustomTagDecoder],
+}
+
+impl fmt::Debug for DebugCustomTag<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self
+            .decoders
+            .iter()
+            .find(|decoder| decoder.typ == self.tag.header().typ)
+        {
+            Some(decoder) => (decoder.fmt)(self.tag, f),
+            None => fmt::Debug::fmt(self.tag.header(), f),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{BootInformation, Builder, CommandLineTag};
+    use alloc::format;
+    use multiboot2_common::{MaybeDynSized, new_boxed};
+
+    #[derive(Debug)]
+    #[repr(C)]
+    struct VendorTag {
+        header: TagHeader,
+        version: u32,
+        flags: u32,
+    }
+
+    impl MaybeDynSized for VendorTag {
+        type Header = TagHeader;
+
+        const BASE_SIZE: usize = size_of::<Self>();
+    }
+
+    impl Tag for VendorTag {
+        type IDType = TagType;
+
+        const ID: TagType = TagType::Custom(0x1337);
+    }
+
+    #[test]
+    fn debug_with() {
+        let structure = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_custom_tag(new_boxed::<DynSizedStructure<TagHeader>>(
+                TagHeader::new(TagType::Custom(0x1337), 0),
+                &[&7_u32.to_ne_bytes(), &3_u32.to_ne_bytes()],
+            ))
+            .add_custom_tag(new_boxed::<DynSizedStructure<TagHeader>>(
+                TagHeader::new(TagType::Custom(0x1338), 0),
+                &[&[1, 2, 3]],
+            ))
+            .add_custom_tag(new_boxed::<DynSizedStructure<TagHeader>>(
+                TagHeader::new(TagType::Custom(0x1339), 0),
+                &[&[4]],
+            ))
+            .build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+
+        let decoders = [
+            CustomTagDecoder::for_tag::<VendorTag>(),
+            CustomTagDecoder::new(TagTypeId::new(0x1338), |tag, f| {
+                write!(f, "OtherTag({:?})", tag.payload())
+            }),
+        ];
+        let output = format!("{:?}", info.debug_with(&decoders));
+        assert!(output.contains("VendorTag { header: TagHeader { typ: Custom(4919), size: 16 }, version: 7, flags: 3 }"), "{output}");
+        assert!(output.contains("OtherTag([1, 2, 3])"), "{output}");
+        assert!(output.contains("custom_tags_count: 3"), "{output}");
+        assert!(
+            output.contains("TagHeader { typ: Custom(4921), size: 9 }"),
+            "{output}"
+        );
+        assert!(output.contains("command_line: Some("), "{output}");
+
+        let output = format!("{info:?}");
+        assert!(!output.contains("VendorTag"), "{output}");
+        assert_eq!(
+            format!("{:?}", decoders[0]),
+            "CustomTagDecoder { typ: Custom(4919), .. }"
+        );
+    }
+}
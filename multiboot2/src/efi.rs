@@ -49,6 +49,30 @@ impl Tag for EFISdt32Tag {
     const ID: TagType = TagType::Efi32;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for EFISdt32Tag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("EFISdt32Tag", 1)?;
+        state.serialize_field("sdt_address", &self.pointer)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EFISdt32Tag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "EFISdt32Tag")]
+        struct Fields {
+            sdt_address: u32,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(fields.sdt_address))
+    }
+}
+
 /// EFI system table in 64 bit mode tag.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C, align(8))]
@@ -86,6 +110,30 @@ impl Tag for EFISdt64Tag {
     const ID: TagType = TagType::Efi64;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for EFISdt64Tag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("EFISdt64Tag", 1)?;
+        state.serialize_field("sdt_address", &self.pointer)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EFISdt64Tag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "EFISdt64Tag")]
+        struct Fields {
+            sdt_address: u64,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(fields.sdt_address))
+    }
+}
+
 /// Tag that contains the pointer to the bootloader's UEFI image handle
 /// (32-bit).
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -126,6 +174,30 @@ impl Tag for EFIImageHandle32Tag {
     const ID: TagType = TagType::Efi32Ih;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for EFIImageHandle32Tag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("EFIImageHandle32Tag", 1)?;
+        state.serialize_field("image_handle", &self.pointer)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EFIImageHandle32Tag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "EFIImageHandle32Tag")]
+        struct Fields {
+            image_handle: u32,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(fields.image_handle))
+    }
+}
+
 /// Tag that contains the pointer to the bootloader's UEFI image handle
 /// (64-bit).
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -164,6 +236,30 @@ impl Tag for EFIImageHandle64Tag {
     const ID: TagType = TagType::Efi64Ih;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for EFIImageHandle64Tag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("EFIImageHandle64Tag", 1)?;
+        state.serialize_field("image_handle", &self.pointer)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EFIImageHandle64Tag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "EFIImageHandle64Tag")]
+        struct Fields {
+            image_handle: u64,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(fields.image_handle))
+    }
+}
+
 /// EFI ExitBootServices was not called tag. This tag has no payload and is
 /// just a marker.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -200,6 +296,28 @@ impl Tag for EFIBootServicesNotExitedTag {
     const ID: TagType = TagType::EfiBs;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for EFIBootServicesNotExitedTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        serializer
+            .serialize_struct("EFIBootServicesNotExitedTag", 0)?
+            .end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EFIBootServicesNotExitedTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "EFIBootServicesNotExitedTag")]
+        struct Fields {}
+        Fields::deserialize(deserializer)?;
+        Ok(Self::new())
+    }
+}
+
 #[cfg(all(test, feature = "builder"))]
 mod tests {
     use super::{EFIImageHandle32Tag, EFIImageHandle64Tag, EFISdt32Tag, EFISdt64Tag};
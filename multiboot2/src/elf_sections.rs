@@ -7,7 +7,7 @@ use elf::endian::NativeEndian;
 use elf::section::{SectionHeader, SectionHeaderTable};
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
-use {alloc::boxed::Box, multiboot2_common::new_boxed};
+use {crate::NewTagError, alloc::boxed::Box, multiboot2_common::try_new_boxed};
 
 /// This tag contains the section header table from an ELF binary.
 // The sections iterator is provided via the [`ElfSectionsTag::sections`]
@@ -27,17 +27,32 @@ pub type ElfSectionIter<'a> = elf::parse::ParsingIterator<'a, NativeEndian, Sect
 
 impl ElfSectionsTag {
     /// Create a new ElfSectionsTag with the given data.
+    ///
+    /// # Panics
+    /// Panics if [`Self::try_new`] fails.
     #[cfg(feature = "builder")]
     #[must_use]
     pub fn new(number_of_sections: u32, entry_size: u32, shndx: u32, sections: &[u8]) -> Box<Self> {
+        Self::try_new(number_of_sections, entry_size, shndx, sections).unwrap()
+    }
+
+    /// Fallible variant of [`Self::new`] that reports a too large section
+    /// header table or a failed allocation.
+    #[cfg(feature = "builder")]
+    pub fn try_new(
+        number_of_sections: u32,
+        entry_size: u32,
+        shndx: u32,
+        sections: &[u8],
+    ) -> Result<Box<Self>, NewTagError> {
         let header = TagHeader::new(Self::ID, 0);
         let number_of_sections = number_of_sections.to_ne_bytes();
         let entry_size = entry_size.to_ne_bytes();
         let shndx = shndx.to_ne_bytes();
-        new_boxed(
+        Ok(try_new_boxed(
             header,
             &[&number_of_sections, &entry_size, &shndx, sections],
-        )
+        )?)
     }
 
     /// Get an iterator over the ELF sections.
@@ -135,6 +150,122 @@ impl Debug for ElfSectionsTag {
     }
 }
 
+/// Serialized form of a [`SectionHeader`]. `name` is the offset of the name in
+/// the string table.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedSection {
+    name: u32,
+    #[serde(rename = "type")]
+    typ: u32,
+    flags: u64,
+    addr: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    addralign: u64,
+    entsize: u64,
+}
+
+#[cfg(feature = "serde")]
+impl From<SectionHeader> for SerializedSection {
+    fn from(section: SectionHeader) -> Self {
+        Self {
+            name: section.sh_name,
+            typ: section.sh_type,
+            flags: section.sh_flags,
+            addr: section.sh_addr,
+            offset: section.sh_offset,
+            size: section.sh_size,
+            link: section.sh_link,
+            info: section.sh_info,
+            addralign: section.sh_addralign,
+            entsize: section.sh_entsize,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ElfSectionsTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+
+        /// Serializes the sections as a sequence.
+        struct Sections<'a>(&'a ElfSectionsTag);
+
+        impl serde::Serialize for Sections<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.sections().map(SerializedSection::from))
+            }
+        }
+
+        if !matches!(self.entry_size, 40 | 64) {
+            return Err(S::Error::custom("unknown ELF section entry size"));
+        }
+        let mut state = serializer.serialize_struct("ElfSectionsTag", 4)?;
+        state.serialize_field("number_of_sections", &self.number_of_sections)?;
+        state.serialize_field("entry_size", &self.entry_size)?;
+        state.serialize_field("shndx", &self.shndx)?;
+        state.serialize_field("sections", &Sections(self))?;
+        state.end()
+    }
+}
+
+/// Deserializes the section headers, which are encoded as ELF32 or ELF64
+/// section headers according to `entry_size`. `number_of_sections` is the
+/// number of deserialized sections.
+#[cfg(all(feature = "serde", feature = "builder"))]
+impl<'de> serde::Deserialize<'de> for Box<ElfSectionsTag> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use alloc::vec::Vec;
+        use serde::Deserialize;
+        use serde::de::Error;
+
+        #[derive(Deserialize)]
+        #[serde(rename = "ElfSectionsTag")]
+        struct Fields {
+            entry_size: u32,
+            shndx: u32,
+            sections: Vec<SerializedSection>,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        if !matches!(fields.entry_size, 40 | 64) {
+            return Err(D::Error::custom("unknown ELF section entry size"));
+        }
+
+        // ELF32 and ELF64 section headers only differ in the width of the
+        // address-sized fields.
+        let push = |bytes: &mut Vec<u8>, value: u64| {
+            if fields.entry_size == 40 {
+                let value =
+                    u32::try_from(value).map_err(|_| D::Error::custom("value exceeds ELF32"))?;
+                bytes.extend_from_slice(&value.to_ne_bytes());
+            } else {
+                bytes.extend_from_slice(&value.to_ne_bytes());
+            }
+            Ok::<_, D::Error>(())
+        };
+        let mut bytes = Vec::with_capacity(fields.sections.len() * fields.entry_size as usize);
+        for section in &fields.sections {
+            bytes.extend_from_slice(&section.name.to_ne_bytes());
+            bytes.extend_from_slice(&section.typ.to_ne_bytes());
+            for value in [section.flags, section.addr, section.offset, section.size] {
+                push(&mut bytes, value)?;
+            }
+            bytes.extend_from_slice(&section.link.to_ne_bytes());
+            bytes.extend_from_slice(&section.info.to_ne_bytes());
+            for value in [section.addralign, section.entsize] {
+                push(&mut bytes, value)?;
+            }
+        }
+        let number_of_sections = u32::try_from(fields.sections.len())
+            .map_err(|_| D::Error::custom("too many ELF sections"))?;
+        ElfSectionsTag::try_new(number_of_sections, fields.entry_size, fields.shndx, &bytes)
+            .map_err(D::Error::custom)
+    }
+}
+
 /// Extension trait for [`SectionHeader`] containing getters for rust-native types
 pub trait ElfSectionExt {
     /// Get the section type as an `ElfSectionType` enum variant.
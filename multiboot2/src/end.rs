@@ -30,6 +30,14 @@ impl Tag for EndTag {
     const ID: TagType = TagType::End;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for EndTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        serializer.serialize_struct("EndTag", 0)?.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -7,7 +7,11 @@ use core::slice;
 use multiboot2_common::{MaybeDynSized, Tag};
 use thiserror::Error;
 #[cfg(feature = "builder")]
-use {alloc::boxed::Box, multiboot2_common::new_boxed};
+use {
+    crate::NewTagError,
+    alloc::boxed::Box,
+    multiboot2_common::{NewBoxedError, try_new_boxed},
+};
 
 /// Helper struct to read bytes from a raw pointer and increase the pointer
 /// automatically.
@@ -96,6 +100,9 @@ pub struct FramebufferTag {
 
 impl FramebufferTag {
     /// Constructs a new tag.
+    ///
+    /// # Panics
+    /// Panics if [`Self::try_new`] fails.
     #[cfg(feature = "builder")]
     #[must_use]
     pub fn new(
@@ -106,6 +113,20 @@ impl FramebufferTag {
         bpp: u8,
         buffer_type: FramebufferType,
     ) -> Box<Self> {
+        Self::try_new(address, pitch, width, height, bpp, buffer_type).unwrap()
+    }
+
+    /// Fallible variant of [`Self::new`] that reports a palette with more
+    /// than `u16::MAX` colors or a failed allocation.
+    #[cfg(feature = "builder")]
+    pub fn try_new(
+        address: u64,
+        pitch: u32,
+        width: u32,
+        height: u32,
+        bpp: u8,
+        buffer_type: FramebufferType,
+    ) -> Result<Box<Self>, NewTagError> {
         let header = TagHeader::new(Self::ID, 0);
         let address = address.to_ne_bytes();
         let pitch = pitch.to_ne_bytes();
@@ -113,8 +134,8 @@ impl FramebufferTag {
         let height = height.to_ne_bytes();
         let buffer_type_id = buffer_type.id();
         let padding = [0; 2];
-        let optional_buffer = buffer_type.serialize();
-        new_boxed(
+        let optional_buffer = buffer_type.serialize()?;
+        Ok(try_new_boxed(
             header,
             &[
                 &address,
@@ -126,7 +147,7 @@ impl FramebufferTag {
                 &padding,
                 &optional_buffer,
             ],
-        )
+        )?)
     }
 
     /// Contains framebuffer physical address.
@@ -157,6 +178,26 @@ impl FramebufferTag {
         self.height
     }
 
+    /// Sets the framebuffer physical address.
+    pub const fn set_address(&mut self, address: u64) {
+        self.address = address;
+    }
+
+    /// Sets the pitch in bytes.
+    pub const fn set_pitch(&mut self, pitch: u32) {
+        self.pitch = pitch;
+    }
+
+    /// Sets the framebuffer width in pixels.
+    pub const fn set_width(&mut self, width: u32) {
+        self.width = width;
+    }
+
+    /// Sets the framebuffer height in pixels.
+    pub const fn set_height(&mut self, height: u32) {
+        self.height = height;
+    }
+
     /// Contains number of bits per pixel.
     #[must_use]
     pub const fn bpp(&self) -> u8 {
@@ -245,6 +286,69 @@ impl Tag for FramebufferTag {
     const ID: TagType = TagType::Framebuffer;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for FramebufferTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+        let buffer_type = self.buffer_type().map_err(S::Error::custom)?;
+        let mut state = serializer.serialize_struct("FramebufferTag", 6)?;
+        state.serialize_field("address", &{ self.address })?;
+        state.serialize_field("pitch", &{ self.pitch })?;
+        state.serialize_field("width", &{ self.width })?;
+        state.serialize_field("height", &{ self.height })?;
+        state.serialize_field("bpp", &self.bpp)?;
+        state.serialize_field("buffer_type", &buffer_type)?;
+        state.end()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "builder"))]
+impl<'de> serde::Deserialize<'de> for Box<FramebufferTag> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        use serde::de::Error;
+        /// Owned counterpart of [`FramebufferType`].
+        #[derive(Deserialize)]
+        #[serde(tag = "type", rename_all = "snake_case")]
+        enum BufferType {
+            Indexed {
+                palette: alloc::vec::Vec<FramebufferColor>,
+            },
+            Rgb {
+                red: FramebufferField,
+                green: FramebufferField,
+                blue: FramebufferField,
+            },
+            Text,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename = "FramebufferTag")]
+        struct Fields {
+            address: u64,
+            pitch: u32,
+            width: u32,
+            height: u32,
+            bpp: u8,
+            buffer_type: BufferType,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        let buffer_type = match &fields.buffer_type {
+            BufferType::Indexed { palette } => FramebufferType::Indexed { palette },
+            &BufferType::Rgb { red, green, blue } => FramebufferType::RGB { red, green, blue },
+            BufferType::Text => FramebufferType::Text,
+        };
+        FramebufferTag::try_new(
+            fields.address,
+            fields.pitch,
+            fields.width,
+            fields.height,
+            fields.bpp,
+            buffer_type,
+        )
+        .map_err(D::Error::custom)
+    }
+}
+
 impl Debug for FramebufferTag {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("FramebufferTag")
@@ -310,6 +414,11 @@ impl From<FramebufferType<'_>> for FramebufferTypeId {
 /// Structured accessory to the provided framebuffer type that is not ABI
 /// compatible.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
 pub enum FramebufferType<'a> {
     /// Indexed color.
     Indexed {
@@ -319,6 +428,7 @@ pub enum FramebufferType<'a> {
 
     /// Direct RGB color.
     #[expect(missing_docs)]
+    #[cfg_attr(feature = "serde", serde(rename = "rgb"))]
     RGB {
         red: FramebufferField,
         green: FramebufferField,
@@ -345,16 +455,23 @@ impl FramebufferType<'_> {
         }
     }
 
-    #[must_use]
     #[cfg(feature = "builder")]
-    fn serialize(&self) -> alloc::vec::Vec<u8> {
+    fn serialize(&self) -> Result<alloc::vec::Vec<u8>, NewTagError> {
+        let len = match self {
+            FramebufferType::Indexed { palette } => 2 + size_of_val(*palette),
+            FramebufferType::RGB { .. } => 6,
+            FramebufferType::Text => 0,
+        };
         let mut data = alloc::vec::Vec::new();
+        data.try_reserve_exact(len)
+            .map_err(|_| NewBoxedError::AllocationFailed(len))?;
         match self {
             FramebufferType::Indexed { palette } => {
                 // TODO we can create a struct for this and implement
                 //  DynSizedStruct for it to leverage the already existing
                 //  functionality
-                let num_colors = palette.len() as u16;
+                let num_colors = u16::try_from(palette.len())
+                    .map_err(|_| NewTagError::PaletteTooLarge(palette.len()))?;
                 data.extend(&num_colors.to_ne_bytes());
                 for color in *palette {
                     let serialized_color = [color.red, color.green, color.blue];
@@ -371,12 +488,13 @@ impl FramebufferType<'_> {
             ]),
             FramebufferType::Text => {}
         }
-        data
+        Ok(data)
     }
 }
 
 /// An RGB color type field.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct FramebufferField {
     /// Color field position.
@@ -392,6 +510,7 @@ pub struct FramebufferField {
 /// The spec is not precise in that regard, but looking at Limine's and GRUB's
 /// source code confirm that.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)] // no align(8) here is correct
 pub struct FramebufferColor {
     /// The Red component of the color.
@@ -404,7 +523,7 @@ pub struct FramebufferColor {
     pub blue: u8,
 }
 
-/// Error when an unknown [`FramebufferTypeId`] is found.
+/// Error when the framebuffer type of a [`FramebufferTag`] is unknown.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Error)]
 #[error("Unknown framebuffer type {0}")]
 pub struct UnknownFramebufferType(u8);
@@ -422,6 +541,28 @@ mod tests {
         assert_eq!(size_of::<FramebufferColor>(), 3)
     }
 
+    #[test]
+    #[cfg(feature = "builder")]
+    fn try_new_palette_too_large() {
+        let color = FramebufferColor {
+            red: 1,
+            green: 2,
+            blue: 3,
+        };
+        let palette = alloc::vec![color; usize::from(u16::MAX) + 1];
+        let buffer_type = FramebufferType::Indexed { palette: &palette };
+        assert_eq!(
+            FramebufferTag::try_new(0x1000, 1, 1024, 1024, 8, buffer_type).unwrap_err(),
+            NewTagError::PaletteTooLarge(0x10000)
+        );
+
+        let buffer_type = FramebufferType::Indexed {
+            palette: &palette[1..],
+        };
+        let tag = FramebufferTag::try_new(0x1000, 1, 1024, 1024, 8, buffer_type.clone()).unwrap();
+        assert_eq!(tag.buffer_type(), Ok(buffer_type));
+    }
+
     #[test]
     #[cfg(feature = "builder")]
     fn create_new() {
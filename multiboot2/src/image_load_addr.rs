@@ -44,6 +44,30 @@ impl Tag for ImageLoadPhysAddrTag {
     const ID: TagType = TagType::LoadBaseAddr;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for ImageLoadPhysAddrTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("ImageLoadPhysAddrTag", 1)?;
+        state.serialize_field("load_base_addr", &self.load_base_addr)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ImageLoadPhysAddrTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "ImageLoadPhysAddrTag")]
+        struct Fields {
+            load_base_addr: u32,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(fields.load_base_addr))
+    }
+}
+
 #[cfg(all(test, feature = "builder"))]
 mod tests {
     use super::ImageLoadPhysAddrTag;
@@ -0,0 +1,399 @@
+//! Module for [`IndexedBootInformation`].
+
+use crate::framebuffer::UnknownFramebufferType;
+use crate::{
+    ApmTag, BasicMemoryInfoTag, BootInformation, BootLoaderNameTag, BootdevTag, CommandLineTag,
+    EFIBootServicesNotExitedTag, EFIImageHandle32Tag, EFIImageHandle64Tag, EFIMemoryMapTag,
+    EFISdt32Tag, EFISdt64Tag, ElfSectionsTag, FramebufferTag, ImageLoadPhysAddrTag, MemoryMapTag,
+    ModuleIter, NetworkTag, RsdpV1Tag, RsdpV2Tag, SmbiosTag, TagHeader, TagIter, TagRef, TagType,
+    VBEInfoTag, module,
+};
+use core::fmt;
+use core::ops::Range;
+use multiboot2_common::{Header, Tag, increase_to_alignment};
+
+/// Amount of tag types specified by Multiboot2, i.e., `0..=21`.
+const SPECIFIED_TAG_TYPES: usize = 22;
+
+/// A [`BootInformation`] with an index over its tags, built once in a single
+/// pass over all tags.
+///
+/// The getters of [`BootInformation`] walk the tags from the start on every
+/// call. This type instead provides the same getters with O(1) access to the
+/// first tag of each type specified by Multiboot2. Iterators over tags that
+/// may occur multiple times, such as [`Self::module_tags`], only walk the
+/// tags from the first to the last tag of that type. The index doesn't need
+/// any allocation. Tags of type [`TagType::Custom`] are not indexed and are
+/// looked up by walking all tags.
+///
+/// # Example
+///
+/// ```no_run
+/// use multiboot2::{BootInformation, BootInformationHeader, IndexedBootInformation};
+///
+/// let mbi_ptr = 0xdeadbeef as *const BootInformationHeader;
+/// let mbi = unsafe { BootInformation::load(mbi_ptr).unwrap() };
+/// let mbi = IndexedBootInformation::new(mbi);
+/// for _ in 0..1000 {
+///     let _areas = mbi.memory_map_tag().map(|tag| tag.memory_areas());
+/// }
+/// ```
+pub struct IndexedBootInformation<'a> {
+    info: BootInformation<'a>,
+    /// The bytes of all tags of `info`.
+    tag_bytes: &'a [u8],
+    /// For each specified tag type, the range of [`Self::tag_bytes`] from the
+    /// start of the first to the end of the last tag of that type. Empty if
+    /// there is no tag of that type.
+    ranges: [Range<usize>; SPECIFIED_TAG_TYPES],
+}
+
+impl<'a> IndexedBootInformation<'a> {
+    /// Builds the index for the given [`BootInformation`] in a single pass
+    /// over all tags.
+    #[must_use]
+    pub fn new(info: BootInformation<'a>) -> Self {
+        const EMPTY: Range<usize> = 0..0;
+        let mut ranges = [EMPTY; SPECIFIED_TAG_TYPES];
+        let mut offset = 0;
+        for tag in info.tags() {
+            let start = offset;
+            offset += increase_to_alignment(tag.header().total_size());
+            let Some(range) = Self::index(tag.header().typ.into()).map(|i| &mut ranges[i]) else {
+                continue;
+            };
+            if range.start == range.end {
+                range.start = start;
+            }
+            range.end = offset;
+        }
+        Self {
+            tag_bytes: info.tag_bytes(),
+            info,
+            ranges,
+        }
+    }
+
+    /// Returns the index of a specified tag type in [`Self::ranges`].
+    fn index(typ: TagType) -> Option<usize> {
+        usize::try_from(typ.val())
+            .ok()
+            .filter(|&i| i < SPECIFIED_TAG_TYPES)
+    }
+
+    /// Returns the underlying [`BootInformation`].
+    #[must_use]
+    pub const fn boot_information(&self) -> &BootInformation<'a> {
+        &self.info
+    }
+
+    /// Returns an iterator over the tags that may contain tags of type
+    /// `typ`. This is the indexed range for specified tag types and all tags
+    /// for custom tag types.
+    fn tags_of(&self, typ: TagType) -> TagIter<'a> {
+        let range = Self::index(typ).map_or(0..self.tag_bytes.len(), |i| self.ranges[i].clone());
+        // SAFETY: The tags were validated when loading the boot information.
+        // The range starts at a tag and ends at the (aligned) end of a tag.
+        unsafe { TagIter::new(&self.tag_bytes[range]) }
+    }
+
+    // ######################################################
+    // ### BEGIN OF TAG GETTERS (in alphabetical order)
+
+    /// Returns the first [`ApmTag`], if present.
+    #[must_use]
+    pub fn apm_tag(&self) -> Option<&'a ApmTag> {
+        self.get_tag::<ApmTag>()
+    }
+
+    /// Returns the first [`BasicMemoryInfoTag`], if present.
+    #[must_use]
+    pub fn basic_memory_info_tag(&self) -> Option<&'a BasicMemoryInfoTag> {
+        self.get_tag::<BasicMemoryInfoTag>()
+    }
+
+    /// Returns the first [`BootLoaderNameTag`], if present.
+    #[must_use]
+    pub fn boot_loader_name_tag(&self) -> Option<&'a BootLoaderNameTag> {
+        self.get_tag::<BootLoaderNameTag>()
+    }
+
+    /// Returns the first [`BootdevTag`], if present.
+    #[must_use]
+    pub fn bootdev_tag(&self) -> Option<&'a BootdevTag> {
+        self.get_tag::<BootdevTag>()
+    }
+
+    /// Returns the first [`CommandLineTag`], if present.
+    #[must_use]
+    pub fn command_line_tag(&self) -> Option<&'a CommandLineTag> {
+        self.get_tag::<CommandLineTag>()
+    }
+
+    /// Returns the first [`EFIBootServicesNotExitedTag`], if present.
+    #[must_use]
+    pub fn efi_bs_not_exited_tag(&self) -> Option<&'a EFIBootServicesNotExitedTag> {
+        self.get_tag::<EFIBootServicesNotExitedTag>()
+    }
+
+    /// Returns the first [`EFIMemoryMapTag`], if the boot services were
+    /// exited. See [`BootInformation::efi_memory_map_tag`].
+    #[must_use]
+    pub fn efi_memory_map_tag(&self) -> Option<&'a EFIMemoryMapTag> {
+        if self.efi_bs_not_exited_tag().is_some() {
+            log::debug!(
+                "The EFI memory map is present but the UEFI Boot Services Not Existed Tag is present. Returning None."
+            );
+            return None;
+        }
+        self.get_tag::<EFIMemoryMapTag>()
+    }
+
+    /// Returns the first [`EFISdt32Tag`], if present.
+    #[must_use]
+    pub fn efi_sdt32_tag(&self) -> Option<&'a EFISdt32Tag> {
+        self.get_tag::<EFISdt32Tag>()
+    }
+
+    /// Returns the first [`EFISdt64Tag`], if present.
+    #[must_use]
+    pub fn efi_sdt64_tag(&self) -> Option<&'a EFISdt64Tag> {
+        self.get_tag::<EFISdt64Tag>()
+    }
+
+    /// Returns the first [`EFIImageHandle32Tag`], if present.
+    #[must_use]
+    pub fn efi_ih32_tag(&self) -> Option<&'a EFIImageHandle32Tag> {
+        self.get_tag::<EFIImageHandle32Tag>()
+    }
+
+    /// Returns the first [`EFIImageHandle64Tag`], if present.
+    #[must_use]
+    pub fn efi_ih64_tag(&self) -> Option<&'a EFIImageHandle64Tag> {
+        self.get_tag::<EFIImageHandle64Tag>()
+    }
+
+    /// Returns the first [`ElfSectionsTag`], if present.
+    #[must_use]
+    pub fn elf_sections_tag(&self) -> Option<&'a ElfSectionsTag> {
+        self.get_tag::<ElfSectionsTag>()
+    }
+
+    /// Returns the first [`FramebufferTag`], if present. The result is
+    /// `Some(Err(e))` if its framebuffer type is unknown.
+    #[must_use]
+    pub fn framebuffer_tag(&self) -> Option<Result<&'a FramebufferTag, UnknownFramebufferType>> {
+        self.get_tag::<FramebufferTag>()
+            .map(|tag| tag.buffer_type().map(|_| tag))
+    }
+
+    /// Returns the first [`ImageLoadPhysAddrTag`], if present.
+    #[must_use]
+    pub fn load_base_addr_tag(&self) -> Option<&'a ImageLoadPhysAddrTag> {
+        self.get_tag::<ImageLoadPhysAddrTag>()
+    }
+
+    /// Returns the first [`MemoryMapTag`], if present.
+    #[must_use]
+    pub fn memory_map_tag(&self) -> Option<&'a MemoryMapTag> {
+        self.get_tag::<MemoryMapTag>()
+    }
+
+    /// Get an iterator of all [`ModuleTag`]s.
+    ///
+    /// [`ModuleTag`]: crate::ModuleTag
+    #[must_use]
+    pub fn module_tags(&self) -> ModuleIter<'a> {
+        module::module_iter(self.tags_of(TagType::Module))
+    }
+
+    /// Returns an iterator over all [`NetworkTag`]s.
+    pub fn network_tags(&self) -> impl Iterator<Item = &'a NetworkTag> + Clone + use<'a> {
+        self.get_tags::<NetworkTag>()
+    }
+
+    /// Returns the first [`RsdpV1Tag`], if present.
+    #[must_use]
+    pub fn rsdp_v1_tag(&self) -> Option<&'a RsdpV1Tag> {
+        self.get_tag::<RsdpV1Tag>()
+    }
+
+    /// Returns the first [`RsdpV2Tag`], if present.
+    #[must_use]
+    pub fn rsdp_v2_tag(&self) -> Option<&'a RsdpV2Tag> {
+        self.get_tag::<RsdpV2Tag>()
+    }
+
+    /// Returns an iterator over all [`SmbiosTag`]s.
+    pub fn smbios_tags(&self) -> impl Iterator<Item = &'a SmbiosTag> + Clone + use<'a> {
+        self.get_tags::<SmbiosTag>()
+    }
+
+    /// Returns the first [`VBEInfoTag`], if present.
+    #[must_use]
+    pub fn vbe_info_tag(&self) -> Option<&'a VBEInfoTag> {
+        self.get_tag::<VBEInfoTag>()
+    }
+
+    // ### END OF TAG GETTERS
+    // ######################################################
+
+    /// Returns the first Multiboot tag of type `T`, including specified and
+    /// custom tags. See [`BootInformation::get_tag`].
+    #[must_use]
+    pub fn get_tag<T>(&self) -> Option<&'a T>
+    where
+        T: Tag<IDType = TagType, Header = TagHeader> + ?Sized + 'a,
+        T::Metadata: Default,
+    {
+        self.get_tags::<T>().next()
+    }
+
+    /// Returns an iterator over all Multiboot tags of type `T`, including
+    /// specified and custom tags. See [`BootInformation::get_tags`].
+    pub fn get_tags<T>(&self) -> impl Iterator<Item = &'a T> + Clone + use<'a, T>
+    where
+        T: Tag<IDType = TagType, Header = TagHeader> + ?Sized + 'a,
+        T::Metadata: Default,
+    {
+        self.tags_of(T::ID)
+            .filter(|tag| tag.header().typ == T::ID)
+            .map(|tag| tag.cast::<T>())
+    }
+
+    /// Returns an untyped iterator over all tags.
+    #[must_use]
+    pub fn tags(&self) -> TagIter<'a> {
+        // SAFETY: The tags were validated when loading the boot information.
+        unsafe { TagIter::new(self.tag_bytes) }
+    }
+
+    /// Returns an iterator over all tags as [`TagRef`], including the end
+    /// tag, in their original order.
+    pub fn typed_tags(&self) -> impl Iterator<Item = TagRef<'a>> + Clone + use<'a> {
+        self.tags().map(TagRef::from)
+    }
+}
+
+impl<'a> From<BootInformation<'a>> for IndexedBootInformation<'a> {
+    fn from(info: BootInformation<'a>) -> Self {
+        Self::new(info)
+    }
+}
+
+impl fmt::Debug for IndexedBootInformation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IndexedBootInformation")
+            .field("info", &self.info)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{Builder, EFIMemoryDesc, FramebufferType, MemoryArea, MemoryAreaType, ModuleTag};
+    use alloc::vec::Vec;
+    use multiboot2_common::{DynSizedStructure, MaybeDynSized, new_boxed};
+
+    #[test]
+    fn same_as_boot_information() {
+        let structure = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_module(ModuleTag::new(0x1000, 0x2000, "module 1"))
+            .bootloader(BootLoaderNameTag::new("bootloader"))
+            .add_module(ModuleTag::new(0x3000, 0x4000, "module 2"))
+            .meminfo(BasicMemoryInfoTag::new(0x4000, 0x5000))
+            .mmap(MemoryMapTag::new(&[MemoryArea::new(
+                0x1000,
+                0x1000,
+                MemoryAreaType::Available,
+            )]))
+            .efi_mmap(EFIMemoryMapTag::new_from_descs(&[EFIMemoryDesc::default()]))
+            .framebuffer(FramebufferTag::new(
+                0x1000,
+                1,
+                1024,
+                768,
+                8,
+                FramebufferType::Text,
+            ))
+            .add_smbios(SmbiosTag::new(0, 0, &[1, 2, 3]))
+            .add_smbios(SmbiosTag::new(1, 1, &[4, 5, 6]))
+            .add_custom_tag(new_boxed::<DynSizedStructure<TagHeader>>(
+                TagHeader::new(TagType::Custom(0x1337), 0),
+                &[&[1, 2, 3]],
+            ))
+            .build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let load = || unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) };
+        let info = load().unwrap();
+        let indexed = IndexedBootInformation::new(load().unwrap());
+
+        assert_eq!(indexed.command_line_tag(), info.command_line_tag());
+        assert_eq!(indexed.boot_loader_name_tag(), info.boot_loader_name_tag());
+        assert_eq!(
+            indexed.basic_memory_info_tag(),
+            info.basic_memory_info_tag()
+        );
+        assert_eq!(
+            indexed.memory_map_tag().map(MemoryMapTag::memory_areas),
+            info.memory_map_tag().map(MemoryMapTag::memory_areas)
+        );
+        assert!(indexed.efi_memory_map_tag().is_some());
+        assert_eq!(
+            indexed.framebuffer_tag().unwrap().unwrap().width(),
+            info.framebuffer_tag().unwrap().unwrap().width()
+        );
+        assert!(indexed.apm_tag().is_none());
+        assert!(indexed.vbe_info_tag().is_none());
+        assert_eq!(
+            indexed
+                .module_tags()
+                .map(|tag| tag.cmdline().unwrap())
+                .collect::<Vec<_>>(),
+            ["module 1", "module 2"]
+        );
+        assert_eq!(
+            indexed
+                .smbios_tags()
+                .map(|tag| tag.major())
+                .collect::<Vec<_>>(),
+            [0, 1]
+        );
+        assert_eq!(indexed.network_tags().count(), 0);
+        assert_eq!(indexed.tags().count(), info.tags().count());
+        assert_eq!(indexed.typed_tags().count(), info.typed_tags().count());
+
+        let custom = indexed
+            .tags_of(TagType::Custom(0x1337))
+            .find(|tag| tag.header().typ == TagType::Custom(0x1337))
+            .unwrap();
+        assert_eq!(custom.payload(), [1, 2, 3]);
+
+        // The builder emits all modules next to each other, so the range of
+        // the module tags contains nothing else.
+        assert_eq!(indexed.tags_of(TagType::Module).count(), 2);
+    }
+
+    #[test]
+    fn efi_memory_map_policy() {
+        let structure = Builder::new()
+            .efi_mmap(EFIMemoryMapTag::new_from_descs(&[EFIMemoryDesc::default()]))
+            .efi_bs(EFIBootServicesNotExitedTag::new())
+            .build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+        let indexed = IndexedBootInformation::from(info);
+        assert!(indexed.efi_bs_not_exited_tag().is_some());
+        assert!(indexed.efi_memory_map_tag().is_none());
+        assert!(
+            indexed
+                .boot_information()
+                .get_tag::<EFIMemoryMapTag>()
+                .is_some()
+        );
+    }
+}
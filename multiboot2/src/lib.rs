@@ -34,6 +34,15 @@
 //! `alloc`; using it requires an `#[global_allocator]`. Remove that feature if
 //! you do not need to construct boot information structures.
 //!
+//! The optional `serde` feature implements `serde::Serialize` for
+//! [`BootInformation`] and all tags, e.g., to dump the boot information as
+//! JSON. Together with `builder`, it also implements `serde::Deserialize` for
+//! [`Builder`], which builds the same boot information again.
+//!
+//! The optional `derive` feature provides the `Multiboot2Tag` derive macro,
+//! which implements the necessary traits for custom tags. See
+//! [`BootInformation::get_tag`].
+//!
 //! ## Example
 //!
 //! ```rust
@@ -50,7 +59,7 @@
 //! ## MSRV
 //! The MSRV is 1.85.1 stable.
 
-#[cfg(feature = "builder")]
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
 // this crate can use std in tests only
@@ -73,33 +82,44 @@ pub type GenericInfoTag = multiboot2_common::DynSizedStructure<TagHeader>;
 
 mod apm;
 mod boot_information;
+mod boot_information_mut;
 mod boot_loader_name;
 mod bootdev;
 mod command_line;
+mod custom_tag_decoder;
 mod efi;
 mod elf_sections;
 mod end;
 mod framebuffer;
 mod image_load_addr;
+mod indexed_boot_information;
 mod memory_map;
 mod module;
 mod network;
+#[cfg(feature = "alloc")]
+mod owned_boot_information;
 mod rsdp;
 mod smbios;
+mod summary;
 mod tag;
+mod tag_ref;
 mod tag_type;
 pub(crate) mod util;
 mod vbe_info;
 
-pub use multiboot2_common::{DynSizedStructure, MaybeDynSized, Tag};
+#[cfg(feature = "builder")]
+pub use multiboot2_common::NewBoxedError;
+pub use multiboot2_common::{ByteOrder, DynSizedStructure, MaybeDynSized, Tag};
 
 pub use apm::ApmTag;
 pub use boot_information::{BootInformation, BootInformationHeader, LoadError};
+pub use boot_information_mut::{BootInformationMut, PushTagError};
 pub use boot_loader_name::BootLoaderNameTag;
 pub use bootdev::BootdevTag;
 #[cfg(feature = "builder")]
-pub use builder::Builder;
+pub use builder::{BuildError, Builder};
 pub use command_line::CommandLineTag;
+pub use custom_tag_decoder::{CustomTagDecoder, CustomTagFormatter};
 pub use efi::{
     EFIBootServicesNotExitedTag, EFIImageHandle32Tag, EFIImageHandle64Tag, EFISdt32Tag, EFISdt64Tag,
 };
@@ -107,25 +127,66 @@ pub use elf_sections::{
     ElfSectionExt, ElfSectionFlags, ElfSectionIter, ElfSectionType, ElfSectionsTag,
 };
 pub use end::EndTag;
-pub use framebuffer::{FramebufferColor, FramebufferField, FramebufferTag, FramebufferType};
+pub use framebuffer::{
+    FramebufferColor, FramebufferField, FramebufferTag, FramebufferType, UnknownFramebufferType,
+};
 pub use image_load_addr::ImageLoadPhysAddrTag;
+pub use indexed_boot_information::IndexedBootInformation;
 pub use memory_map::{
     BasicMemoryInfoTag, EFIMemoryAreaType, EFIMemoryAttribute, EFIMemoryDesc, EFIMemoryMapTag,
     MemoryArea, MemoryAreaType, MemoryAreaTypeId, MemoryMapTag,
 };
 pub use module::{ModuleIter, ModuleTag};
 pub use network::NetworkTag;
+#[cfg(feature = "alloc")]
+pub use owned_boot_information::OwnedBootInformation;
 pub use ptr_meta::Pointee;
 pub use rsdp::{RsdpV1Tag, RsdpV2Tag};
 pub use smbios::SmbiosTag;
 pub use tag::TagHeader;
+pub use tag_ref::TagRef;
 pub use tag_type::{TagType, TagTypeId};
+#[cfg(feature = "builder")]
+pub use util::NewTagError;
 pub use util::{StringError, parse_slice_as_string};
 pub use vbe_info::{
     VBECapabilities, VBEControlInfo, VBEDirectColorAttributes, VBEField, VBEInfoTag,
     VBEMemoryModel, VBEModeAttributes, VBEModeInfo, VBEWindowAttributes,
 };
 
+#[cfg(feature = "derive")]
+pub use multiboot2_common::NoPadding;
+#[cfg(feature = "derive")]
+pub use multiboot2_derive::Multiboot2Tag;
+
+/// Items used by the code generated by [`Multiboot2Tag`]. Not public API.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::__multiboot2_builder_only as builder_only;
+    pub use crate::{TagHeader, TagType};
+    pub use multiboot2_common::{Header, MaybeDynSized, Tag, bytes_of};
+    pub use ptr_meta::Pointee;
+    #[cfg(feature = "builder")]
+    pub use {alloc::boxed::Box, multiboot2_common::new_boxed};
+}
+
+/// Expands the given items only with the `builder` feature.
+#[cfg(all(feature = "derive", feature = "builder"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __multiboot2_builder_only {
+    ($($item:item)*) => { $($item)* };
+}
+
+/// Expands the given items only with the `builder` feature.
+#[cfg(all(feature = "derive", not(feature = "builder")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __multiboot2_builder_only {
+    ($($item:item)*) => {};
+}
+
 /// Magic number that a Multiboot2-compliant bootloader will use to identify
 /// the handoff. The location depends on the architecture and the targeted
 /// machine state.
@@ -11,7 +11,7 @@ use core::fmt::{Debug, Formatter};
 use core::marker::PhantomData;
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
-use {alloc::boxed::Box, core::slice, multiboot2_common::new_boxed};
+use {crate::NewTagError, alloc::boxed::Box, core::slice, multiboot2_common::try_new_boxed};
 
 /// This tag provides an initial host memory map (legacy boot, not UEFI).
 ///
@@ -34,9 +34,19 @@ pub struct MemoryMapTag {
 
 impl MemoryMapTag {
     /// Constructs a new tag.
+    ///
+    /// # Panics
+    /// Panics if [`Self::try_new`] fails.
     #[cfg(feature = "builder")]
     #[must_use]
     pub fn new(areas: &[MemoryArea]) -> Box<Self> {
+        Self::try_new(areas).unwrap()
+    }
+
+    /// Fallible variant of [`Self::new`] that reports a too large memory map
+    /// or a failed allocation.
+    #[cfg(feature = "builder")]
+    pub fn try_new(areas: &[MemoryArea]) -> Result<Box<Self>, NewTagError> {
         let header = TagHeader::new(Self::ID, 0);
         let entry_size = (size_of::<MemoryArea>() as u32).to_ne_bytes();
         let entry_version = 0_u32.to_ne_bytes();
@@ -47,7 +57,10 @@ impl MemoryMapTag {
             // initialized bytes.
             unsafe { slice::from_raw_parts(ptr, len) }
         };
-        new_boxed(header, &[&entry_size, &entry_version, areas])
+        Ok(try_new_boxed(
+            header,
+            &[&entry_size, &entry_version, areas],
+        )?)
     }
 
     /// Returns the entry size.
@@ -72,6 +85,15 @@ impl MemoryMapTag {
         assert_eq!(self.entry_size as usize, size_of::<MemoryArea>());
         &self.areas
     }
+
+    /// Returns the mutable slice of the provided [`MemoryArea`]s, e.g., to
+    /// change their type.
+    #[must_use]
+    pub fn memory_areas_mut(&mut self) -> &mut [MemoryArea] {
+        // If this ever fails, we need to model this differently in this crate.
+        assert_eq!(self.entry_size as usize, size_of::<MemoryArea>());
+        &mut self.areas
+    }
 }
 
 impl MaybeDynSized for MemoryMapTag {
@@ -93,6 +115,36 @@ impl Tag for MemoryMapTag {
     const ID: TagType = TagType::Mmap;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for MemoryMapTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+        if self.entry_size as usize != size_of::<MemoryArea>() {
+            return Err(S::Error::custom("unsupported memory map entry size"));
+        }
+        let mut state = serializer.serialize_struct("MemoryMapTag", 3)?;
+        state.serialize_field("entry_size", &self.entry_size)?;
+        state.serialize_field("entry_version", &self.entry_version)?;
+        state.serialize_field("areas", &self.areas)?;
+        state.end()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "builder"))]
+impl<'de> serde::Deserialize<'de> for Box<MemoryMapTag> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        use serde::de::Error;
+        #[derive(Deserialize)]
+        #[serde(rename = "MemoryMapTag")]
+        struct Fields {
+            areas: alloc::vec::Vec<MemoryArea>,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        MemoryMapTag::try_new(&fields.areas).map_err(D::Error::custom)
+    }
+}
+
 /// A descriptor for an available or taken area of physical memory.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C)]
@@ -137,6 +189,11 @@ impl MemoryArea {
     pub const fn typ(&self) -> MemoryAreaTypeId {
         self.typ
     }
+
+    /// Sets the type of the memory region, e.g., to mark it as reserved.
+    pub fn set_typ(&mut self, typ: impl Into<MemoryAreaTypeId>) {
+        self.typ = typ.into();
+    }
 }
 
 impl Debug for MemoryArea {
@@ -149,6 +206,35 @@ impl Debug for MemoryArea {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for MemoryArea {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("MemoryArea", 3)?;
+        state.serialize_field("base_addr", &self.base_addr)?;
+        state.serialize_field("length", &self.length)?;
+        state.serialize_field("type", &self.typ.0)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MemoryArea {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "MemoryArea")]
+        struct Fields {
+            base_addr: u64,
+            length: u64,
+            #[serde(rename = "type")]
+            typ: u32,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(fields.base_addr, fields.length, fields.typ))
+    }
+}
+
 /// ABI-friendly version of [`MemoryAreaType`].
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C)]
@@ -289,6 +375,16 @@ impl BasicMemoryInfoTag {
     pub const fn memory_upper(&self) -> u32 {
         self.memory_upper
     }
+
+    /// Sets the lower memory bound.
+    pub const fn set_memory_lower(&mut self, memory_lower: u32) {
+        self.memory_lower = memory_lower;
+    }
+
+    /// Sets the upper memory bound.
+    pub const fn set_memory_upper(&mut self, memory_upper: u32) {
+        self.memory_upper = memory_upper;
+    }
 }
 
 impl MaybeDynSized for BasicMemoryInfoTag {
@@ -303,6 +399,32 @@ impl Tag for BasicMemoryInfoTag {
     const ID: TagType = TagType::BasicMeminfo;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for BasicMemoryInfoTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("BasicMemoryInfoTag", 2)?;
+        state.serialize_field("memory_lower", &self.memory_lower)?;
+        state.serialize_field("memory_upper", &self.memory_upper)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BasicMemoryInfoTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "BasicMemoryInfoTag")]
+        struct Fields {
+            memory_lower: u32,
+            memory_upper: u32,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(fields.memory_lower, fields.memory_upper))
+    }
+}
+
 /// EFI memory map tag. The embedded [`EFIMemoryDesc`]s follows the EFI
 /// specification.
 #[derive(ptr_meta::Pointee, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -330,9 +452,19 @@ pub struct EFIMemoryMapTag {
 
 impl EFIMemoryMapTag {
     /// Create a new EFI memory map tag with the given memory descriptors.
+    ///
+    /// # Panics
+    /// Panics if [`Self::try_new_from_descs`] fails.
     #[cfg(feature = "builder")]
     #[must_use]
     pub fn new_from_descs(descs: &[EFIMemoryDesc]) -> Box<Self> {
+        Self::try_new_from_descs(descs).unwrap()
+    }
+
+    /// Fallible variant of [`Self::new_from_descs`] that reports a too large
+    /// memory map or a failed allocation.
+    #[cfg(feature = "builder")]
+    pub fn try_new_from_descs(descs: &[EFIMemoryDesc]) -> Result<Box<Self>, NewTagError> {
         let efi_mmap = {
             let ptr = descs.as_ptr().cast::<u8>();
             let len = size_of_val(descs);
@@ -341,7 +473,7 @@ impl EFIMemoryMapTag {
             unsafe { slice::from_raw_parts(ptr, len) }
         };
 
-        Self::new_from_map(
+        Self::try_new_from_map(
             size_of::<EFIMemoryDesc>() as u32,
             EFIMemoryDesc::VERSION,
             efi_mmap,
@@ -349,14 +481,45 @@ impl EFIMemoryMapTag {
     }
 
     /// Create a new EFI memory map tag from the given EFI memory map.
+    ///
+    /// # Panics
+    /// Panics if [`Self::try_new_from_map`] fails.
     #[cfg(feature = "builder")]
     #[must_use]
     pub fn new_from_map(desc_size: u32, desc_version: u32, efi_mmap: &[u8]) -> Box<Self> {
+        Self::try_new_from_map(desc_size, desc_version, efi_mmap).unwrap()
+    }
+
+    /// Fallible variant of [`Self::new_from_map`] that reports a zero
+    /// `desc_size`, a too large memory map, or a failed allocation.
+    #[cfg(feature = "builder")]
+    pub fn try_new_from_map(
+        desc_size: u32,
+        desc_version: u32,
+        efi_mmap: &[u8],
+    ) -> Result<Box<Self>, NewTagError> {
         let header = TagHeader::new(Self::ID, 0);
-        assert_ne!(desc_size, 0);
+        if desc_size == 0 {
+            return Err(NewTagError::ZeroDescriptorSize);
+        }
         let desc_size = desc_size.to_ne_bytes();
         let desc_version = desc_version.to_ne_bytes();
-        new_boxed(header, &[&desc_size, &desc_version, efi_mmap])
+        Ok(try_new_boxed(
+            header,
+            &[&desc_size, &desc_version, efi_mmap],
+        )?)
+    }
+
+    /// Returns the size of each descriptor.
+    #[must_use]
+    pub const fn desc_size(&self) -> u32 {
+        self.desc_size
+    }
+
+    /// Returns the version of the descriptors.
+    #[must_use]
+    pub const fn desc_version(&self) -> u32 {
+        self.desc_version
     }
 
     /// Returns an iterator over the provided memory areas.
@@ -421,6 +584,100 @@ impl Tag for EFIMemoryMapTag {
     const ID: TagType = TagType::EfiMmap;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for EFIMemoryMapTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+
+        /// Serializes the descriptors of an [`EFIMemoryAreaIter`].
+        struct Descriptors<'a>(EFIMemoryAreaIter<'a>);
+
+        impl serde::Serialize for Descriptors<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.clone().map(Descriptor))
+            }
+        }
+
+        /// Serializes an [`EFIMemoryDesc`], which is a foreign type.
+        struct Descriptor<'a>(&'a EFIMemoryDesc);
+
+        impl serde::Serialize for Descriptor<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut state = serializer.serialize_struct("EFIMemoryDesc", 5)?;
+                state.serialize_field("type", &self.0.ty.0)?;
+                state.serialize_field("phys_start", &self.0.phys_start)?;
+                state.serialize_field("virt_start", &self.0.virt_start)?;
+                state.serialize_field("page_count", &self.0.page_count)?;
+                state.serialize_field("attribute", &self.0.att.bits())?;
+                state.end()
+            }
+        }
+
+        // Report what `memory_areas` would panic on as error.
+        let desc_size = self.desc_size as usize;
+        if self.desc_version != EFIMemoryDesc::VERSION
+            || desc_size < size_of::<EFIMemoryDesc>()
+            || desc_size % align_of::<EFIMemoryDesc>() != 0
+            || self.memory_map.len() % desc_size != 0
+        {
+            return Err(S::Error::custom("unsupported EFI memory map layout"));
+        }
+        let mut state = serializer.serialize_struct("EFIMemoryMapTag", 3)?;
+        state.serialize_field("desc_size", &self.desc_size)?;
+        state.serialize_field("desc_version", &self.desc_version)?;
+        state.serialize_field("descriptors", &Descriptors(self.memory_areas()))?;
+        state.end()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "builder"))]
+impl<'de> serde::Deserialize<'de> for Box<EFIMemoryMapTag> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use alloc::vec::Vec;
+        use serde::Deserialize;
+        use serde::de::Error;
+        #[derive(Deserialize)]
+        #[serde(rename = "EFIMemoryDesc")]
+        struct Descriptor {
+            #[serde(rename = "type")]
+            typ: u32,
+            phys_start: u64,
+            virt_start: u64,
+            page_count: u64,
+            attribute: u64,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename = "EFIMemoryMapTag")]
+        struct Fields {
+            desc_size: u32,
+            desc_version: u32,
+            descriptors: Vec<Descriptor>,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        let desc_size = fields.desc_size as usize;
+        if desc_size < size_of::<EFIMemoryDesc>() || desc_size % align_of::<EFIMemoryDesc>() != 0 {
+            return Err(D::Error::custom(
+                "EFI descriptor size must cover an aligned EFI memory descriptor",
+            ));
+        }
+        let mut efi_mmap = Vec::new();
+        for desc in fields.descriptors {
+            // Layout of `EFI_MEMORY_DESCRIPTOR`, including the padding after
+            // the type, followed by the zeroed remainder of `desc_size`.
+            let start = efi_mmap.len();
+            efi_mmap.extend_from_slice(&desc.typ.to_ne_bytes());
+            efi_mmap.extend_from_slice(&[0; 4]);
+            efi_mmap.extend_from_slice(&desc.phys_start.to_ne_bytes());
+            efi_mmap.extend_from_slice(&desc.virt_start.to_ne_bytes());
+            efi_mmap.extend_from_slice(&desc.page_count.to_ne_bytes());
+            efi_mmap.extend_from_slice(&desc.attribute.to_ne_bytes());
+            efi_mmap.resize(start + desc_size, 0);
+        }
+        EFIMemoryMapTag::try_new_from_map(fields.desc_size, fields.desc_version, &efi_mmap)
+            .map_err(D::Error::custom)
+    }
+}
+
 /// An iterator over the EFI memory areas emitting [`EFIMemoryDesc`] items.
 #[derive(Clone)]
 pub struct EFIMemoryAreaIter<'a> {
@@ -504,6 +761,21 @@ mod tests {
         dbg!(mmap);
     }
 
+    #[test]
+    fn test_try_new_mmap() {
+        let areas = [MemoryArea::new(0x1000, 0x2000, MemoryAreaType::Available)];
+        let mmap = MemoryMapTag::try_new(&areas).unwrap();
+        assert_eq!(mmap.memory_areas(), &areas);
+    }
+
+    #[test]
+    fn efi_try_new_zero_desc_size() {
+        assert_eq!(
+            EFIMemoryMapTag::try_new_from_map(0, EFIMemoryDesc::VERSION, &[]).unwrap_err(),
+            NewTagError::ZeroDescriptorSize
+        );
+    }
+
     #[test]
     fn efi_construct_and_parse() {
         let descs = [
@@ -5,7 +5,7 @@ use crate::{StringError, TagIter, TagType, parse_slice_as_string};
 use core::fmt::{Debug, Formatter};
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
-use {alloc::boxed::Box, multiboot2_common::new_boxed};
+use {crate::NewTagError, alloc::boxed::Box, multiboot2_common::try_new_boxed};
 
 /// The module tag can occur multiple times and specifies passed boot modules
 /// (blobs in memory). The tag itself doesn't include the blob, but references
@@ -22,21 +22,34 @@ pub struct ModuleTag {
 
 impl ModuleTag {
     /// Constructs a new tag.
+    ///
+    /// # Panics
+    /// Panics if [`Self::try_new`] fails.
     #[cfg(feature = "builder")]
     #[must_use]
     pub fn new(start: u32, end: u32, cmdline: &str) -> Box<Self> {
+        Self::try_new(start, end, cmdline).unwrap()
+    }
+
+    /// Fallible variant of [`Self::new`] that reports an empty module, a too
+    /// large command line, or a failed allocation.
+    #[cfg(feature = "builder")]
+    pub fn try_new(start: u32, end: u32, cmdline: &str) -> Result<Box<Self>, NewTagError> {
         let header = TagHeader::new(Self::ID, 0);
-        assert!(end > start, "must have a size");
+        if end <= start {
+            return Err(NewTagError::EmptyModule { start, end });
+        }
 
         let start = start.to_ne_bytes();
         let end = end.to_ne_bytes();
         let cmdline = cmdline.as_bytes();
 
-        if cmdline.ends_with(&[0]) {
-            new_boxed(header, &[&start, &end, cmdline])
+        let tag = if cmdline.ends_with(&[0]) {
+            try_new_boxed(header, &[&start, &end, cmdline])
         } else {
-            new_boxed(header, &[&start, &end, cmdline, &[0]])
-        }
+            try_new_boxed(header, &[&start, &end, cmdline, &[0]])
+        };
+        Ok(tag?)
     }
 
     /// Reads the command line of the boot module as Rust string slice without
@@ -87,6 +100,35 @@ impl Tag for ModuleTag {
     const ID: TagType = TagType::Module;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for ModuleTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+        let mut state = serializer.serialize_struct("ModuleTag", 3)?;
+        state.serialize_field("start", &self.mod_start)?;
+        state.serialize_field("end", &self.mod_end)?;
+        state.serialize_field("cmdline", self.cmdline().map_err(S::Error::custom)?)?;
+        state.end()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "builder"))]
+impl<'de> serde::Deserialize<'de> for Box<ModuleTag> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        use serde::de::Error;
+        #[derive(Deserialize)]
+        #[serde(rename = "ModuleTag")]
+        struct Fields {
+            start: u32,
+            end: u32,
+            cmdline: alloc::string::String,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        ModuleTag::try_new(fields.start, fields.end, &fields.cmdline).map_err(D::Error::custom)
+    }
+}
+
 impl Debug for ModuleTag {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("ModuleTag")
@@ -186,4 +228,16 @@ mod tests {
         let tag = ModuleTag::new(0, 1, "AbCdEfGhUjK YEAH".repeat(42).as_str());
         assert_eq!(tag.cmdline(), Ok("AbCdEfGhUjK YEAH".repeat(42).as_str()));
     }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn try_new_empty_module() {
+        assert_eq!(
+            ModuleTag::try_new(0x1000, 0x1000, "").unwrap_err(),
+            NewTagError::EmptyModule {
+                start: 0x1000,
+                end: 0x1000
+            }
+        );
+    }
 }
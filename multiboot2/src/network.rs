@@ -40,3 +40,27 @@ impl Tag for NetworkTag {
 
     const ID: TagType = TagType::Network;
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for NetworkTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("NetworkTag", 1)?;
+        state.serialize_field("dhcpack", &self.dhcpack)?;
+        state.end()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "builder"))]
+impl<'de> serde::Deserialize<'de> for Box<NetworkTag> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "NetworkTag")]
+        struct Fields {
+            dhcpack: alloc::vec::Vec<u8>,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(NetworkTag::new(&fields.dhcpack))
+    }
+}
@@ -0,0 +1,303 @@
+//! Module for [`OwnedBootInformation`].
+
+use crate::{BootInformation, BootInformationHeader, LoadError, TagHeader, TagType};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use multiboot2_common::{
+    ALIGNMENT, ByteOrder, ByteSwapper, DynSizedStructure, MaybeDynSized, MemoryError, new_boxed,
+};
+
+/// A boot information copied from a possibly unaligned buffer, such as a dump
+/// file, into an owned heap allocation.
+///
+/// The buffer may use a different byte order than the host. For example,
+/// little-endian dumps can be analyzed on big-endian hosts. All integer fields
+/// of the known tags are converted to the native byte order while copying.
+/// Custom tags are copied as they are, except for their header.
+///
+/// # Example
+///
+/// ```rust
+/// use multiboot2::{ByteOrder, OwnedBootInformation};
+///
+/// # let dump = [16, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0];
+/// // A dump from an x86 machine.
+/// let owned = OwnedBootInformation::load(&dump, ByteOrder::Little).unwrap();
+/// assert_eq!(owned.boot_information().total_size(), 16);
+/// ```
+#[derive(Debug)]
+pub struct OwnedBootInformation(Box<DynSizedStructure<BootInformationHeader>>);
+
+impl OwnedBootInformation {
+    /// Loads the boot information at the beginning of `buffer`, which uses
+    /// the given `byte_order`.
+    ///
+    /// The same checks as in [`BootInformation::load`] are performed.
+    pub fn load(buffer: &[u8], byte_order: ByteOrder) -> Result<Self, LoadError> {
+        let total_size = buffer
+            .first_chunk::<4>()
+            .map(|bytes| byte_order.read_u32(*bytes) as usize)
+            .ok_or(LoadError::Memory(MemoryError::ShorterThanHeader))?;
+        let mut bytes: Vec<u8> = buffer
+            .get(..total_size)
+            .ok_or(LoadError::Memory(MemoryError::InvalidReportedTotalSize(
+                total_size,
+                buffer.len(),
+            )))?
+            .into();
+        let header_size = size_of::<BootInformationHeader>();
+        if total_size < header_size {
+            return Err(LoadError::Memory(MemoryError::SizeInsufficient(
+                total_size,
+                header_size,
+            )));
+        }
+        if !byte_order.is_native() {
+            swap_tags(&mut bytes, byte_order);
+        }
+
+        let header = BootInformationHeader::new(total_size as u32);
+        let this = Self(new_boxed(header, &[&bytes[header_size..]]));
+        // Validates the tags.
+        BootInformation::from_slice(&this.0.as_bytes())?;
+        Ok(this)
+    }
+
+    /// Returns the parsed boot information.
+    #[must_use]
+    pub fn boot_information(&self) -> BootInformation<'_> {
+        // SAFETY: The boot information was validated on construction.
+        unsafe { BootInformation::load((&raw const *self.0).cast()) }
+            .expect("should be a valid boot information")
+    }
+}
+
+/// Reverses the byte order of the integer fields of the boot information
+/// header and all tags, which currently use `byte_order`.
+fn swap_tags(bytes: &mut [u8], byte_order: ByteOrder) {
+    ByteSwapper::new(bytes).swap_n(4, 2);
+    let mut offset = size_of::<BootInformationHeader>();
+    while let Some(header) = bytes.get_mut(offset..offset + size_of::<TagHeader>()) {
+        let typ = byte_order.read_u32(header[0..4].try_into().unwrap());
+        let size = byte_order.read_u32(header[4..8].try_into().unwrap()) as usize;
+        ByteSwapper::new(header).swap_n(4, 2);
+        let Some(tag) = bytes.get_mut(offset..offset.saturating_add(size)) else {
+            break;
+        };
+        if size < size_of::<TagHeader>() || typ == TagType::End.val() {
+            break;
+        }
+        swap_tag(typ.into(), tag, byte_order);
+        offset = offset.saturating_add(size.next_multiple_of(ALIGNMENT));
+    }
+}
+
+/// Reverses the byte order of the integer fields of a tag, except for its
+/// header.
+fn swap_tag(typ: TagType, tag: &mut [u8], byte_order: ByteOrder) {
+    let read_u32 = |tag: &[u8], idx: usize| {
+        tag.get(idx..idx + 4)
+            .map(|bytes| byte_order.read_u32(bytes.try_into().unwrap()) as usize)
+    };
+    let framebuffer_type = tag.get(29).copied();
+    let num_elf_sections = read_u32(tag, 8).unwrap_or(0);
+    let entry_size = match typ {
+        TagType::ElfSections => read_u32(tag, 12),
+        _ => read_u32(tag, 8),
+    }
+    .unwrap_or(0);
+
+    let mut swapper = ByteSwapper::new(tag);
+    swapper.skip(size_of::<TagHeader>());
+    match typ {
+        TagType::Module | TagType::BasicMeminfo => {
+            swapper.swap_n(4, 2);
+        }
+        TagType::Bootdev => {
+            swapper.swap_n(4, 3);
+        }
+        TagType::Mmap if entry_size >= 24 => {
+            swapper.swap_n(4, 2);
+            for _ in 0..swapper.remaining() / entry_size {
+                swapper.swap_n(8, 2).swap_n(4, 2).skip(entry_size - 24);
+            }
+        }
+        TagType::Vbe => {
+            swapper.swap_n(2, 4);
+            // VBE control info
+            swapper
+                .skip(4)
+                .swap(2)
+                .swap_n(4, 3)
+                .swap_n(2, 2)
+                .swap_n(4, 3);
+            swapper.skip(222 + 256);
+            // VBE mode info
+            swapper.swap(2).skip(2).swap_n(2, 4).swap(4).swap_n(2, 3);
+            swapper.skip(18).swap_n(4, 2).swap(2);
+        }
+        TagType::Framebuffer => {
+            swapper.swap(8).swap_n(4, 3).skip(2).swap(2);
+            // Indexed framebuffers start with the number of colors.
+            if framebuffer_type == Some(0) {
+                swapper.swap(2);
+            }
+        }
+        TagType::ElfSections => {
+            swapper.swap_n(4, 3);
+            let count = match entry_size {
+                40 | 64 => num_elf_sections.min(swapper.remaining() / entry_size),
+                _ => 0,
+            };
+            for _ in 0..count {
+                if entry_size == 40 {
+                    swapper.swap_n(4, 10);
+                } else {
+                    swapper.swap_n(4, 2).swap_n(8, 4).swap_n(4, 2).swap_n(8, 2);
+                }
+            }
+        }
+        TagType::Apm => {
+            swapper.swap_n(2, 2).swap(4).swap_n(2, 6);
+        }
+        TagType::Efi32 | TagType::Efi32Ih | TagType::LoadBaseAddr => {
+            swapper.swap(4);
+        }
+        TagType::Efi64 | TagType::Efi64Ih => {
+            swapper.swap(8);
+        }
+        TagType::AcpiV1 => {
+            swapper.skip(16).swap(4);
+        }
+        TagType::AcpiV2 => {
+            swapper.skip(16).swap_n(4, 2).swap(8);
+        }
+        TagType::EfiMmap if entry_size >= 40 => {
+            swapper.swap_n(4, 2);
+            for _ in 0..swapper.remaining() / entry_size {
+                swapper.swap_n(4, 2).swap_n(8, 4).skip(entry_size - 40);
+            }
+        }
+        // Strings, raw data, and unknown layouts.
+        _ => {}
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{
+        BasicMemoryInfoTag, Builder, CommandLineTag, EFIMemoryDesc, EFIMemoryMapTag,
+        ElfSectionsTag, FramebufferTag, FramebufferType, MemoryArea, MemoryAreaType, MemoryMapTag,
+        ModuleTag, RsdpV2Tag, VBEControlInfo, VBEInfoTag, VBEModeInfo,
+    };
+    use multiboot2_common::{DynSizedStructure as Dst, new_boxed};
+    use uefi_raw::table::boot::MemoryType;
+
+    const FOREIGN: ByteOrder = match ByteOrder::NATIVE {
+        ByteOrder::Little => ByteOrder::Big,
+        ByteOrder::Big => ByteOrder::Little,
+    };
+
+    #[test]
+    fn load() {
+        let mut elf_sections = [0_u8; 2 * 64];
+        elf_sections[64 + 4..64 + 8].copy_from_slice(&1_u32.to_ne_bytes());
+        elf_sections[64 + 16..64 + 24].copy_from_slice(&0x1000_u64.to_ne_bytes());
+        let structure = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_module(ModuleTag::new(0x1000, 0x2000, "module"))
+            .meminfo(BasicMemoryInfoTag::new(640, 7 * 1024))
+            .mmap(MemoryMapTag::new(&[MemoryArea::new(
+                0x100000,
+                0x200000,
+                MemoryAreaType::Available,
+            )]))
+            .vbe(VBEInfoTag::new(
+                0x118,
+                1,
+                2,
+                3,
+                VBEControlInfo::default(),
+                VBEModeInfo::default(),
+            ))
+            .framebuffer(FramebufferTag::new(
+                0xfd00_0000,
+                4096,
+                1024,
+                768,
+                32,
+                FramebufferType::Text,
+            ))
+            .elf_sections(ElfSectionsTag::new(2, 64, 0, &elf_sections))
+            .rsdpv2(RsdpV2Tag::new(*b"OEMIDX", 2, 0xe0000, 36, 0x1234_5678_9abc))
+            .efi_mmap(EFIMemoryMapTag::new_from_descs(&[EFIMemoryDesc {
+                ty: MemoryType::CONVENTIONAL,
+                phys_start: 0x4000,
+                page_count: 4,
+                ..Default::default()
+            }]))
+            .add_custom_tag(new_boxed::<Dst<TagHeader>>(
+                TagHeader::new(TagType::Custom(0x1337), 0),
+                &[&[1, 2, 3, 4]],
+            ))
+            .build();
+        let native = structure.as_bytes();
+        let native: &[u8] = native.as_ref();
+
+        let mut foreign = native.to_vec();
+        swap_tags(&mut foreign, ByteOrder::NATIVE);
+        assert_ne!(foreign, native);
+
+        for (bytes, byte_order) in [(&foreign[..], FOREIGN), (native, ByteOrder::NATIVE)] {
+            // Unaligned copy
+            let mut buffer = vec![0; bytes.len() + 1];
+            buffer[1..].copy_from_slice(bytes);
+            let owned = OwnedBootInformation::load(&buffer[1..], byte_order).unwrap();
+            let mbi = owned.boot_information();
+            assert_eq!(owned.0.as_bytes().as_ref(), native);
+            assert_eq!(mbi.command_line_tag().unwrap().cmdline(), Ok("cmdline"));
+            assert_eq!(
+                mbi.basic_memory_info_tag().unwrap().memory_upper(),
+                7 * 1024
+            );
+            let module = mbi.module_tags().next().unwrap();
+            assert_eq!(module.start_address(), 0x1000);
+            let area = mbi.memory_map_tag().unwrap().memory_areas()[0];
+            assert_eq!(area.start_address(), 0x100000);
+            assert_eq!(area.typ(), MemoryAreaType::Available);
+            assert_eq!(mbi.vbe_info_tag().unwrap().mode(), 0x118);
+            let framebuffer = mbi.framebuffer_tag().unwrap().unwrap();
+            assert_eq!(framebuffer.width(), 1024);
+            let section = mbi.elf_sections_tag().unwrap().sections().nth(1).unwrap();
+            assert_eq!(section.sh_addr, 0x1000);
+            assert_eq!(mbi.rsdp_v2_tag().unwrap().xsdt_address(), 0x1234_5678_9abc);
+            let desc = mbi
+                .efi_memory_map_tag()
+                .unwrap()
+                .memory_areas()
+                .next()
+                .unwrap();
+            assert_eq!(desc.phys_start, 0x4000);
+            assert_eq!(desc.page_count, 4);
+        }
+    }
+
+    #[test]
+    fn load_invalid() {
+        assert_eq!(
+            OwnedBootInformation::load(&[16, 0], ByteOrder::Little).unwrap_err(),
+            LoadError::Memory(MemoryError::ShorterThanHeader)
+        );
+        assert_eq!(
+            OwnedBootInformation::load(&[0, 0, 0, 16, 0, 0, 0, 0], ByteOrder::Big).unwrap_err(),
+            LoadError::Memory(MemoryError::InvalidReportedTotalSize(16, 8))
+        );
+        // Missing end tag
+        let dump = [16, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 8, 0, 0, 0];
+        assert_eq!(
+            OwnedBootInformation::load(&dump, ByteOrder::Little).unwrap_err(),
+            LoadError::NoEndTag
+        );
+    }
+}
@@ -31,6 +31,36 @@ fn compute_checksum(bytes: &[&[u8]]) -> u8 {
     0u8.wrapping_sub(sum_bytes(bytes))
 }
 
+/// OEM ID of a deserialized RSDP tag, which must be a string of exactly six
+/// bytes.
+#[cfg(feature = "serde")]
+struct OemId([u8; 6]);
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for OemId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = OemId;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_str("an OEM ID of six bytes")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, oem_id: &str) -> Result<OemId, E> {
+                oem_id
+                    .as_bytes()
+                    .try_into()
+                    .map(OemId)
+                    .map_err(|_| E::invalid_length(oem_id.len(), &self))
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
 /// This tag contains a copy of RSDP as defined per ACPI 1.0 specification.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C, align(8))]
@@ -124,6 +154,43 @@ impl Tag for RsdpV1Tag {
     const ID: TagType = TagType::AcpiV1;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for RsdpV1Tag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+        let mut state = serializer.serialize_struct("RsdpV1Tag", 6)?;
+        state.serialize_field("signature", self.signature().map_err(S::Error::custom)?)?;
+        state.serialize_field("checksum", &self.checksum)?;
+        state.serialize_field("checksum_valid", &self.checksum_is_valid())?;
+        state.serialize_field("oem_id", self.oem_id().map_err(S::Error::custom)?)?;
+        state.serialize_field("revision", &self.revision)?;
+        state.serialize_field("rsdt_address", &self.rsdt_address)?;
+        state.end()
+    }
+}
+
+/// Deserializes the fields of [`RsdpV1Tag::new`]. The signature and the
+/// checksum are computed.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RsdpV1Tag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "RsdpV1Tag")]
+        struct Fields {
+            oem_id: OemId,
+            revision: u8,
+            rsdt_address: u32,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(
+            fields.oem_id.0,
+            fields.revision,
+            fields.rsdt_address,
+        ))
+    }
+}
+
 /// This tag contains a copy of RSDP as defined per ACPI 2.0 or later specification.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C, align(8))]
@@ -233,6 +300,18 @@ impl RsdpV2Tag {
         self.revision
     }
 
+    /// The physical address of the RSDT table.
+    #[must_use]
+    pub const fn rsdt_address(&self) -> usize {
+        self.rsdt_address as usize
+    }
+
+    /// The length of the RSDP in bytes, including the extended fields.
+    #[must_use]
+    pub const fn length(&self) -> u32 {
+        self.length
+    }
+
     /// Physical address of the XSDT table.
     ///
     /// On x86, this is truncated from 64-bit to 32-bit.
@@ -260,6 +339,50 @@ impl Tag for RsdpV2Tag {
     const ID: TagType = TagType::AcpiV2;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for RsdpV2Tag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+        let mut state = serializer.serialize_struct("RsdpV2Tag", 9)?;
+        state.serialize_field("signature", self.signature().map_err(S::Error::custom)?)?;
+        state.serialize_field("checksum", &self.checksum)?;
+        state.serialize_field("checksum_valid", &self.checksum_is_valid())?;
+        state.serialize_field("oem_id", self.oem_id().map_err(S::Error::custom)?)?;
+        state.serialize_field("revision", &self.revision)?;
+        state.serialize_field("rsdt_address", &self.rsdt_address)?;
+        state.serialize_field("length", &self.length)?;
+        state.serialize_field("xsdt_address", &self.xsdt_address)?;
+        state.serialize_field("ext_checksum", &self.ext_checksum)?;
+        state.end()
+    }
+}
+
+/// Deserializes the fields of [`RsdpV2Tag::new`]. The signature and the
+/// checksums are computed.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RsdpV2Tag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "RsdpV2Tag")]
+        struct Fields {
+            oem_id: OemId,
+            revision: u8,
+            rsdt_address: u32,
+            length: u32,
+            xsdt_address: u64,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(
+            fields.oem_id.0,
+            fields.revision,
+            fields.rsdt_address,
+            fields.length,
+            fields.xsdt_address,
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,6 +397,8 @@ mod tests {
     fn v2_new_computes_valid_checksums() {
         let tag = RsdpV2Tag::new(*b"ABCDEF", 2, 0x1234_5678, 36, 0x1234_5678_9abc_def0);
         assert!(tag.checksum_is_valid());
+        assert_eq!(tag.rsdt_address(), 0x1234_5678);
+        assert_eq!(tag.length(), 36);
     }
 
     #[test]
@@ -5,7 +5,7 @@ use crate::tag::TagHeader;
 use core::fmt::Debug;
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
-use {alloc::boxed::Box, multiboot2_common::new_boxed};
+use {crate::NewTagError, alloc::boxed::Box, multiboot2_common::try_new_boxed};
 
 /// This tag contains a copy of SMBIOS tables as well as their version.
 #[derive(ptr_meta::Pointee, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -20,12 +20,25 @@ pub struct SmbiosTag {
 
 impl SmbiosTag {
     /// Constructs a new tag.
+    ///
+    /// # Panics
+    /// Panics if [`Self::try_new`] fails.
     #[cfg(feature = "builder")]
     #[must_use]
     pub fn new(major: u8, minor: u8, tables: &[u8]) -> Box<Self> {
+        Self::try_new(major, minor, tables).unwrap()
+    }
+
+    /// Fallible variant of [`Self::new`] that reports too large tables or a
+    /// failed allocation.
+    #[cfg(feature = "builder")]
+    pub fn try_new(major: u8, minor: u8, tables: &[u8]) -> Result<Box<Self>, NewTagError> {
         let header = TagHeader::new(Self::ID, 0);
         let reserved = [0, 0, 0, 0, 0, 0];
-        new_boxed(header, &[&[major, minor], &reserved, tables])
+        Ok(try_new_boxed(
+            header,
+            &[&[major, minor], &reserved, tables],
+        )?)
     }
 
     /// Returns the major number.
@@ -64,6 +77,35 @@ impl Tag for SmbiosTag {
     const ID: TagType = TagType::Smbios;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for SmbiosTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("SmbiosTag", 3)?;
+        state.serialize_field("major", &self.major)?;
+        state.serialize_field("minor", &self.minor)?;
+        state.serialize_field("tables", &self.tables)?;
+        state.end()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "builder"))]
+impl<'de> serde::Deserialize<'de> for Box<SmbiosTag> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        use serde::de::Error;
+        #[derive(Deserialize)]
+        #[serde(rename = "SmbiosTag")]
+        struct Fields {
+            major: u8,
+            minor: u8,
+            tables: alloc::vec::Vec<u8>,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        SmbiosTag::try_new(fields.major, fields.minor, &fields.tables).map_err(D::Error::custom)
+    }
+}
+
 impl Debug for SmbiosTag {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("SmbiosTag")
@@ -0,0 +1,445 @@
+//! Module for the human-readable summary of a [`BootInformation`].
+
+use crate::{
+    BootInformation, EFIMemoryAreaType, EFIMemoryDesc, EFIMemoryMapTag, ElfSectionExt,
+    ElfSectionFlags, ElfSectionsTag, FramebufferTag, FramebufferType, MemoryArea, MemoryAreaType,
+    MemoryMapTag, ModuleIter, UnknownFramebufferType,
+};
+use core::fmt::{self, Display, Formatter, Write};
+
+/// Size of a page in the EFI memory map.
+const EFI_PAGE_SIZE: u64 = 4096;
+
+/// Names of the EFI memory types specified by UEFI, indexed by their value.
+const EFI_MEMORY_TYPE_NAMES: [&str; 16] = [
+    "RESERVED",
+    "LOADER_CODE",
+    "LOADER_DATA",
+    "BOOT_SERVICES_CODE",
+    "BOOT_SERVICES_DATA",
+    "RUNTIME_SERVICES_CODE",
+    "RUNTIME_SERVICES_DATA",
+    "CONVENTIONAL",
+    "UNUSABLE",
+    "ACPI_RECLAIM",
+    "ACPI_NON_VOLATILE",
+    "MMIO",
+    "MMIO_PORT_SPACE",
+    "PAL_CODE",
+    "PERSISTENT_MEMORY",
+    "UNACCEPTED",
+];
+
+/// Compact, human-readable report of a [`BootInformation`]. See
+/// [`BootInformation::summary`].
+pub struct Summary<'a, 'b> {
+    pub info: &'b BootInformation<'a>,
+    /// String table to resolve ELF section names with.
+    pub string_table: Option<&'b [u8]>,
+}
+
+impl Display for Summary<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let info = self.info;
+        writeln!(f, "total size: {} bytes", info.total_size())?;
+        if let Some(tag) = info.command_line_tag() {
+            writeln!(f, "command line: {}", Str(tag.cmdline()))?;
+        }
+        if let Some(tag) = info.boot_loader_name_tag() {
+            writeln!(f, "boot loader: {}", Str(tag.name()))?;
+        }
+        if let Some(tag) = info.basic_memory_info_tag() {
+            writeln!(
+                f,
+                "basic memory: lower={} KiB upper={} KiB",
+                tag.memory_lower(),
+                tag.memory_upper()
+            )?;
+        }
+        if let Some(tag) = info.memory_map_tag() {
+            memory_map(f, tag)?;
+        }
+        modules(f, info.module_tags())?;
+        if let Some(tag) = info.framebuffer_tag() {
+            framebuffer(f, tag)?;
+        }
+        match (info.rsdp_v2_tag(), info.rsdp_v1_tag()) {
+            (Some(tag), _) => acpi(f, "v2", tag.revision(), tag.oem_id())?,
+            (None, Some(tag)) => acpi(f, "v1", tag.revision(), tag.oem_id())?,
+            (None, None) => {}
+        }
+        if let Some(tag) = info.elf_sections_tag() {
+            elf_sections(f, tag, self.string_table)?;
+        }
+        if let Some(tag) = info.efi_memory_map_tag() {
+            efi_memory_map(f, tag)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a string of a tag in quotes, or the error if it is invalid.
+struct Str<'a, E>(Result<&'a str, E>);
+
+impl<E: Display> Display for Str<'_, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Ok(str) => write!(f, "{str:?}"),
+            Err(e) => write!(f, "<{e}>"),
+        }
+    }
+}
+
+/// Formats a size in bytes as MiB with two (truncated) decimal places.
+struct Mib(u64);
+
+impl Display for Mib {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        const MIB: u64 = 1024 * 1024;
+        let fraction = (self.0 % MIB) * 100 / MIB;
+        write!(f, "{:>8}.{fraction:02} MiB", self.0 / MIB)
+    }
+}
+
+fn memory_map(f: &mut Formatter<'_>, tag: &MemoryMapTag) -> fmt::Result {
+    // `memory_areas` panics for other entry sizes.
+    if tag.entry_size() as usize != size_of::<MemoryArea>() {
+        return writeln!(
+            f,
+            "memory map: <unsupported entry size {}>",
+            tag.entry_size()
+        );
+    }
+    writeln!(f, "memory map:")?;
+    for area in tag.memory_areas() {
+        writeln!(
+            f,
+            "  {:#018x}-{:#018x} {}  {:?}",
+            area.start_address(),
+            area.end_address(),
+            Mib(area.size()),
+            MemoryAreaType::from(area.typ())
+        )?;
+    }
+    Ok(())
+}
+
+fn modules(f: &mut Formatter<'_>, mut modules: ModuleIter) -> fmt::Result {
+    let Some(first) = modules.next() else {
+        return Ok(());
+    };
+    writeln!(f, "modules:")?;
+    for module in core::iter::once(first).chain(modules) {
+        writeln!(
+            f,
+            "  {:#018x}-{:#018x} {}  {}",
+            module.start_address(),
+            module.end_address(),
+            Mib(u64::from(module.module_size())),
+            Str(module.cmdline())
+        )?;
+    }
+    Ok(())
+}
+
+fn framebuffer(
+    f: &mut Formatter<'_>,
+    tag: Result<&FramebufferTag, UnknownFramebufferType>,
+) -> fmt::Result {
+    let tag = match tag {
+        Ok(tag) => tag,
+        Err(e) => return writeln!(f, "framebuffer: <{e}>"),
+    };
+    write!(
+        f,
+        "framebuffer: {}x{}x{} ",
+        tag.width(),
+        tag.height(),
+        tag.bpp()
+    )?;
+    match tag.buffer_type() {
+        Ok(FramebufferType::Indexed { palette }) => {
+            write!(f, "indexed ({} colors)", palette.len())?
+        }
+        Ok(FramebufferType::RGB { .. }) => f.write_str("rgb")?,
+        Ok(FramebufferType::Text) => f.write_str("text")?,
+        Err(e) => write!(f, "<{e}>")?,
+    }
+    writeln!(f, " pitch={} address={:#x}", tag.pitch(), tag.address())
+}
+
+fn acpi(
+    f: &mut Formatter<'_>,
+    rsdp: &str,
+    revision: u8,
+    oem_id: Result<&str, core::str::Utf8Error>,
+) -> fmt::Result {
+    writeln!(
+        f,
+        "acpi: rsdp={rsdp} revision={revision} oem={}",
+        Str(oem_id)
+    )
+}
+
+fn elf_sections(
+    f: &mut Formatter<'_>,
+    tag: &ElfSectionsTag,
+    string_table: Option<&[u8]>,
+) -> fmt::Result {
+    // `sections` panics for other entry sizes.
+    if !matches!(tag.entry_size(), 40 | 64) {
+        return writeln!(f, "elf sections: <unknown entry size {}>", tag.entry_size());
+    }
+    writeln!(f, "elf sections:")?;
+    for section in tag.sections() {
+        match string_table {
+            Some(table) => {
+                let name = Some(table)
+                    .filter(|table| (section.sh_name as usize) < table.len())
+                    .and_then(|table| section.name_from_string_table(table).ok())
+                    .and_then(|name| name.to_str().ok())
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or("?");
+                write!(f, "  {name:<24}")?;
+            }
+            // Offset of the name in the string table
+            None => write!(f, "  {:<#24x}", section.sh_name)?,
+        }
+        writeln!(
+            f,
+            " {:#018x} {:#018x} {}",
+            section.sh_addr,
+            section.sh_size,
+            Flags(section.flags())
+        )?;
+    }
+    Ok(())
+}
+
+/// Formats the most relevant ELF section flags like `readelf`, e.g., `WAX`.
+struct Flags(ElfSectionFlags);
+
+impl Display for Flags {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (flag, c) in [
+            (ElfSectionFlags::WRITABLE, 'W'),
+            (ElfSectionFlags::ALLOCATED, 'A'),
+            (ElfSectionFlags::EXECUTABLE, 'X'),
+        ] {
+            f.write_char(if self.0.contains(flag) { c } else { '-' })?;
+        }
+        Ok(())
+    }
+}
+
+fn efi_memory_map(f: &mut Formatter<'_>, tag: &EFIMemoryMapTag) -> fmt::Result {
+    // `memory_areas` panics for other descriptor versions and sizes.
+    if tag.desc_version() != EFIMemoryDesc::VERSION {
+        return writeln!(
+            f,
+            "efi memory map: <unsupported descriptor version {}>",
+            tag.desc_version()
+        );
+    }
+    let desc_size = tag.desc_size() as usize;
+    if desc_size < size_of::<EFIMemoryDesc>() || desc_size % align_of::<EFIMemoryDesc>() != 0 {
+        return writeln!(
+            f,
+            "efi memory map: <unsupported descriptor size {desc_size}>"
+        );
+    }
+    writeln!(f, "efi memory map:")?;
+    // Group the descriptors by type, in the order in which each type first
+    // occurs, without allocating.
+    for (i, desc) in tag.memory_areas().enumerate() {
+        if tag.memory_areas().take(i).any(|other| other.ty == desc.ty) {
+            continue;
+        }
+        let (count, pages) = tag
+            .memory_areas()
+            .filter(|other| other.ty == desc.ty)
+            .fold((0, 0_u64), |(count, pages), other| {
+                (count + 1, pages.saturating_add(other.page_count))
+            });
+        writeln!(
+            f,
+            "  {count:>6} areas {pages:>10} pages {}  {}",
+            Mib(pages.saturating_mul(EFI_PAGE_SIZE)),
+            EfiMemoryType(desc.ty)
+        )?;
+    }
+    Ok(())
+}
+
+/// Formats the name of an [`EFIMemoryAreaType`].
+struct EfiMemoryType(EFIMemoryAreaType);
+
+impl Display for EfiMemoryType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match EFI_MEMORY_TYPE_NAMES.get(self.0.0 as usize) {
+            Some(name) => f.write_str(name),
+            None => write!(f, "{:#x}", self.0.0),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use crate::{
+        BasicMemoryInfoTag, BootInformation, BootLoaderNameTag, Builder, CommandLineTag,
+        EFIMemoryAreaType, EFIMemoryDesc, EFIMemoryMapTag, ElfSectionsTag, FramebufferField,
+        FramebufferTag, FramebufferType, MemoryArea, MemoryAreaType, MemoryMapTag, ModuleTag,
+        RsdpV2Tag, TagHeader, TagType,
+    };
+    use alloc::format;
+    use alloc::vec::Vec;
+    use multiboot2_common::{MaybeDynSized, new_boxed};
+
+    /// String table of the ELF sections.
+    const STRING_TABLE: [u8; 17] = *b"\0.text\0.shstrtab\0";
+
+    /// Returns an ELF64 section header.
+    fn section(name: u32, typ: u32, flags: u64, addr: u64, size: u64) -> [u8; 64] {
+        let mut bytes = [0; 64];
+        bytes[0..4].copy_from_slice(&name.to_ne_bytes());
+        bytes[4..8].copy_from_slice(&typ.to_ne_bytes());
+        bytes[8..16].copy_from_slice(&flags.to_ne_bytes());
+        bytes[16..24].copy_from_slice(&addr.to_ne_bytes());
+        bytes[32..40].copy_from_slice(&size.to_ne_bytes());
+        bytes
+    }
+
+    fn efi_desc(ty: EFIMemoryAreaType, phys_start: u64, page_count: u64) -> EFIMemoryDesc {
+        EFIMemoryDesc {
+            ty,
+            phys_start,
+            page_count,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn summary() {
+        let sections = [
+            section(0, 0, 0, 0, 0),
+            section(1, 1, 0b110, 0x10_0000, 0x1800),
+            section(7, 3, 0, 0x10_2000, STRING_TABLE.len() as u64),
+        ]
+        .concat();
+        let structure = Builder::new()
+            .cmdline(CommandLineTag::new("console=ttyS0"))
+            .bootloader(BootLoaderNameTag::new("GRUB 2.12"))
+            .add_module(ModuleTag::new(0x20_0000, 0x38_0000, "initrd"))
+            .add_module(ModuleTag::new(0x40_0000, 0x40_1000, ""))
+            .meminfo(BasicMemoryInfoTag::new(639, 130_048))
+            .mmap(MemoryMapTag::new(&[
+                MemoryArea::new(0, 0x9_fc00, MemoryAreaType::Available),
+                MemoryArea::new(0x10_0000, 0x7ee_0000, MemoryAreaType::Available),
+                MemoryArea::new(0xfffc_0000, 0x4_0000, MemoryAreaType::Reserved),
+            ]))
+            .framebuffer(FramebufferTag::new(
+                0xfd00_0000,
+                4096,
+                1024,
+                768,
+                32,
+                FramebufferType::RGB {
+                    red: FramebufferField {
+                        position: 16,
+                        size: 8,
+                    },
+                    green: FramebufferField {
+                        position: 8,
+                        size: 8,
+                    },
+                    blue: FramebufferField {
+                        position: 0,
+                        size: 8,
+                    },
+                },
+            ))
+            .elf_sections(ElfSectionsTag::new(3, 64, 2, &sections))
+            .rsdpv2(RsdpV2Tag::new(*b"BOCHS ", 2, 0x7fe_1234, 36, 0x7fe_2000))
+            .efi_mmap(EFIMemoryMapTag::new_from_descs(&[
+                efi_desc(EFIMemoryAreaType::CONVENTIONAL, 0, 0x9f),
+                efi_desc(EFIMemoryAreaType::BOOT_SERVICES_DATA, 0x10_0000, 0x100),
+                efi_desc(EFIMemoryAreaType::CONVENTIONAL, 0x20_0000, 0x7000),
+                efi_desc(EFIMemoryAreaType(0x7000_0000), 0xfffc_0000, 0x40),
+            ]))
+            .build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+
+        let summary = format!("{}", info.summary());
+        let expected = [
+            format!("total size: {} bytes", info.total_size()),
+            "command line: \"console=ttyS0\"".into(),
+            "boot loader: \"GRUB 2.12\"".into(),
+            "basic memory: lower=639 KiB upper=130048 KiB".into(),
+            "memory map:".into(),
+            "  0x0000000000000000-0x000000000009fc00        0.62 MiB  Available".into(),
+            "  0x0000000000100000-0x0000000007fe0000      126.87 MiB  Available".into(),
+            "  0x00000000fffc0000-0x0000000100000000        0.25 MiB  Reserved".into(),
+            "modules:".into(),
+            "  0x0000000000200000-0x0000000000380000        1.50 MiB  \"initrd\"".into(),
+            "  0x0000000000400000-0x0000000000401000        0.00 MiB  \"\"".into(),
+            "framebuffer: 1024x768x32 rgb pitch=4096 address=0xfd000000".into(),
+            "acpi: rsdp=v2 revision=2 oem=\"BOCHS \"".into(),
+            "elf sections:".into(),
+            "  0x0                      0x0000000000000000 0x0000000000000000 ---".into(),
+            "  0x1                      0x0000000000100000 0x0000000000001800 -AX".into(),
+            "  0x7                      0x0000000000102000 0x0000000000000011 ---".into(),
+            "efi memory map:".into(),
+            "       2 areas      28831 pages      112.62 MiB  CONVENTIONAL".into(),
+            "       1 areas        256 pages        1.00 MiB  BOOT_SERVICES_DATA".into(),
+            "       1 areas         64 pages        0.25 MiB  0x70000000".into(),
+        ];
+        assert_eq!(summary.lines().collect::<Vec<_>>(), expected, "{summary}");
+
+        let summary = format!("{}", info.summary_with_string_table(Some(&STRING_TABLE)));
+        assert!(summary.contains(
+            "elf sections:\n  ?                        0x0000000000000000 0x0000000000000000 ---\n  \
+             .text                    0x0000000000100000 0x0000000000001800 -AX\n  \
+             .shstrtab                0x0000000000102000 0x0000000000000011 ---\n"
+        ), "{summary}");
+    }
+
+    #[test]
+    fn summary_unknown_elf_entry_size() {
+        // Memory map with 16-byte entries
+        let mmap = new_boxed::<MemoryMapTag>(
+            TagHeader::new(TagType::Mmap, 0),
+            &[&16_u32.to_ne_bytes(), &0_u32.to_ne_bytes(), &[0; 48]],
+        );
+        let structure = Builder::new()
+            .mmap(mmap)
+            .elf_sections(ElfSectionsTag::new(1, 48, 0, &[0; 48]))
+            .efi_mmap(EFIMemoryMapTag::new_from_map(48, 2, &[0; 48]))
+            .build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+        let summary = format!("{}", info.summary());
+        assert!(
+            summary.ends_with(
+                "memory map: <unsupported entry size 16>\n\
+                 elf sections: <unknown entry size 48>\n\
+                 efi memory map: <unsupported descriptor version 2>\n"
+            ),
+            "{summary}"
+        );
+
+        let structure = Builder::new()
+            .efi_mmap(EFIMemoryMapTag::new_from_map(20, 1, &[0; 40]))
+            .build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+        let summary = format!("{}", info.summary());
+        assert!(
+            summary.ends_with("efi memory map: <unsupported descriptor size 20>\n"),
+            "{summary}"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Module for [`TagRef`].
+
+use crate::framebuffer::UnknownFramebufferType;
+use crate::{
+    ApmTag, BasicMemoryInfoTag, BootLoaderNameTag, BootdevTag, CommandLineTag,
+    EFIBootServicesNotExitedTag, EFIImageHandle32Tag, EFIImageHandle64Tag, EFIMemoryMapTag,
+    EFISdt32Tag, EFISdt64Tag, ElfSectionsTag, EndTag, FramebufferTag, ImageLoadPhysAddrTag,
+    MemoryMapTag, ModuleTag, NetworkTag, RsdpV1Tag, RsdpV2Tag, SmbiosTag, TagHeader, TagType,
+    VBEInfoTag,
+};
+use multiboot2_common::DynSizedStructure;
+
+/// A reference to a boot information tag, typed by its [`TagType`].
+///
+/// There is one variant per tag type specified by Multiboot2, named like the
+/// corresponding [`TagType`] variant. This enables exhaustive matching over
+/// all tags, e.g., for logging or conversions. Use
+/// [`BootInformation::typed_tags`] to iterate the tags of a boot information.
+///
+/// [`BootInformation::typed_tags`]: crate::BootInformation::typed_tags
+#[derive(Copy, Clone, Debug)]
+pub enum TagRef<'a> {
+    /// The [`EndTag`].
+    End(&'a EndTag),
+    /// The [`CommandLineTag`].
+    Cmdline(&'a CommandLineTag),
+    /// The [`BootLoaderNameTag`].
+    BootLoaderName(&'a BootLoaderNameTag),
+    /// A [`ModuleTag`].
+    Module(&'a ModuleTag),
+    /// The [`BasicMemoryInfoTag`].
+    BasicMeminfo(&'a BasicMemoryInfoTag),
+    /// The [`BootdevTag`].
+    Bootdev(&'a BootdevTag),
+    /// The [`MemoryMapTag`].
+    Mmap(&'a MemoryMapTag),
+    /// The [`VBEInfoTag`].
+    Vbe(&'a VBEInfoTag),
+    /// The [`FramebufferTag`] or an error if its framebuffer type is unknown.
+    Framebuffer(Result<&'a FramebufferTag, UnknownFramebufferType>),
+    /// The [`ElfSectionsTag`].
+    ElfSections(&'a ElfSectionsTag),
+    /// The [`ApmTag`].
+    Apm(&'a ApmTag),
+    /// The [`EFISdt32Tag`].
+    Efi32(&'a EFISdt32Tag),
+    /// The [`EFISdt64Tag`].
+    Efi64(&'a EFISdt64Tag),
+    /// A [`SmbiosTag`].
+    Smbios(&'a SmbiosTag),
+    /// The [`RsdpV1Tag`].
+    AcpiV1(&'a RsdpV1Tag),
+    /// The [`RsdpV2Tag`].
+    AcpiV2(&'a RsdpV2Tag),
+    /// A [`NetworkTag`].
+    Network(&'a NetworkTag),
+    /// The [`EFIMemoryMapTag`].
+    EfiMmap(&'a EFIMemoryMapTag),
+    /// The [`EFIBootServicesNotExitedTag`].
+    EfiBs(&'a EFIBootServicesNotExitedTag),
+    /// The [`EFIImageHandle32Tag`].
+    Efi32Ih(&'a EFIImageHandle32Tag),
+    /// The [`EFIImageHandle64Tag`].
+    Efi64Ih(&'a EFIImageHandle64Tag),
+    /// The [`ImageLoadPhysAddrTag`].
+    LoadBaseAddr(&'a ImageLoadPhysAddrTag),
+    /// A tag of type [`TagType::Custom`]. Use [`DynSizedStructure::cast`] to
+    /// cast it to a custom tag type.
+    Unknown(&'a DynSizedStructure<TagHeader>),
+}
+
+impl TagRef<'_> {
+    /// Returns the [`TagType`] of the tag.
+    #[must_use]
+    pub fn typ(&self) -> TagType {
+        match self {
+            Self::End(_) => TagType::End,
+            Self::Cmdline(_) => TagType::Cmdline,
+            Self::BootLoaderName(_) => TagType::BootLoaderName,
+            Self::Module(_) => TagType::Module,
+            Self::BasicMeminfo(_) => TagType::BasicMeminfo,
+            Self::Bootdev(_) => TagType::Bootdev,
+            Self::Mmap(_) => TagType::Mmap,
+            Self::Vbe(_) => TagType::Vbe,
+            Self::Framebuffer(_) => TagType::Framebuffer,
+            Self::ElfSections(_) => TagType::ElfSections,
+            Self::Apm(_) => TagType::Apm,
+            Self::Efi32(_) => TagType::Efi32,
+            Self::Efi64(_) => TagType::Efi64,
+            Self::Smbios(_) => TagType::Smbios,
+            Self::AcpiV1(_) => TagType::AcpiV1,
+            Self::AcpiV2(_) => TagType::AcpiV2,
+            Self::Network(_) => TagType::Network,
+            Self::EfiMmap(_) => TagType::EfiMmap,
+            Self::EfiBs(_) => TagType::EfiBs,
+            Self::Efi32Ih(_) => TagType::Efi32Ih,
+            Self::Efi64Ih(_) => TagType::Efi64Ih,
+            Self::LoadBaseAddr(_) => TagType::LoadBaseAddr,
+            Self::Unknown(tag) => tag.header().typ.into(),
+        }
+    }
+}
+
+impl<'a> From<&'a DynSizedStructure<TagHeader>> for TagRef<'a> {
+    fn from(tag: &'a DynSizedStructure<TagHeader>) -> Self {
+        match tag.header().typ.into() {
+            TagType::End => Self::End(tag.cast()),
+            TagType::Cmdline => Self::Cmdline(tag.cast()),
+            TagType::BootLoaderName => Self::BootLoaderName(tag.cast()),
+            TagType::Module => Self::Module(tag.cast()),
+            TagType::BasicMeminfo => Self::BasicMeminfo(tag.cast()),
+            TagType::Bootdev => Self::Bootdev(tag.cast()),
+            TagType::Mmap => Self::Mmap(tag.cast()),
+            TagType::Vbe => Self::Vbe(tag.cast()),
+            TagType::Framebuffer => {
+                let tag = tag.cast::<FramebufferTag>();
+                Self::Framebuffer(tag.buffer_type().map(|_| tag))
+            }
+            TagType::ElfSections => Self::ElfSections(tag.cast()),
+            TagType::Apm => Self::Apm(tag.cast()),
+            TagType::Efi32 => Self::Efi32(tag.cast()),
+            TagType::Efi64 => Self::Efi64(tag.cast()),
+            TagType::Smbios => Self::Smbios(tag.cast()),
+            TagType::AcpiV1 => Self::AcpiV1(tag.cast()),
+            TagType::AcpiV2 => Self::AcpiV2(tag.cast()),
+            TagType::Network => Self::Network(tag.cast()),
+            TagType::EfiMmap => Self::EfiMmap(tag.cast()),
+            TagType::EfiBs => Self::EfiBs(tag.cast()),
+            TagType::Efi32Ih => Self::Efi32Ih(tag.cast()),
+            TagType::Efi64Ih => Self::Efi64Ih(tag.cast()),
+            TagType::LoadBaseAddr => Self::LoadBaseAddr(tag.cast()),
+            TagType::Custom(_) => Self::Unknown(tag),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{BootInformation, Builder, FramebufferType, MemoryArea, MemoryAreaType};
+    use alloc::vec::Vec;
+    use multiboot2_common::{MaybeDynSized, new_boxed};
+
+    #[test]
+    fn typed_tags() {
+        let structure = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_module(ModuleTag::new(0x1000, 0x2000, "module"))
+            .mmap(MemoryMapTag::new(&[MemoryArea::new(
+                0x1000,
+                0x1000,
+                MemoryAreaType::Available,
+            )]))
+            .framebuffer(FramebufferTag::new(
+                0x1000,
+                1,
+                1024,
+                768,
+                8,
+                FramebufferType::Text,
+            ))
+            .add_custom_tag(new_boxed::<DynSizedStructure<TagHeader>>(
+                TagHeader::new(TagType::Custom(0x1337), 0),
+                &[&[1, 2, 3]],
+            ))
+            .build();
+        // SAFETY: The builder constructs a complete, aligned MBI with
+        // a valid end tag.
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+
+        let tags = info.typed_tags().collect::<Vec<_>>();
+        assert_eq!(
+            tags.iter().map(TagRef::typ).collect::<Vec<_>>(),
+            info.tags()
+                .map(|tag| TagType::from(tag.header().typ))
+                .collect::<Vec<_>>()
+        );
+        let mut custom = 0;
+        for tag in &tags {
+            match tag {
+                TagRef::Cmdline(tag) => assert_eq!(tag.cmdline(), Ok("cmdline")),
+                TagRef::Module(tag) => assert_eq!(tag.start_address(), 0x1000),
+                TagRef::Mmap(tag) => assert_eq!(tag.memory_areas().len(), 1),
+                TagRef::Framebuffer(tag) => assert_eq!(tag.unwrap().width(), 1024),
+                TagRef::Unknown(tag) => {
+                    assert_eq!(tag.payload(), [1, 2, 3]);
+                    custom += 1;
+                }
+                TagRef::End(_) => {}
+                tag => panic!("unexpected tag {tag:?}"),
+            }
+        }
+        assert_eq!(custom, 1);
+        assert!(matches!(tags.last(), Some(TagRef::End(_))));
+    }
+}
@@ -1,6 +1,8 @@
 //! Various utilities.
 
 use core::str::Utf8Error;
+#[cfg(feature = "builder")]
+use multiboot2_common::NewBoxedError;
 use thiserror::Error;
 
 /// Error type describing failures when parsing the string from a tag.
@@ -15,6 +17,30 @@ pub enum StringError {
     Utf8(#[source] Utf8Error),
 }
 
+/// Errors that may occur when creating a tag with one of the fallible
+/// `try_new*` constructors.
+#[cfg(feature = "builder")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+pub enum NewTagError {
+    /// The end address of a module is not above its start address.
+    #[error("module end 0x{end:x} is not above its start 0x{start:x}")]
+    EmptyModule {
+        /// The start address of the module.
+        start: u32,
+        /// The end address of the module.
+        end: u32,
+    },
+    /// The size of the EFI memory descriptors is zero.
+    #[error("EFI memory descriptor size must not be zero")]
+    ZeroDescriptorSize,
+    /// The palette of an indexed framebuffer has more than `u16::MAX` colors.
+    #[error("framebuffer palette with {0} colors is too large")]
+    PaletteTooLarge(usize),
+    /// The tag couldn't be created on the heap.
+    #[error(transparent)]
+    Boxed(#[from] NewBoxedError),
+}
+
 /// Parses the provided byte sequence as Multiboot string, which maps to a
 /// [`str`].
 pub fn parse_slice_as_string(bytes: &[u8]) -> Result<&str, StringError> {
@@ -94,6 +94,53 @@ impl Tag for VBEInfoTag {
     const ID: TagType = TagType::Vbe;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for VBEInfoTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("VBEInfoTag", 6)?;
+        state.serialize_field("mode", &self.mode)?;
+        state.serialize_field("interface_segment", &self.interface_segment)?;
+        state.serialize_field("interface_offset", &self.interface_offset)?;
+        state.serialize_field("interface_length", &self.interface_length)?;
+        state.serialize_field("control_info", &self.control_info)?;
+        state.serialize_field("mode_info", &self.mode_info)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for VBEInfoTag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        #[serde(rename = "VBEInfoTag")]
+        struct Fields {
+            mode: u16,
+            interface_segment: u16,
+            interface_offset: u16,
+            interface_length: u16,
+            control_info: VBEControlInfo,
+            mode_info: VBEModeInfo,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self::new(
+            fields.mode,
+            fields.interface_segment,
+            fields.interface_offset,
+            fields.interface_length,
+            fields.control_info,
+            fields.mode_info,
+        ))
+    }
+}
+
+/// Default of the reserved fields that aren't serialized.
+#[cfg(feature = "serde")]
+const fn zeroed<const N: usize>() -> [u8; N] {
+    [0; N]
+}
+
 /// VBE controller information.
 ///
 /// The capabilities of the display controller, the revision level of the
@@ -103,6 +150,7 @@ impl Tag for VBEInfoTag {
 /// The purpose of this struct is to provide information to the kernel about the general
 /// capabilities of the installed VBE software and hardware.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C, packed)]
 pub struct VBEControlInfo {
     /// VBE Signature aka "VESA".
@@ -136,9 +184,11 @@ pub struct VBEControlInfo {
     pub oem_product_revision_ptr: u32,
 
     /// Reserved for VBE implementation scratch area.
+    #[cfg_attr(feature = "serde", serde(skip, default = "zeroed"))]
     reserved: [u8; 222],
 
     /// Data area for OEM strings.
+    #[cfg_attr(feature = "serde", serde(skip, default = "zeroed"))]
     oem_data: [u8; 256],
 }
 
@@ -183,6 +233,7 @@ impl Default for VBEControlInfo {
 /// Extended information about a specific VBE display mode from the
 /// mode list returned by `VBEControlInfo` (VBE Function `00h`).
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C, packed)]
 pub struct VBEModeInfo {
     /// Mode attributes.
@@ -237,6 +288,7 @@ pub struct VBEModeInfo {
     pub number_of_image_pages: u8,
 
     /// Reserved for page function.
+    #[cfg_attr(feature = "serde", serde(skip))]
     reserved0: u8,
 
     /// Red colour field.
@@ -272,6 +324,7 @@ pub struct VBEModeInfo {
     pub offscreen_memory_size: u16,
 
     /// Remainder of mode info block
+    #[cfg_attr(feature = "serde", serde(skip, default = "zeroed"))]
     reserved1: [u8; 206],
 }
 
@@ -345,6 +398,7 @@ impl Default for VBEModeInfo {
 ///
 /// Describes the size and position of some colour capability.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C, packed)]
 pub struct VBEField {
     /// The size, in bits, of the color components of a direct color pixel.
@@ -358,6 +412,7 @@ pub struct VBEField {
 bitflags! {
     /// The Capabilities field indicates the support of specific features in the graphics environment.
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
     #[repr(transparent)]
     pub struct VBECapabilities: u32 {
         /// Can the DAC be switched between 6 and 8 bit modes.
@@ -376,6 +431,7 @@ bitflags! {
 bitflags! {
     /// A Mode attributes bitfield.
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
     #[repr(transparent)]
     pub struct VBEModeAttributes: u16 {
         /// Mode supported by hardware configuration.
@@ -409,6 +465,7 @@ bitflags! {
     /// The WindowAttributes describe the characteristics of the CPU windowing
     /// scheme such as whether the windows exist and are read/writeable, as follows:
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
     #[repr(transparent)]
     pub struct VBEWindowAttributes: u8 {
         /// Relocatable window(s) supported?
@@ -433,6 +490,7 @@ bitflags! {
     /// Bit D1 specifies whether the bits in the Rsvd field of the direct color
     /// pixel can be used by the application or are reserved, and thus unusable.
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
     #[repr(transparent)]
     pub struct VBEDirectColorAttributes: u8 {
         /// Color ramp is fixed when cleared and programmable when set.
@@ -445,6 +503,7 @@ bitflags! {
 
 /// The MemoryModel field specifies the general type of memory organization used in modes.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 #[expect(missing_docs)]
 pub enum VBEMemoryModel {